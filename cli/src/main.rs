@@ -1,8 +1,14 @@
 // saccade/cli/src/main.rs
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use saccade_core::config::{Config, GitMode};
+use saccade_core::module_graph::Granularity;
+use saccade_core::parser::SkeletonLevel;
+use saccade_core::pii::{self, PiiMode};
+use saccade_core::registry::LanguageDir;
+use saccade_core::request::{RequestFile, RequestRange, RequestTarget};
+use saccade_core::safety::SafetyMode;
 use saccade_core::SaccadePack;
 use std::path::PathBuf;
 
@@ -16,6 +22,9 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[command(version = VERSION)]
 #[command(about = "Generate staged, token-efficient context packs for LLMs", long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Output directory for the AI pack
     #[arg(short, long, default_value = "ai-pack")]
     out: PathBuf,
@@ -44,6 +53,66 @@ struct Cli {
     #[arg(long)]
     code_only: bool,
 
+    /// Extra dir with languages.json / queries/<lang>/skeleton.scm (repeatable; overrides defaults)
+    #[arg(long, value_name = "DIR")]
+    lang_dir: Vec<PathBuf>,
+
+    /// Stage-2 fidelity: outline | signatures | types | full
+    #[arg(long, value_name = "LEVEL", default_value = "full")]
+    skeleton_level: SkeletonLevel,
+
+    /// Cap Stage-2 at ~N tokens, keeping the most central files (import-graph rank)
+    #[arg(long, value_name = "TOKENS")]
+    stage2_budget: Option<usize>,
+
+    /// GRAPH section module unit: dir | package
+    #[arg(long, value_name = "UNIT", default_value = "dir")]
+    graph_by: Granularity,
+
+    /// Layer order for GRAPH, top first (comma-separated path prefixes); lower layers must not import higher ones
+    #[arg(long, value_name = "LAYERS")]
+    layers: Option<String>,
+
+    /// Describe the task; the most relevant files get a FOCUS section at the top of PACK.txt
+    #[arg(long, value_name = "QUERY")]
+    focus: Option<String>,
+
+    /// Number of files listed in FOCUS
+    #[arg(long, value_name = "N", default_value = "10")]
+    focus_top: usize,
+
+    /// Never run cargo/npm/pip/go; read DEPS from lockfiles and manifests only
+    #[arg(long, visible_alias = "no-external-tools")]
+    offline: bool,
+
+    /// Per-tool time limit for DEPS tools, in seconds
+    #[arg(long, value_name = "SECS", default_value = "30")]
+    tool_timeout: u64,
+
+    /// Minimum manifest density (unique build keywords per line) for a file to be scored as a manifest
+    #[arg(long, value_name = "SCORE", default_value_t = saccade_core::config::MDS_CANDIDACY_THRESHOLD)]
+    manifest_mds: f32,
+
+    /// Minimum final confidence for a non-standard manifest to be listed in DEPS
+    #[arg(long, value_name = "SCORE", default_value_t = saccade_core::config::FINAL_CONFIDENCE_THRESHOLD)]
+    manifest_confidence: f32,
+
+    /// Keep secrets found in file content instead of replacing them with <redacted:KIND>
+    #[arg(long)]
+    no_redact: bool,
+
+    /// Prompt-injection phrases and invisible characters: flag | neutralize | strip (always listed in SAFETY)
+    #[arg(long, value_name = "MODE", default_value = "flag")]
+    injection_mode: SafetyMode,
+
+    /// PII (emails, phones, IPs, internal hosts, home paths): off | redact | pseudonymize
+    #[arg(long, value_name = "MODE", default_value = "pseudonymize")]
+    pii: PiiMode,
+
+    /// Extra PII rule as NAME=REGEX (repeatable); if it has capture groups, the first that matched is what gets replaced
+    #[arg(long, value_name = "NAME=REGEX")]
+    pii_rule: Vec<String>,
+
     /// Show stats and what would be generated, then exit
     #[arg(long)]
     dry_run: bool,
@@ -53,6 +122,34 @@ struct Cli {
     verbose: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// List every reference to a symbol (file:line:col and the source line)
+    Refs {
+        /// Symbol name; `Type::method` or `Type.method` searches for `method`
+        symbol: String,
+    },
+    /// Show which build systems are detected, the files that confirm them and the ones rejected
+    Detect,
+    /// Answer a REQUEST_FILE: print the file, line range, symbol or references as markdown
+    Request {
+        /// File path, or a glob pattern (`src/**/*.rs`)
+        target: String,
+        /// Line range: `80-140`, `80-` or `80`
+        #[arg(long, conflicts_with_all = ["symbol", "refs"])]
+        lines: Option<String>,
+        /// Symbol definition; `Type::method` also works
+        #[arg(long, conflicts_with = "refs")]
+        symbol: Option<String>,
+        /// Every reference to a symbol across the matched files
+        #[arg(long)]
+        refs: Option<String>,
+        /// Why the file is wanted (echoed in the output)
+        #[arg(long, default_value = "")]
+        reason: String,
+    },
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
@@ -62,6 +159,23 @@ fn main() -> Result<()> {
     config.code_only = cli.code_only;
     config.dry_run = cli.dry_run;
     config.verbose = cli.verbose;
+    config.skeleton_level = cli.skeleton_level;
+    config.stage2_token_budget = cli.stage2_budget;
+    config.graph_granularity = cli.graph_by;
+    config.focus = cli.focus.clone();
+    config.focus_top = cli.focus_top;
+    config.offline = cli.offline;
+    config.tool_timeout_secs = cli.tool_timeout;
+    config.manifest_mds_threshold = cli.manifest_mds;
+    config.manifest_confidence_threshold = cli.manifest_confidence;
+    config.redact_secrets = !cli.no_redact;
+    config.safety_mode = cli.injection_mode;
+    config.pii_mode = cli.pii;
+    config.pii_rules = cli.pii_rule.iter().map(|spec| pii::parse_rule(spec)).collect::<saccade_core::error::Result<_>>()?;
+    if let Some(layers) = &cli.layers {
+        config.layers = layers.split(',').map(str::trim).filter(|l| !l.is_empty()).map(str::to_string).collect();
+    }
+    config.language_dirs.extend(cli.lang_dir.iter().cloned().map(LanguageDir::trusted));
 
     if cli.git_only && cli.no_git {
         eprintln!("ERROR: Cannot specify both --git-only and --no-git");
@@ -84,6 +198,33 @@ fn main() -> Result<()> {
     }
 
     let pack = SaccadePack::new(config);
+    match &cli.command {
+        Some(Command::Refs { symbol }) => {
+            print!("{}", pack.find_references(symbol)?.render());
+            return Ok(());
+        }
+        Some(Command::Detect) => {
+            print!("{}", pack.detect()?.render());
+            return Ok(());
+        }
+        Some(Command::Request { target, lines, symbol, refs, reason }) => {
+            let target = if target.contains(['*', '?', '[']) {
+                RequestTarget::Pattern { pattern: target.clone() }
+            } else {
+                RequestTarget::SinglePath { path: target.clone() }
+            };
+            let range = match (lines, symbol, refs) {
+                (Some(lines), _, _) => Some(RequestRange::Lines { lines: lines.clone() }),
+                (_, Some(symbol), _) => Some(RequestRange::Symbol { symbol: symbol.clone() }),
+                (_, _, Some(refs)) => Some(RequestRange::Refs { refs: refs.clone() }),
+                _ => None,
+            };
+            let request = RequestFile { target, reason: reason.clone(), range };
+            print!("{}", pack.resolve_request(&request)?.to_markdown());
+            return Ok(());
+        }
+        None => {}
+    }
     pack.generate()?;
 
     // ✅ Windows-only clickable file:// link.
@@ -0,0 +1,825 @@
+// saccade/core/src/build_manifests.rs
+//
+// Declared dependencies from build manifests outside the lockfile ecosystems,
+// parsed offline into one row format:
+//   Bazel    MODULE.bazel `bazel_dep`/overrides, WORKSPACE repository rules (Starlark via the Python grammar)
+//   Meson    meson.build `dependency()`/`subproject()`, subprojects/*.wrap
+//   Make     `-l` link flags and `pkg-config` packages
+//   Nix      flake.nix inputs, pinned revisions from flake.lock
+//   Gradle   build.gradle(.kts) configurations, gradle/libs.versions.toml
+//   Maven    pom.xml dependencies with `${property}` substitution
+//   SwiftPM  Package.swift `.package(...)`, pins from Package.resolved
+//   Dart     pubspec.yaml
+//   Elixir   mix.exs `deps`
+//   Haskell  *.cabal `build-depends`, stack.yaml `extra-deps`
+//   Zig      build.zig.zon `.dependencies`
+//   C/C++    conanfile.py, conanfile.txt, vcpkg.json (see cpp_manifests.rs)
+
+use crate::cpp_manifests::{parse_conanfile_py, parse_conanfile_txt, parse_vcpkg_json};
+use crate::manifest_text::{inline_table_value, toml_entries, toml_value, unquote};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Parser, Query, QueryCursor};
+
+const STARLARK_CALL_QUERY: &str = r#"
+(call
+  function: (identifier) @fn
+  arguments: (argument_list) @args)
+"#;
+
+const BAZEL_REPOSITORY_RULES: &[&str] = &[
+    "http_archive", "http_file", "http_jar", "git_repository", "new_git_repository", "local_repository",
+    "new_local_repository", "maven_install",
+];
+const BAZEL_OVERRIDES: &[&str] = &["single_version_override", "multiple_version_override", "git_override", "archive_override", "local_path_override"];
+
+static MESON_DEPENDENCY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b(dependency|subproject)\s*\(\s*'([^']+)'([^)]*)\)"#).unwrap());
+static MESON_VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"version\s*:\s*(\[[^\]]*\]|'[^']*')"#).unwrap());
+static MAKE_LINK_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|\s)-l([A-Za-z0-9_+.-]+)").unwrap());
+static MAKE_PKG_CONFIG_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"pkg-config((?:\s+[^\s)`;|]+)+)").unwrap());
+static NIX_INPUT_URL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?:inputs\.)?([A-Za-z_][\w-]*)\.url\s*=\s*"([^"]+)"|([A-Za-z_][\w-]*)\s*=\s*\{[^{}]*?\burl\s*=\s*"([^"]+)""#).unwrap()
+});
+static GRADLE_DEPENDENCY_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?m)^\s*(implementation|api|compileOnly|runtimeOnly|testImplementation|testRuntimeOnly|androidTestImplementation|debugImplementation|kapt|ksp|annotationProcessor|classpath|compile|testCompile)\s*\(?\s*(?:(?:enforcedPlatform|platform)\s*\(\s*)?(?:"([^"]+)"|'([^']+)'|(libs\.[\w.]+))"#,
+    )
+    .unwrap()
+});
+static MAVEN_DEPENDENCY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?s)<dependency>(.*?)</dependency>").unwrap());
+static MAVEN_PROPERTY_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<([\w.-]+)>([^<]*)</[\w.-]+>").unwrap());
+static SWIFT_LOCATION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\b(url|path|id)\s*:\s*"([^"]+)""#).unwrap());
+static SWIFT_REQUIREMENT_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(from|exact|branch|revision)\s*:\s*"([^"]+)"|"([^"]+)"\s*(\.\.<|\.\.\.)\s*"([^"]+)"|\.(upToNextMajor|upToNextMinor)\s*\(\s*from\s*:\s*"([^"]+)""#).unwrap()
+});
+static MIX_DEP_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\{\s*:([a-z_][a-z0-9_]*)\s*,([^{}]*)\}"#).unwrap());
+static ZIG_DEPENDENCY_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\.(@"[^"]+"|[A-Za-z_]\w*)\s*=\s*\.\{([^{}]*)\}"#).unwrap());
+static ZIG_FIELD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\.(url|path|hash)\s*=\s*"([^"]+)""#).unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeclaredDependency {
+    pub name: String,
+    /// Version, range, tag or source location; `None` when unconstrained.
+    pub constraint: Option<String>,
+    /// `requires`/`dependency` (plain), or `dev`, `test`, `tool_requires`, `override`, `link`, …
+    pub kind: String,
+    /// `@user/channel`, features, platform, locked revision, `only: :test`, …
+    pub notes: Vec<String>,
+}
+
+impl DeclaredDependency {
+    fn new(name: impl Into<String>, constraint: Option<String>, kind: &str) -> Self {
+        Self { name: name.into(), constraint, kind: kind.to_string(), notes: Vec::new() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BuildManifest {
+    pub path: PathBuf,
+    pub dependencies: Vec<DeclaredDependency>,
+    /// File-wide context: vcpkg baseline, Stack resolver, minimum Zig version.
+    pub note: Option<String>,
+}
+
+impl BuildManifest {
+    /// Parse by file name; `None` for files that are not dependency manifests.
+    pub fn read(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        let file_name = path.file_name()?.to_str()?;
+        let sibling = |name: &str| path.parent().and_then(|dir| fs::read_to_string(dir.join(name)).ok());
+        let mut note = None;
+        let dependencies = match file_name {
+            "conanfile.py" => parse_conanfile_py(&content),
+            "conanfile.txt" => parse_conanfile_txt(&content),
+            "vcpkg.json" => {
+                let json: Value = serde_json::from_str(&content).ok()?;
+                note = json.get("builtin-baseline").and_then(Value::as_str).map(|b| format!("builtin-baseline {}", b));
+                parse_vcpkg_json(&json)
+            }
+            "MODULE.bazel" | "WORKSPACE" | "WORKSPACE.bazel" => parse_bazel(&content),
+            "meson.build" => parse_meson_build(&content),
+            "Makefile" | "makefile" | "GNUmakefile" => parse_makefile(&content),
+            "flake.nix" => parse_flake(&content, sibling("flake.lock").as_deref()),
+            "build.gradle" | "build.gradle.kts" => parse_gradle(&content),
+            "libs.versions.toml" => parse_gradle_catalog(&content),
+            "pom.xml" => parse_pom(&content),
+            "Package.swift" => parse_package_swift(&content, sibling("Package.resolved").as_deref()),
+            "pubspec.yaml" => parse_pubspec(&content),
+            "mix.exs" => parse_mix_exs(&content),
+            "stack.yaml" => {
+                note = yaml_scalar(&content, "resolver").or_else(|| yaml_scalar(&content, "snapshot")).map(|r| format!("resolver {}", r));
+                parse_stack_yaml(&content)
+            }
+            "build.zig.zon" => {
+                note = zon_scalar(&content, "minimum_zig_version").map(|v| format!("minimum_zig_version {}", v));
+                parse_build_zig_zon(&content)
+            }
+            name if name.ends_with(".wrap") => parse_meson_wrap(name.trim_end_matches(".wrap"), &content),
+            name if name.ends_with(".cabal") => parse_cabal(&content),
+            _ => return None,
+        };
+        Some(Self { path: path.to_path_buf(), dependencies, note })
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{}: {} declared dependencies\n",
+            self.path.to_string_lossy().replace('\\', "/").trim_start_matches("./"),
+            self.dependencies.len()
+        );
+        if let Some(note) = &self.note {
+            out.push_str(&format!("  ({})\n", note));
+        }
+        for dep in &self.dependencies {
+            let mut line = format!("  {} {}", dep.name, dep.constraint.as_deref().unwrap_or("*"));
+            if dep.kind != "requires" && dep.kind != "dependency" {
+                line.push_str(&format!(" [{}]", dep.kind));
+            }
+            if !dep.notes.is_empty() {
+                line.push_str(&format!(" ({})", dep.notes.join(", ")));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// --- Bazel -----------------------------------------------------------------
+
+/// `bazel_dep(name, version)`, version overrides and WORKSPACE repository rules.
+pub fn parse_bazel(content: &str) -> Vec<DeclaredDependency> {
+    let language = tree_sitter_python::language();
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else { return Vec::new() };
+    let Ok(query) = Query::new(&language, STARLARK_CALL_QUERY) else { return Vec::new() };
+    let text = |node: tree_sitter::Node| node.utf8_text(content.as_bytes()).unwrap_or_default();
+
+    let mut out = Vec::new();
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(&query, tree.root_node(), content.as_bytes()) {
+        let (Some(function), Some(args)) = (m.captures.first(), m.captures.get(1)) else { continue };
+        let rule = text(function.node);
+        let is_dep = rule == "bazel_dep";
+        if !is_dep && !BAZEL_REPOSITORY_RULES.contains(&rule) && !BAZEL_OVERRIDES.contains(&rule) {
+            continue;
+        }
+        let mut walker = args.node.walk();
+        let kwargs: HashMap<&str, String> = args
+            .node
+            .named_children(&mut walker)
+            .filter(|a| a.kind() == "keyword_argument")
+            .filter_map(|a| {
+                let key = text(a.child_by_field_name("name")?);
+                let value = a.child_by_field_name("value")?;
+                let value = if value.kind() == "list" {
+                    let mut w = value.walk();
+                    value.named_children(&mut w).map(|v| unquote(text(v)).to_string()).collect::<Vec<_>>().join(" ")
+                } else {
+                    unquote(text(value)).to_string()
+                };
+                Some((key, value))
+            })
+            .collect();
+        let Some(name) = kwargs.get("name").or_else(|| kwargs.get("module_name")) else { continue };
+        let dep = if is_dep {
+            let kind = if kwargs.get("dev_dependency").is_some_and(|v| v == "True") { "dev" } else { "dependency" };
+            let mut dep = DeclaredDependency::new(name.as_str(), kwargs.get("version").cloned(), kind);
+            if let Some(repo_name) = kwargs.get("repo_name") {
+                dep.notes.push(format!("as @{}", repo_name));
+            }
+            dep
+        } else {
+            let constraint = ["version", "tag", "commit", "url", "urls", "remote", "path"]
+                .iter()
+                .find_map(|key| kwargs.get(*key))
+                .map(|v| if v.len() == 40 && v.chars().all(|c| c.is_ascii_hexdigit()) { v[..12].to_string() } else { v.clone() });
+            let kind = if BAZEL_OVERRIDES.contains(&rule) { "override" } else { rule };
+            DeclaredDependency::new(name.as_str(), constraint, kind)
+        };
+        out.push(dep);
+    }
+    out
+}
+
+// --- Meson -----------------------------------------------------------------
+
+pub fn parse_meson_build(content: &str) -> Vec<DeclaredDependency> {
+    let code: String = content.lines().map(|l| l.split('#').next().unwrap_or(l)).collect::<Vec<_>>().join("\n");
+    MESON_DEPENDENCY_RE
+        .captures_iter(&code)
+        .map(|c| {
+            let args = c.get(3).map_or("", |m| m.as_str());
+            let version = MESON_VERSION_RE.captures(args).map(|v| {
+                let raw = &v[1];
+                raw.trim_matches(|ch| ch == '[' || ch == ']')
+                    .split(',')
+                    .map(|p| p.trim().trim_matches('\''))
+                    .filter(|p| !p.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            });
+            let kind = if &c[1] == "subproject" { "subproject" } else { "dependency" };
+            let mut dep = DeclaredDependency::new(&c[2], version, kind);
+            if args.split_whitespace().collect::<String>().contains("required:false") {
+                dep.notes.push("optional".to_string());
+            }
+            dep
+        })
+        .collect()
+}
+
+/// `subprojects/<name>.wrap`: where Meson fetches a subproject from.
+pub fn parse_meson_wrap(name: &str, content: &str) -> Vec<DeclaredDependency> {
+    let mut section = "";
+    let mut values: HashMap<&str, &str> = HashMap::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']');
+        } else if let Some((key, value)) = line.split_once('=') {
+            values.insert(key.trim(), value.trim());
+        }
+    }
+    let constraint = match section {
+        "wrap-git" => values.get("url").map(|u| format!("git {} @ {}", u, values.get("revision").unwrap_or(&"HEAD"))),
+        "wrap-file" => values.get("source_url").map(|u| format!("url {}", u)),
+        _ => None,
+    };
+    vec![DeclaredDependency::new(name, constraint, "subproject")]
+}
+
+// --- Make ------------------------------------------------------------------
+
+/// Libraries a Makefile links (`-lfoo`) and packages it asks `pkg-config` for.
+pub fn parse_makefile(content: &str) -> Vec<DeclaredDependency> {
+    let mut out: Vec<DeclaredDependency> = Vec::new();
+    let mut push = |name: &str, kind: &str| {
+        if !out.iter().any(|d| d.name == name && d.kind == kind) {
+            out.push(DeclaredDependency::new(name, None, kind));
+        }
+    };
+    for line in content.lines().map(|l| l.split('#').next().unwrap_or(l)) {
+        for c in MAKE_PKG_CONFIG_RE.captures_iter(line) {
+            for package in c[1].split_whitespace().filter(|p| !p.starts_with('-') && !p.starts_with('$')) {
+                push(package, "pkg-config");
+            }
+        }
+        for c in MAKE_LINK_RE.captures_iter(line) {
+            push(&c[1], "link");
+        }
+    }
+    out
+}
+
+// --- Nix -------------------------------------------------------------------
+
+/// flake.nix inputs, annotated with the revision flake.lock pins them to.
+pub fn parse_flake(content: &str, lock: Option<&str>) -> Vec<DeclaredDependency> {
+    let lock: Option<Value> = lock.and_then(|l| serde_json::from_str(l).ok());
+    let locked_rev = |input: &str| -> Option<String> {
+        let lock = lock.as_ref()?;
+        let node = lock.pointer(&format!("/nodes/root/inputs/{}", input))?.as_str()?;
+        let rev = lock.pointer(&format!("/nodes/{}/locked/rev", node))?.as_str()?;
+        Some(format!("locked {}", rev.chars().take(12).collect::<String>()))
+    };
+    let mut out: Vec<DeclaredDependency> = Vec::new();
+    for c in NIX_INPUT_URL_RE.captures_iter(content) {
+        let (name, url) = match (c.get(1), c.get(2), c.get(3), c.get(4)) {
+            (Some(n), Some(u), _, _) | (_, _, Some(n), Some(u)) => (n.as_str(), u.as_str()),
+            _ => continue,
+        };
+        if out.iter().any(|d| d.name == name) {
+            continue;
+        }
+        let mut dep = DeclaredDependency::new(name, Some(url.to_string()), "input");
+        dep.notes.extend(locked_rev(name));
+        out.push(dep);
+    }
+    out
+}
+
+// --- Gradle ----------------------------------------------------------------
+
+/// `implementation("g:a:v")`, `api 'g:a:v'`, `platform(...)`, and version-catalog `libs.x` references.
+pub fn parse_gradle(content: &str) -> Vec<DeclaredDependency> {
+    GRADLE_DEPENDENCY_RE
+        .captures_iter(content)
+        .map(|c| {
+            let configuration = &c[1];
+            let kind = match configuration {
+                "implementation" | "api" | "compile" => "dependency",
+                c if c.starts_with("test") || c.starts_with("androidTest") => "test",
+                other => other,
+            };
+            if let Some(alias) = c.get(4) {
+                return DeclaredDependency::new(alias.as_str(), None, kind);
+            }
+            let coordinate = c.get(2).or(c.get(3)).map_or("", |m| m.as_str());
+            let mut parts = coordinate.splitn(3, ':');
+            let (group, artifact, version) = (parts.next().unwrap_or(""), parts.next(), parts.next());
+            let name = match artifact {
+                Some(artifact) => format!("{}:{}", group, artifact),
+                None => group.to_string(),
+            };
+            DeclaredDependency::new(name, version.map(str::to_string), kind)
+        })
+        .collect()
+}
+
+/// gradle/libs.versions.toml `[libraries]` and `[plugins]`, with `version.ref` resolved.
+pub fn parse_gradle_catalog(content: &str) -> Vec<DeclaredDependency> {
+    let entries = toml_entries(content);
+    let version_of = |value: &str| -> Option<String> {
+        if let Some(reference) = inline_table_value(value, "version.ref") {
+            return toml_value(&entries, "versions", reference).map(|v| unquote(v).to_string());
+        }
+        inline_table_value(value, "version").map(str::to_string)
+    };
+    let mut out = Vec::new();
+    for (table, alias, value) in &entries {
+        let dep = match table.as_str() {
+            "libraries" if value.starts_with('{') => {
+                let module = inline_table_value(value, "module").map(str::to_string).or_else(|| {
+                    Some(format!("{}:{}", inline_table_value(value, "group")?, inline_table_value(value, "name")?))
+                });
+                let Some(module) = module else { continue };
+                DeclaredDependency::new(module, version_of(value), "dependency")
+            }
+            "libraries" => {
+                let coordinate = unquote(value);
+                let (module, version) = match coordinate.rsplit_once(':') {
+                    Some((m, v)) if m.contains(':') => (m.to_string(), Some(v.to_string())),
+                    _ => (coordinate.to_string(), None),
+                };
+                DeclaredDependency::new(module, version, "dependency")
+            }
+            "plugins" => {
+                let id = inline_table_value(value, "id").unwrap_or(alias);
+                DeclaredDependency::new(id, version_of(value), "plugin")
+            }
+            _ => continue,
+        };
+        let mut dep = dep;
+        dep.notes.push(format!("libs.{}", alias.replace(['-', '_'], ".")));
+        out.push(dep);
+    }
+    out
+}
+
+// --- Maven -----------------------------------------------------------------
+
+/// pom.xml `<dependency>` entries; those under `<dependencyManagement>` are `managed`.
+pub fn parse_pom(content: &str) -> Vec<DeclaredDependency> {
+    let properties: HashMap<String, String> = content
+        .split_once("<properties>")
+        .and_then(|(_, rest)| rest.split_once("</properties>"))
+        .map(|(block, _)| MAVEN_PROPERTY_RE.captures_iter(block).map(|c| (c[1].to_string(), c[2].trim().to_string())).collect())
+        .unwrap_or_default();
+    let resolve = |value: &str| -> String {
+        let mut out = value.to_string();
+        for (key, val) in &properties {
+            out = out.replace(&format!("${{{}}}", key), val);
+        }
+        out
+    };
+    let managed: Vec<(usize, usize)> = content
+        .match_indices("<dependencyManagement>")
+        .filter_map(|(start, _)| Some((start, start + content[start..].find("</dependencyManagement>")?)))
+        .collect();
+    let tag = |block: &str, name: &str| -> Option<String> {
+        let (_, rest) = block.split_once(&format!("<{}>", name))?;
+        Some(rest.split_once(&format!("</{}>", name))?.0.trim().to_string())
+    };
+    MAVEN_DEPENDENCY_RE
+        .captures_iter(content)
+        .filter_map(|c| {
+            let block = c.get(1)?;
+            let name = format!("{}:{}", tag(block.as_str(), "groupId")?, tag(block.as_str(), "artifactId")?);
+            let is_managed = managed.iter().any(|(s, e)| (*s..*e).contains(&block.start()));
+            let kind = match tag(block.as_str(), "scope").as_deref() {
+                _ if is_managed => "managed".to_string(),
+                Some("test") => "test".to_string(),
+                Some("compile") | None => "dependency".to_string(),
+                Some(other) => other.to_string(),
+            };
+            let mut dep = DeclaredDependency::new(resolve(&name), tag(block.as_str(), "version").map(|v| resolve(&v)), &kind);
+            if tag(block.as_str(), "optional").as_deref() == Some("true") {
+                dep.notes.push("optional".to_string());
+            }
+            Some(dep)
+        })
+        .collect()
+}
+
+// --- SwiftPM ---------------------------------------------------------------
+
+/// `.package(url:…, from:…)` and friends, with versions pinned in Package.resolved.
+pub fn parse_package_swift(content: &str, resolved: Option<&str>) -> Vec<DeclaredDependency> {
+    let pins: HashMap<String, String> = resolved
+        .and_then(|r| serde_json::from_str::<Value>(r).ok())
+        .map(|json| {
+            let pins = json.get("pins").or_else(|| json.pointer("/object/pins")).and_then(Value::as_array).cloned();
+            pins.unwrap_or_default()
+                .iter()
+                .filter_map(|pin| {
+                    let identity = pin.get("identity").or_else(|| pin.get("package"))?.as_str()?.to_ascii_lowercase();
+                    let state = pin.get("state")?;
+                    let pinned = state.get("version").and_then(Value::as_str).or_else(|| state.get("revision")?.as_str())?;
+                    Some((identity, pinned.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut out = Vec::new();
+    for (start, _) in content.match_indices(".package(") {
+        let args = balanced_parens(&content[start + ".package".len()..]);
+        let Some(location) = SWIFT_LOCATION_RE.captures(args) else { continue };
+        let (key, value) = (&location[1], &location[2]);
+        let name = value.trim_end_matches('/').rsplit('/').next().unwrap_or(value).trim_end_matches(".git").to_string();
+        let constraint = match key {
+            "path" => Some(format!("path {}", value)),
+            _ => SWIFT_REQUIREMENT_RE.captures(args).map(|r| {
+                if let (Some(k), Some(v)) = (r.get(1), r.get(2)) {
+                    match k.as_str() {
+                        "from" => format!(">={}", v.as_str()),
+                        "exact" => format!("={}", v.as_str()),
+                        other => format!("{} {}", other, v.as_str()),
+                    }
+                } else if let (Some(low), Some(op), Some(high)) = (r.get(3), r.get(4), r.get(5)) {
+                    format!("{}{}{}", low.as_str(), op.as_str(), high.as_str())
+                } else {
+                    let prefix = if r.get(6).map(|m| m.as_str()) == Some("upToNextMinor") { "~" } else { "^" };
+                    format!("{}{}", prefix, r.get(7).map_or("", |m| m.as_str()))
+                }
+            }),
+        };
+        let mut dep = DeclaredDependency::new(name.clone(), constraint, if key == "path" { "path" } else { "dependency" });
+        if key == "url" {
+            dep.notes.push(value.to_string());
+        }
+        if let Some(pinned) = pins.get(&name.to_ascii_lowercase()) {
+            dep.notes.push(format!("resolved {}", pinned));
+        }
+        out.push(dep);
+    }
+    out
+}
+
+/// `(…)` starting at `s[0]`, including nested parentheses; the whole rest if unbalanced.
+fn balanced_parens(s: &str) -> &str {
+    let mut depth = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return &s[..=i];
+                }
+            }
+            _ => {}
+        }
+    }
+    s
+}
+
+// --- Dart ------------------------------------------------------------------
+
+/// pubspec.yaml `dependencies`, `dev_dependencies` and `dependency_overrides`.
+pub fn parse_pubspec(content: &str) -> Vec<DeclaredDependency> {
+    let mut out: Vec<DeclaredDependency> = Vec::new();
+    let mut kind: Option<&str> = None;
+    // Indent of the section's entries, taken from its first entry (2, 4, …).
+    let mut entry_indent: Option<usize> = None;
+    // Nested `sdk:` / `git:` / `path:` / `version:` lines of the current entry.
+    let mut nested: BTreeMap<String, String> = BTreeMap::new();
+    let flush = |out: &mut Vec<DeclaredDependency>, nested: &mut BTreeMap<String, String>| {
+        if let Some(last) = out.last_mut().filter(|d| d.constraint.is_none()) {
+            last.constraint = ["version", "sdk", "git", "url", "path"]
+                .iter()
+                .find_map(|k| nested.get(*k).map(|v| if *k == "version" { v.clone() } else { format!("{} {}", k, v) }));
+        }
+        nested.clear();
+    };
+    for line in content.lines() {
+        let code = line.split(" #").next().unwrap_or(line).trim_end();
+        if code.trim().is_empty() || code.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = code.len() - code.trim_start().len();
+        let Some((key, value)) = code.trim().split_once(':') else { continue };
+        let value = unquote(value.trim());
+        if indent == 0 {
+            flush(&mut out, &mut nested);
+            entry_indent = None;
+            kind = match key {
+                "dependencies" => Some("dependency"),
+                "dev_dependencies" => Some("dev"),
+                "dependency_overrides" => Some("override"),
+                _ => None,
+            };
+        } else if let Some(kind) = kind {
+            if indent <= *entry_indent.get_or_insert(indent) {
+                flush(&mut out, &mut nested);
+                let constraint = (!value.is_empty()).then(|| value.to_string());
+                out.push(DeclaredDependency::new(key, constraint, kind));
+            } else if !value.is_empty() {
+                nested.entry(key.trim().to_string()).or_insert_with(|| value.to_string());
+            }
+        }
+    }
+    flush(&mut out, &mut nested);
+    out
+}
+
+// --- Elixir ----------------------------------------------------------------
+
+/// `{:name, "~> 1.0", only: :test}` tuples in mix.exs `deps`.
+pub fn parse_mix_exs(content: &str) -> Vec<DeclaredDependency> {
+    let deps = content
+        .find("defp deps")
+        .or_else(|| content.find("def deps"))
+        .and_then(|start| {
+            let open = start + content[start..].find('[')?;
+            let mut depth = 0;
+            for (i, c) in content[open..].char_indices() {
+                match c {
+                    '[' => depth += 1,
+                    ']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return Some(&content[open..=open + i]);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            None
+        })
+        .unwrap_or("");
+    MIX_DEP_RE
+        .captures_iter(deps)
+        .map(|c| {
+            let rest = c[2].trim();
+            let mut options: Vec<&str> = rest.split(',').map(str::trim).filter(|o| !o.is_empty()).collect();
+            let constraint = if options.first().is_some_and(|o| o.starts_with('"')) {
+                Some(unquote(options.remove(0)).to_string())
+            } else {
+                options.iter().find_map(|o| {
+                    let (key, value) = o.split_once(':')?;
+                    matches!(key, "git" | "github" | "path").then(|| format!("{} {}", key, unquote(value.trim())))
+                })
+            };
+            let only = options.iter().find_map(|o| o.strip_prefix("only:")).map(str::trim);
+            let kind = match only {
+                Some(":test") => "test",
+                Some(":dev") => "dev",
+                _ => "dependency",
+            };
+            let mut dep = DeclaredDependency::new(&c[1], constraint, kind);
+            dep.notes.extend(
+                options
+                    .iter()
+                    .filter(|o| o.starts_with("only:") && kind == "dependency" || o.starts_with("runtime:") || o.starts_with("optional:"))
+                    .map(|o| o.split_whitespace().collect::<Vec<_>>().join(" ")),
+            );
+            dep
+        })
+        .collect()
+}
+
+// --- Haskell ---------------------------------------------------------------
+
+/// `build-depends:` across a .cabal file's components; test suites and benchmarks are `test`.
+pub fn parse_cabal(content: &str) -> Vec<DeclaredDependency> {
+    let own_name = content.lines().find_map(|l| {
+        let (key, value) = l.split_once(':')?;
+        key.trim().eq_ignore_ascii_case("name").then(|| value.trim().to_string())
+    });
+    let mut out: Vec<DeclaredDependency> = Vec::new();
+    let mut component_kind = "dependency";
+    let lines: Vec<&str> = content.lines().collect();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].split("--").next().unwrap_or(lines[i]);
+        let indent = line.len() - line.trim_start().len();
+        let lower = line.trim().to_ascii_lowercase();
+        if indent == 0 && !lower.is_empty() && !lower.contains(':') {
+            component_kind = if lower.starts_with("test-suite") || lower.starts_with("benchmark") { "test" } else { "dependency" };
+        }
+        if let Some(rest) = lower.strip_prefix("build-depends:") {
+            // The field continues on lines indented deeper than `build-depends:`.
+            let mut field = line.trim()[line.trim().len() - rest.len()..].to_string();
+            while i + 1 < lines.len() {
+                let next = lines[i + 1].split("--").next().unwrap_or(lines[i + 1]);
+                let next_indent = next.len() - next.trim_start().len();
+                if next.trim().is_empty() || next_indent <= indent {
+                    break;
+                }
+                field.push(' ');
+                field.push_str(next.trim());
+                i += 1;
+            }
+            for spec in field.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let (name, constraint) = match spec.find(|c: char| c.is_whitespace() || "<>=^".contains(c)) {
+                    Some(at) => (&spec[..at], Some(spec[at..].trim().to_string()).filter(|c| !c.is_empty())),
+                    None => (spec, None),
+                };
+                if own_name.as_deref() == Some(name) || out.iter().any(|d| d.name == name && d.kind == component_kind) {
+                    continue;
+                }
+                out.push(DeclaredDependency::new(name, constraint, component_kind));
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+/// stack.yaml `extra-deps`: `acme-1.2.3`, `acme-1.2.3@sha256:…` or `git:`/`github:` entries.
+pub fn parse_stack_yaml(content: &str) -> Vec<DeclaredDependency> {
+    let mut out = Vec::new();
+    let mut in_extra = false;
+    for line in content.lines() {
+        if !line.starts_with(' ') && !line.starts_with('-') {
+            in_extra = line.trim_end() == "extra-deps:";
+            continue;
+        }
+        let Some(item) = line.trim().strip_prefix("- ").filter(|_| in_extra) else { continue };
+        let item = unquote(item.split('@').next().unwrap_or(item));
+        if let Some((key, location)) = item.split_once(": ") {
+            out.push(DeclaredDependency::new(unquote(location), None, key.trim()));
+            continue;
+        }
+        let split = item.rmatch_indices('-').find(|(i, _)| item[i + 1..].starts_with(|c: char| c.is_ascii_digit()));
+        match split {
+            Some((i, _)) => out.push(DeclaredDependency::new(&item[..i], Some(item[i + 1..].to_string()), "extra-dep")),
+            None => out.push(DeclaredDependency::new(item, None, "extra-dep")),
+        }
+    }
+    out
+}
+
+fn yaml_scalar(content: &str, key: &str) -> Option<String> {
+    content.lines().find_map(|l| Some(unquote(l.strip_prefix(key)?.strip_prefix(':')?).to_string()))
+}
+
+// --- Zig -------------------------------------------------------------------
+
+/// build.zig.zon `.dependencies = .{ .name = .{ .url, .hash } | .{ .path } }`.
+pub fn parse_build_zig_zon(content: &str) -> Vec<DeclaredDependency> {
+    let Some(start) = content.find(".dependencies") else { return Vec::new() };
+    ZIG_DEPENDENCY_RE
+        .captures_iter(&content[start..])
+        .filter_map(|c| {
+            let name = c[1].trim_start_matches('@').trim_matches('"').to_string();
+            let fields: HashMap<String, String> =
+                ZIG_FIELD_RE.captures_iter(&c[2]).map(|f| (f[1].to_string(), f[2].to_string())).collect();
+            let constraint = match (fields.get("url"), fields.get("path")) {
+                (Some(url), _) => format!("url {}", url),
+                (None, Some(path)) => format!("path {}", path),
+                _ => return None,
+            };
+            Some(DeclaredDependency::new(name, Some(constraint), "dependency"))
+        })
+        .collect()
+}
+
+fn zon_scalar(content: &str, key: &str) -> Option<String> {
+    let (_, rest) = content.split_once(&format!(".{} = \"", key))?;
+    Some(rest.split_once('"')?.0.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(deps: &[DeclaredDependency]) -> Vec<(&str, Option<&str>, &str)> {
+        deps.iter().map(|d| (d.name.as_str(), d.constraint.as_deref(), d.kind.as_str())).collect()
+    }
+
+    #[test]
+    fn bazel_meson_make_and_nix() {
+        let module = r#"
+module(name = "app", version = "1.0")
+bazel_dep(name = "rules_cc", version = "0.0.9")
+bazel_dep(name = "googletest", version = "1.14.0", dev_dependency = True, repo_name = "gtest")
+git_override(module_name = "rules_cc", remote = "https://github.com/bazelbuild/rules_cc", commit = "0123456789abcdef0123456789abcdef01234567")
+"#;
+        assert_eq!(
+            rows(&parse_bazel(module)),
+            [
+                ("rules_cc", Some("0.0.9"), "dependency"),
+                ("googletest", Some("1.14.0"), "dev"),
+                ("rules_cc", Some("0123456789ab"), "override"),
+            ]
+        );
+
+        let meson = "project('demo', 'c')\nzlib = dependency('zlib', version : '>=1.2.8')\nglib = dependency('glib-2.0', version : ['>=2.70', '<3'], required : false)\nsub = subproject('fmt') # vendored\n";
+        let deps = parse_meson_build(meson);
+        assert_eq!(
+            rows(&deps),
+            [("zlib", Some(">=1.2.8"), "dependency"), ("glib-2.0", Some(">=2.70 <3"), "dependency"), ("fmt", None, "subproject")]
+        );
+        assert_eq!(deps[1].notes, ["optional"]);
+
+        let make = "CFLAGS += $(shell pkg-config --cflags gtk+-3.0 libcurl)\nLDLIBS = -lm -lpthread $(shell pkg-config --libs gtk+-3.0)\n# -lignored\n";
+        assert_eq!(
+            rows(&parse_makefile(make)),
+            [("gtk+-3.0", None, "pkg-config"), ("libcurl", None, "pkg-config"), ("m", None, "link"), ("pthread", None, "link")]
+        );
+
+        let flake = "{\n  inputs.nixpkgs.url = \"github:NixOS/nixpkgs/nixos-24.05\";\n  inputs.utils = { url = \"github:numtide/flake-utils\"; };\n  outputs = { self, nixpkgs, utils }: { };\n}\n";
+        let lock = r#"{"nodes": {"root": {"inputs": {"nixpkgs": "nixpkgs"}}, "nixpkgs": {"locked": {"rev": "0a1b2c3d4e5f60718293a4b5c6d7e8f901234567"}}}}"#;
+        let deps = parse_flake(flake, Some(lock));
+        assert_eq!(
+            rows(&deps),
+            [("nixpkgs", Some("github:NixOS/nixpkgs/nixos-24.05"), "input"), ("utils", Some("github:numtide/flake-utils"), "input")]
+        );
+        assert_eq!(deps[0].notes, ["locked 0a1b2c3d4e5f"]);
+        let lock = r#"{"nodes": {"root": {"inputs": {"nixpkgs": "nixpkgs"}}, "nixpkgs": {"locked": {"rev": "ветка-релиза-24"}}}}"#;
+        assert_eq!(parse_flake(flake, Some(lock))[0].notes, ["locked ветка-релиза"]);
+    }
+
+    #[test]
+    fn jvm_swift_dart_elixir_haskell_and_zig() {
+        let gradle = "dependencies {\n    implementation(\"com.squareup.okhttp3:okhttp:4.12.0\")\n    implementation platform('org.springframework.boot:spring-boot-dependencies:3.2.0')\n    testImplementation(libs.junit.jupiter)\n}\n";
+        assert_eq!(
+            rows(&parse_gradle(gradle)),
+            [
+                ("com.squareup.okhttp3:okhttp", Some("4.12.0"), "dependency"),
+                ("org.springframework.boot:spring-boot-dependencies", Some("3.2.0"), "dependency"),
+                ("libs.junit.jupiter", None, "test"),
+            ]
+        );
+        let catalog = "[versions]\njunit = \"5.10.1\"\n\n[libraries]\njunit-jupiter = { module = \"org.junit.jupiter:junit-jupiter\", version.ref = \"junit\" }\nguava = \"com.google.guava:guava:33.0.0-jre\"\n";
+        assert_eq!(
+            rows(&parse_gradle_catalog(catalog)),
+            [("org.junit.jupiter:junit-jupiter", Some("5.10.1"), "dependency"), ("com.google.guava:guava", Some("33.0.0-jre"), "dependency")]
+        );
+
+        let pom = "<project><properties><jackson.version>2.16.0</jackson.version></properties>\n<dependencyManagement><dependencies><dependency><groupId>org.slf4j</groupId><artifactId>slf4j-bom</artifactId><version>2.0.9</version></dependency></dependencies></dependencyManagement>\n<dependencies><dependency><groupId>com.fasterxml.jackson.core</groupId><artifactId>jackson-databind</artifactId><version>${jackson.version}</version></dependency>\n<dependency><groupId>junit</groupId><artifactId>junit</artifactId><version>4.13.2</version><scope>test</scope></dependency></dependencies></project>";
+        assert_eq!(
+            rows(&parse_pom(pom)),
+            [
+                ("org.slf4j:slf4j-bom", Some("2.0.9"), "managed"),
+                ("com.fasterxml.jackson.core:jackson-databind", Some("2.16.0"), "dependency"),
+                ("junit:junit", Some("4.13.2"), "test"),
+            ]
+        );
+
+        let swift = "import PackageDescription\nlet package = Package(\n  dependencies: [\n    .package(url: \"https://github.com/apple/swift-argument-parser.git\", from: \"1.3.0\"),\n    .package(url: \"https://github.com/vapor/vapor\", .upToNextMinor(from: \"4.89.0\")),\n    .package(path: \"../Shared\"),\n  ]\n)\n";
+        let resolved = r#"{"pins": [{"identity": "swift-argument-parser", "state": {"version": "1.3.1"}}], "version": 2}"#;
+        let deps = parse_package_swift(swift, Some(resolved));
+        assert_eq!(
+            rows(&deps),
+            [("swift-argument-parser", Some(">=1.3.0"), "dependency"), ("vapor", Some("~4.89.0"), "dependency"), ("Shared", Some("path ../Shared"), "path")]
+        );
+        assert_eq!(deps[0].notes[1], "resolved 1.3.1");
+
+        let pubspec = "name: app\nenvironment:\n  sdk: '>=3.0.0 <4.0.0'\ndependencies:\n  flutter:\n    sdk: flutter\n  http: ^1.1.0\n  shared:\n    path: ../shared\ndev_dependencies:\n  lints: ^3.0.0\n";
+        assert_eq!(
+            rows(&parse_pubspec(pubspec)),
+            [("flutter", Some("sdk flutter"), "dependency"), ("http", Some("^1.1.0"), "dependency"), ("shared", Some("path ../shared"), "dependency"), ("lints", Some("^3.0.0"), "dev")]
+        );
+        let four_spaces = "dependencies:\n    http: ^1.1.0\n    shared:\n        path: ../shared\n";
+        assert_eq!(
+            rows(&parse_pubspec(four_spaces)),
+            [("http", Some("^1.1.0"), "dependency"), ("shared", Some("path ../shared"), "dependency")]
+        );
+
+        let mix = "defmodule App.MixProject do\n  use Mix.Project\n  defp deps do\n    [\n      {:phoenix, \"~> 1.7.10\"},\n      {:credo, \"~> 1.7\", only: [:dev, :test], runtime: false},\n      {:ex_machina, \"~> 2.7\", only: :test},\n      {:plug, github: \"elixir-plug/plug\"}\n    ]\n  end\nend\n";
+        assert_eq!(
+            rows(&parse_mix_exs(mix)),
+            [
+                ("phoenix", Some("~> 1.7.10"), "dependency"),
+                ("credo", Some("~> 1.7"), "dependency"),
+                ("ex_machina", Some("~> 2.7"), "test"),
+                ("plug", Some("github elixir-plug/plug"), "dependency"),
+            ]
+        );
+
+        let cabal = "cabal-version: 3.0\nname: demo\n\nlibrary\n  build-depends:    base >=4.14 && <5\n                  , text ^>=2.0\n\ntest-suite spec\n  build-depends: demo, hspec\n";
+        assert_eq!(
+            rows(&parse_cabal(cabal)),
+            [("base", Some(">=4.14 && <5"), "dependency"), ("text", Some("^>=2.0"), "dependency"), ("hspec", None, "test")]
+        );
+        let stack = "resolver: lts-22.6\nextra-deps:\n  - acme-missiles-0.3@sha256:abc\n  - git: https://github.com/x/y\n";
+        assert_eq!(rows(&parse_stack_yaml(stack)), [("acme-missiles", Some("0.3"), "extra-dep"), ("https://github.com/x/y", None, "git")]);
+
+        let zon = ".{\n    .name = \"app\",\n    .dependencies = .{\n        .clap = .{\n            .url = \"https://github.com/Hejsil/zig-clap/archive/0.8.0.tar.gz\",\n            .hash = \"1220abc\",\n        },\n        .@\"zig-local\" = .{ .path = \"libs/local\" },\n    },\n}\n";
+        assert_eq!(
+            rows(&parse_build_zig_zon(zon)),
+            [("clap", Some("url https://github.com/Hejsil/zig-clap/archive/0.8.0.tar.gz"), "dependency"), ("zig-local", Some("path libs/local"), "dependency")]
+        );
+    }
+}
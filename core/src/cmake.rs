@@ -0,0 +1,451 @@
+// saccade/core/src/cmake.rs
+//
+// CMake project model for DEPS, built from the Tree-sitter CMake grammar:
+//   project()                              name and version
+//   find_package()                         name, version, COMPONENTS, REQUIRED/OPTIONAL
+//   add_executable() / add_library()       targets with kind and sources
+//   target_sources() / target_link_libraries()
+//   FetchContent_Declare() / ExternalProject_Add()   where third-party code comes from
+//   add_subdirectory()                     the directory tree
+// `set()` and `project()` variables are substituted in later arguments, scoped per directory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser, Query, QueryCursor};
+
+const CMAKE_COMMAND_QUERY: &str = r#"
+(normal_command) @command
+"#;
+
+/// Keywords that end a `find_package` component list.
+const FIND_PACKAGE_KEYWORDS: &[&str] = &[
+    "REQUIRED", "OPTIONAL", "QUIET", "EXACT", "MODULE", "CONFIG", "NO_MODULE", "GLOBAL", "COMPONENTS",
+    "OPTIONAL_COMPONENTS", "NAMES", "CONFIGS", "HINTS", "PATHS", "PATH_SUFFIXES", "NO_DEFAULT_PATH",
+    "NO_POLICY_SCOPE", "BYPASS_PROVIDER",
+];
+const LIBRARY_KINDS: &[&str] = &["STATIC", "SHARED", "MODULE", "OBJECT", "INTERFACE"];
+const VISIBILITY_KEYWORDS: &[&str] = &["PUBLIC", "PRIVATE", "INTERFACE", "LINK_PUBLIC", "LINK_PRIVATE", "LINK_INTERFACE_LIBRARIES"];
+const MAX_LISTED_SOURCES: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FindPackage {
+    pub name: String,
+    pub version: Option<String>,
+    pub components: Vec<String>,
+    pub required: bool,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CMakeTarget {
+    pub name: String,
+    /// `executable`, `library STATIC`, `library INTERFACE`, `alias of x`, `imported library`, …
+    pub kind: String,
+    pub sources: Vec<String>,
+    pub links: Vec<String>,
+    pub file: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalSource {
+    pub name: String,
+    /// `FetchContent` or `ExternalProject`.
+    pub via: &'static str,
+    /// `git <repo> @ <tag>`, `url <url>`, or `source <dir>`.
+    pub origin: String,
+    pub file: String,
+}
+
+#[derive(Debug, Default)]
+pub struct CMakeProject {
+    pub projects: Vec<(String, Option<String>, String)>,
+    pub packages: Vec<FindPackage>,
+    pub targets: Vec<CMakeTarget>,
+    pub external: Vec<ExternalSource>,
+    /// Containing file → `add_subdirectory` arguments.
+    pub subdirectories: BTreeMap<String, Vec<String>>,
+}
+
+impl CMakeProject {
+    /// Parse every CMake file; targets linked or extended in one file and declared in another are merged.
+    pub fn parse(files: &[(PathBuf, String)]) -> Self {
+        let mut project = CMakeProject::default();
+        let mut parser = Parser::new();
+        let language = tree_sitter_cmake::language();
+        if parser.set_language(&language).is_err() {
+            return project;
+        }
+        let Ok(query) = Query::new(&language, CMAKE_COMMAND_QUERY) else { return project };
+        // Link/source additions whose target may be declared in a later file.
+        let mut pending: Vec<(String, Vec<String>, Vec<String>)> = Vec::new();
+        // Parents before subdirectories, so inherited variables like ${PROJECT_NAME} resolve.
+        let mut ordered: Vec<&(PathBuf, String)> = files.iter().collect();
+        ordered.sort_by_key(|(path, _)| (path.components().count(), path.clone()));
+        // Directory scopes: each file starts from its nearest parent CMakeLists.txt's variables,
+        // and only a CMakeLists.txt passes its own on, so siblings and `.cmake` modules don't leak.
+        let mut scopes: HashMap<PathBuf, HashMap<String, String>> = HashMap::new();
+        for (path, content) in ordered {
+            let Some(tree) = parser.parse(content, None) else { continue };
+            let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let mut vars = dir.ancestors().find_map(|d| scopes.get(d)).cloned().unwrap_or_default();
+            let file = display_path(path);
+            let mut cursor = QueryCursor::new();
+            for m in cursor.matches(&query, tree.root_node(), content.as_bytes()) {
+                let Some((command, args)) = command_args(m.captures[0].node, content, &vars) else { continue };
+                project.apply(&command.to_ascii_lowercase(), &args, &file, &mut vars, &mut pending);
+            }
+            if path.file_name().is_some_and(|n| n == "CMakeLists.txt") {
+                scopes.insert(dir, vars);
+            }
+        }
+        for (target, sources, links) in pending {
+            if let Some(t) = project.targets.iter_mut().find(|t| t.name == target) {
+                t.sources.extend(sources);
+                t.links.extend(links);
+            }
+        }
+        for target in &mut project.targets {
+            dedup_keep_order(&mut target.links);
+            dedup_keep_order(&mut target.sources);
+        }
+        project
+    }
+
+    fn apply(
+        &mut self,
+        command: &str,
+        args: &[String],
+        file: &str,
+        vars: &mut HashMap<String, String>,
+        pending: &mut Vec<(String, Vec<String>, Vec<String>)>,
+    ) {
+        let Some(first) = args.first().cloned() else { return };
+        match command {
+            "set" => {
+                let values = &args[1..];
+                match values.iter().position(|a| a == "CACHE") {
+                    // A cache entry never overrides a normal variable of the same name.
+                    Some(i) => {
+                        vars.entry(first).or_insert_with(|| values[..i].join(";"));
+                    }
+                    // Sets the variable in the parent directory only.
+                    None if values.last().is_some_and(|a| a == "PARENT_SCOPE") => {}
+                    None if values.is_empty() => {
+                        vars.remove(&first);
+                    }
+                    None => {
+                        vars.insert(first, values.join(";"));
+                    }
+                }
+            }
+            "project" => {
+                let version = keyword_value(args, "VERSION");
+                vars.insert("PROJECT_NAME".into(), first.clone());
+                vars.entry("CMAKE_PROJECT_NAME".into()).or_insert_with(|| first.clone());
+                if let Some(v) = &version {
+                    vars.insert("PROJECT_VERSION".into(), v.clone());
+                }
+                self.projects.push((first, version, file.to_string()));
+            }
+            "find_package" => {
+                let rest = &args[1..];
+                let version = rest.first().filter(|a| a.starts_with(|c: char| c.is_ascii_digit())).cloned();
+                // `REQUIRED a b` and `COMPONENTS a b` both list components.
+                let mut components: Vec<String> = Vec::new();
+                let mut collecting = false;
+                for arg in rest {
+                    if FIND_PACKAGE_KEYWORDS.contains(&arg.as_str()) {
+                        collecting = matches!(arg.as_str(), "REQUIRED" | "COMPONENTS" | "OPTIONAL_COMPONENTS");
+                    } else if collecting {
+                        components.push(arg.clone());
+                    }
+                }
+                self.packages.push(FindPackage {
+                    name: first,
+                    version,
+                    components,
+                    required: args.iter().any(|a| a == "REQUIRED"),
+                    file: file.to_string(),
+                });
+            }
+            "add_executable" | "add_library" => {
+                let rest = &args[1..];
+                let kind = if let Some(i) = rest.iter().position(|a| a == "ALIAS") {
+                    format!("alias of {}", rest.get(i + 1).map(String::as_str).unwrap_or("?"))
+                } else if rest.iter().any(|a| a == "IMPORTED") {
+                    format!("imported {}", if command == "add_executable" { "executable" } else { "library" })
+                } else if command == "add_executable" {
+                    "executable".to_string()
+                } else {
+                    match rest.iter().find(|a| LIBRARY_KINDS.contains(&a.as_str())) {
+                        Some(kind) => format!("library {}", kind),
+                        None => "library".to_string(),
+                    }
+                };
+                let sources = rest
+                    .iter()
+                    .filter(|a| !is_target_keyword(a) && !kind.starts_with("alias"))
+                    .cloned()
+                    .collect();
+                self.targets.push(CMakeTarget { name: first, kind, sources, links: Vec::new(), file: file.to_string() });
+            }
+            "target_sources" | "target_link_libraries" => {
+                let items: Vec<String> = args[1..]
+                    .iter()
+                    .filter(|a| !VISIBILITY_KEYWORDS.contains(&a.as_str()) && !matches!(a.as_str(), "debug" | "optimized" | "general"))
+                    .filter(|a| !a.starts_with("$<") && !a.starts_with('-'))
+                    .cloned()
+                    .collect();
+                let (sources, links) = if command == "target_sources" { (items, Vec::new()) } else { (Vec::new(), items) };
+                pending.push((first, sources, links));
+            }
+            "fetchcontent_declare" | "externalproject_add" => {
+                let origin = if let Some(repo) = keyword_value(args, "GIT_REPOSITORY") {
+                    match keyword_value(args, "GIT_TAG") {
+                        Some(tag) => format!("git {} @ {}", repo, tag),
+                        None => format!("git {}", repo),
+                    }
+                } else if let Some(url) = keyword_value(args, "URL") {
+                    format!("url {}", url)
+                } else if let Some(dir) = keyword_value(args, "SOURCE_DIR") {
+                    format!("source {}", dir)
+                } else {
+                    "(no source given)".to_string()
+                };
+                let via = if command == "fetchcontent_declare" { "FetchContent" } else { "ExternalProject" };
+                self.external.push(ExternalSource { name: first, via, origin, file: file.to_string() });
+            }
+            "add_subdirectory" => {
+                self.subdirectories.entry(file.to_string()).or_default().push(first);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.projects.is_empty()
+            && self.packages.is_empty()
+            && self.targets.is_empty()
+            && self.external.is_empty()
+            && self.subdirectories.is_empty()
+    }
+
+    /// Where a linked name comes from: another target, a `find_package`, or a fetched project.
+    fn link_origin(&self, link: &str) -> Option<String> {
+        if let Some(target) = self.targets.iter().find(|t| t.name == link) {
+            return Some(match target.kind.strip_prefix("alias of ") {
+                Some(real) => format!("target {}", real),
+                None => "target".to_string(),
+            });
+        }
+        let namespace = link.split("::").next().unwrap_or(link).to_ascii_lowercase();
+        if let Some(p) = self.packages.iter().find(|p| p.name.to_ascii_lowercase() == namespace) {
+            return Some(format!("find_package {}", p.name));
+        }
+        self.external
+            .iter()
+            .find(|e| e.name.to_ascii_lowercase() == namespace)
+            .map(|e| format!("{} {}", e.via, e.name))
+    }
+
+    pub fn render(&self) -> String {
+        let mut out: Vec<String> = Vec::new();
+        for (name, version, file) in &self.projects {
+            out.push(format!("Project: {}{}  ({})", name, version.as_ref().map(|v| format!(" {}", v)).unwrap_or_default(), file));
+        }
+        if !self.subdirectories.is_empty() {
+            out.push("Subdirectories:".to_string());
+            for (file, dirs) in &self.subdirectories {
+                out.push(format!("  {} → {}", file, dirs.join(", ")));
+            }
+        }
+        if !self.packages.is_empty() {
+            out.push("Packages (find_package):".to_string());
+            for p in &self.packages {
+                let mut line = format!("  - {}", p.name);
+                if let Some(v) = &p.version {
+                    line.push_str(&format!(" {}", v));
+                }
+                line.push_str(if p.required { " REQUIRED" } else { " optional" });
+                if !p.components.is_empty() {
+                    line.push_str(&format!(" COMPONENTS {}", p.components.join(" ")));
+                }
+                out.push(format!("{}  ({})", line, p.file));
+            }
+        }
+        if !self.external.is_empty() {
+            out.push("Fetched sources (FetchContent / ExternalProject):".to_string());
+            for e in &self.external {
+                out.push(format!("  - {}  {}  ({}, {})", e.name, e.origin, e.via, e.file));
+            }
+        }
+        if !self.targets.is_empty() {
+            out.push(format!("Targets ({}):", self.targets.len()));
+            for t in &self.targets {
+                let mut line = format!("  {} [{}]  ({})", t.name, t.kind, t.file);
+                if !t.sources.is_empty() {
+                    let shown: Vec<&str> = t.sources.iter().take(MAX_LISTED_SOURCES).map(String::as_str).collect();
+                    line.push_str(&format!(" — {} source{}: {}", t.sources.len(), if t.sources.len() == 1 { "" } else { "s" }, shown.join(", ")));
+                    if t.sources.len() > MAX_LISTED_SOURCES {
+                        line.push_str(", …");
+                    }
+                }
+                out.push(line);
+                if !t.links.is_empty() {
+                    let links: Vec<String> = t
+                        .links
+                        .iter()
+                        .map(|l| match self.link_origin(l) {
+                            Some(origin) => format!("{} ({})", l, origin),
+                            None => l.clone(),
+                        })
+                        .collect();
+                    out.push(format!("      links → {}", links.join(", ")));
+                }
+            }
+        }
+        out.join("\n")
+    }
+}
+
+fn is_target_keyword(arg: &str) -> bool {
+    LIBRARY_KINDS.contains(&arg) || matches!(arg, "WIN32" | "MACOSX_BUNDLE" | "EXCLUDE_FROM_ALL" | "IMPORTED" | "GLOBAL")
+}
+
+/// The argument following `keyword`.
+fn keyword_value(args: &[String], keyword: &str) -> Option<String> {
+    args.iter().position(|a| a == keyword).and_then(|i| args.get(i + 1)).cloned()
+}
+
+fn dedup_keep_order(items: &mut Vec<String>) {
+    let mut seen = std::collections::HashSet::new();
+    items.retain(|i| seen.insert(i.clone()));
+}
+
+/// `${VAR}` references to known variables; unknown ones are left as written.
+fn substitute(arg: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = arg.to_string();
+    let mut from = 0;
+    // Bounded, so a variable that refers to itself cannot loop.
+    for _ in 0..32 {
+        let Some(start) = out[from..].find("${").map(|i| from + i) else { break };
+        let Some(len) = out[start..].find('}') else { break };
+        match vars.get(&out[start + 2..start + len]) {
+            Some(value) => out.replace_range(start..start + len + 1, value),
+            None => from = start + len + 1,
+        }
+    }
+    out
+}
+
+fn display_path(path: &Path) -> String {
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Command name and its arguments with quotes and brackets removed and variables substituted.
+fn command_args(node: Node, content: &str, vars: &HashMap<String, String>) -> Option<(String, Vec<String>)> {
+    let name = node.child(0).filter(|n| n.kind() == "identifier")?.utf8_text(content.as_bytes()).ok()?;
+    let mut args = Vec::new();
+    let mut stack = vec![node];
+    while let Some(current) = stack.pop() {
+        let mut cursor = current.walk();
+        let children: Vec<Node> = current.named_children(&mut cursor).collect();
+        for child in children.into_iter().rev() {
+            match child.kind() {
+                "argument" | "argument_list" => stack.push(child),
+                "quoted_argument" => args.push((child.start_byte(), substitute(&argument_text(child, content), vars))),
+                "bracket_argument" => args.push((child.start_byte(), argument_text(child, content))),
+                // Unquoted arguments are `;`-separated lists, also after substitution.
+                "unquoted_argument" => {
+                    for item in substitute(&argument_text(child, content), vars).split(';').filter(|a| !a.is_empty()) {
+                        args.push((child.start_byte(), item.to_string()));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    args.sort_by_key(|(start, _)| *start);
+    let args = args.into_iter().map(|(_, text)| text).collect();
+    Some((name.to_string(), args))
+}
+
+fn argument_text(node: Node, content: &str) -> String {
+    let text = node.utf8_text(content.as_bytes()).unwrap_or_default();
+    match node.kind() {
+        "quoted_argument" => text.trim_matches('"').to_string(),
+        // `[==[text]==]`
+        "bracket_argument" => {
+            let level = text[1..].chars().take_while(|&c| c == '=').count() + 2;
+            text.get(level..text.len().saturating_sub(level)).unwrap_or_default().to_string()
+        }
+        _ => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_target_graph_with_packages_and_fetched_sources() {
+        let root = r#"
+cmake_minimum_required(VERSION 3.20)
+project(demo VERSION 1.4.0 LANGUAGES CXX)
+set(FMT_TAG 10.2.1)
+find_package(Boost 1.80 REQUIRED COMPONENTS filesystem system)
+find_package(OpenSSL)
+include(FetchContent)
+FetchContent_Declare(
+  fmt
+  GIT_REPOSITORY https://github.com/fmtlib/fmt.git
+  GIT_TAG ${FMT_TAG}
+)
+FetchContent_MakeAvailable(fmt)
+add_subdirectory(src)
+add_subdirectory(tests)
+"#;
+        let src = r#"
+add_library(${PROJECT_NAME}_core STATIC core.cpp "util.cpp")
+add_library(demo::core ALIAS demo_core)
+add_executable(app main.cpp)
+target_link_libraries(app PRIVATE demo::core fmt::fmt Boost::filesystem pthread)
+target_sources(demo_core PRIVATE extra.cpp)
+"#;
+        let files = vec![
+            (PathBuf::from("./CMakeLists.txt"), root.to_string()),
+            (PathBuf::from("./src/CMakeLists.txt"), src.to_string()),
+        ];
+        let project = CMakeProject::parse(&files);
+
+        assert_eq!(project.projects, [("demo".to_string(), Some("1.4.0".to_string()), "CMakeLists.txt".to_string())]);
+        let boost = &project.packages[0];
+        assert_eq!((boost.version.as_deref(), boost.required), (Some("1.80"), true));
+        assert_eq!(boost.components, ["filesystem", "system"]);
+        assert!(!project.packages[1].required);
+        assert_eq!(project.external[0].origin, "git https://github.com/fmtlib/fmt.git @ 10.2.1");
+        assert_eq!(project.subdirectories["CMakeLists.txt"], ["src", "tests"]);
+
+        let rendered = project.render();
+        assert!(rendered.contains("  demo_core [library STATIC]  (src/CMakeLists.txt) — 3 sources: core.cpp, util.cpp, extra.cpp"));
+        assert!(rendered.contains(
+            "      links → demo::core (target demo_core), fmt::fmt (FetchContent fmt), Boost::filesystem (find_package Boost), pthread"
+        ));
+    }
+
+    #[test]
+    fn variables_expand_lists_and_stay_in_their_directory() {
+        let files = vec![
+            (PathBuf::from("CMakeLists.txt"), "set(SRCS main.cpp app.cpp)\nset(OPT on CACHE BOOL \"doc\")\nadd_executable(app ${SRCS} ${UNSET} ${OPT}.cpp)\n".to_string()),
+            (PathBuf::from("a/CMakeLists.txt"), "set(LOCAL a.cpp)\nadd_library(a ${LOCAL} ${SRCS})\n".to_string()),
+            (PathBuf::from("b/CMakeLists.txt"), "add_library(b ${LOCAL} b.cpp)\n".to_string()),
+            (PathBuf::from("cmake/deps.cmake"), "set(SRCS leaked.cpp)\n".to_string()),
+        ];
+        let project = CMakeProject::parse(&files);
+        let sources = |name: &str| project.targets.iter().find(|t| t.name == name).unwrap().sources.clone();
+        assert_eq!(sources("app"), ["main.cpp", "app.cpp", "${UNSET}", "on.cpp"]);
+        assert_eq!(sources("a"), ["a.cpp", "main.cpp", "app.cpp"]);
+        assert_eq!(sources("b"), ["${LOCAL}", "b.cpp"]);
+    }
+}
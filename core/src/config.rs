@@ -1,6 +1,11 @@
 // saccade/core/src/config.rs
 
 use crate::error::{Result, SaccadeError};
+use crate::module_graph::Granularity;
+use crate::parser::SkeletonLevel;
+use crate::pii::{PiiMode, PiiRule};
+use crate::registry::{default_language_dirs, LanguageDir};
+use crate::safety::SafetyMode;
 use regex::Regex;
 use std::path::PathBuf;
 
@@ -21,6 +26,35 @@ pub struct Config {
     pub code_only: bool,
     pub dry_run: bool,
     pub verbose: bool,
+    /// Dirs searched for extra grammars and `.scm` query overrides (later dirs win).
+    pub language_dirs: Vec<LanguageDir>,
+    pub skeleton_level: SkeletonLevel,
+    /// Cap on estimated Stage-2 tokens; lowest-ranked files are dropped first.
+    pub stage2_token_budget: Option<usize>,
+    /// Module unit for the GRAPH section.
+    pub graph_granularity: Granularity,
+    /// Layer order for the GRAPH section, top first (e.g. `["cli", "core"]`).
+    pub layers: Vec<String>,
+    /// Task description for the FOCUS section (`--focus`).
+    pub focus: Option<String>,
+    /// How many files FOCUS lists.
+    pub focus_top: usize,
+    /// Never spawn ecosystem tools; DEPS comes from lockfiles and manifests only.
+    pub offline: bool,
+    /// Wall-clock limit for each external tool run.
+    pub tool_timeout_secs: u64,
+    /// Layer-1 Manifest Density Score a file needs to become a manifest candidate.
+    pub manifest_mds_threshold: f32,
+    /// Final score (MDS × path context) a candidate needs to be listed in DEPS.
+    pub manifest_confidence_threshold: f32,
+    /// Replace secrets found in pack content with `<redacted:KIND>`.
+    pub redact_secrets: bool,
+    /// What to do with prompt-injection phrases and invisible characters besides listing them in SAFETY.
+    pub safety_mode: SafetyMode,
+    /// Emails, phones, IPs, internal hostnames and home paths: off, redact or pseudonymize.
+    pub pii_mode: PiiMode,
+    /// Custom PII rules (`NAME=REGEX`), applied after the built-ins.
+    pub pii_rules: Vec<PiiRule>,
 }
 
 impl Config {
@@ -34,6 +68,21 @@ impl Config {
             code_only: false,
             dry_run: false,
             verbose: false,
+            language_dirs: default_language_dirs(),
+            skeleton_level: SkeletonLevel::Full,
+            stage2_token_budget: None,
+            graph_granularity: Granularity::Directory,
+            layers: Vec::new(),
+            focus: None,
+            focus_top: 10,
+            offline: false,
+            tool_timeout_secs: 30,
+            manifest_mds_threshold: MDS_CANDIDACY_THRESHOLD,
+            manifest_confidence_threshold: FINAL_CONFIDENCE_THRESHOLD,
+            redact_secrets: true,
+            safety_mode: SafetyMode::Flag,
+            pii_mode: PiiMode::Pseudonymize,
+            pii_rules: Vec::new(),
         }
     }
 
@@ -46,6 +95,35 @@ impl Config {
             });
         }
 
+        if self.focus.is_some() && self.focus_top == 0 {
+            return Err(SaccadeError::InvalidConfig {
+                field: "focus_top".to_string(),
+                value: "0".to_string(),
+                reason: "must be at least 1 when --focus is set".to_string(),
+            });
+        }
+
+        if self.tool_timeout_secs == 0 {
+            return Err(SaccadeError::InvalidConfig {
+                field: "tool_timeout_secs".to_string(),
+                value: "0".to_string(),
+                reason: "must be at least 1 second".to_string(),
+            });
+        }
+
+        for (field, value) in [
+            ("manifest_mds_threshold", self.manifest_mds_threshold),
+            ("manifest_confidence_threshold", self.manifest_confidence_threshold),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(SaccadeError::InvalidConfig {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                    reason: "must be a non-negative number".to_string(),
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -69,8 +147,14 @@ pub const PRUNE_DIRS: &[&str] = &[
     ".git", "node_modules", "dist", "build", "target", "gen", "schemas",
     "tests", "test", "__tests__", ".venv", "venv", ".tox", ".cache",
     "coverage", "vendor", "third_party",
+    // Tool caches and build output: CMake FetchContent, vcpkg, Gradle, Dart, Zig, Cabal, Stack, SwiftPM.
+    "_deps", "vcpkg_installed", ".gradle", ".dart_tool", "zig-cache", ".zig-cache", "zig-out",
+    "dist-newstyle", ".stack-work", ".build",
 ];
 
+/// Generated directory name prefixes (`bazel-out`, `cmake-build-debug`), pruned like `PRUNE_DIRS`.
+pub const PRUNE_DIR_PREFIXES: &[&str] = &["bazel-", "cmake-build-"];
+
 pub const BIN_EXT_PATTERN: &str = r"(?i)\.(png|jpe?g|gif|svg|ico|icns|webp|woff2?|ttf|otf|pdf|mp4|mov|mkv|avi|mp3|wav|flac|zip|gz|bz2|xz|7z|rar|jar|csv|tsv|parquet|sqlite|db|bin|exe|dll|so|dylib|pkl|onnx|torch|tgz|zst)$";
 
 pub const SECRET_PATTERN: &str = r"(?i)(^\.?env(\..*)?$|/\.?env(\..*)?$|(^|/)(id_rsa(\.pub)?|id_ed25519(\.pub)?|.*\.(pem|p12|jks|keystore|pfx))$)";
@@ -78,4 +162,84 @@ pub const SECRET_PATTERN: &str = r"(?i)(^\.?env(\..*)?$|/\.?env(\..*)?$|(^|/)(id
 // --- These must be public for the HeuristicFilter ---
 pub const CODE_EXT_PATTERN: &str = r"(?i)\.(c|h|cc|hh|cpp|hpp|rs|go|py|js|jsx|ts|tsx|java|kt|kts|rb|php|scala|cs|swift|m|mm|lua|sh|bash|zsh|fish|ps1|sql|html|xhtml|xml|xsd|xslt|yaml|yml|toml|ini|cfg|conf|json|ndjson|md|rst|tex|s|asm|cmake|gradle|proto|graphql|gql|nix|dart|scss|less|css)$";
 
-pub const CODE_BARE_PATTERN: &str = r"(?i)(Makefile|Dockerfile|dockerfile|CMakeLists\.txt|BUILD|WORKSPACE)$";
\ No newline at end of file
+pub const CODE_BARE_PATTERN: &str = r"(?i)(Makefile|GNUmakefile|\.mk|Dockerfile|dockerfile|CMakeLists\.txt|conanfile\.txt|BUILD|WORKSPACE|\.bazel|meson\.build|mix\.exs|\.cabal|cabal\.project|build\.zig|build\.zig\.zon|flake\.lock|Package\.resolved)$";
+
+// --- Manifest detection funnel (docs/dynamic-dependancy-detection.md) ---
+
+/// Layer 1: high-signal build/dependency keywords, matched case-insensitively.
+pub const PAMP_KEYWORDS: &[&str] = &[
+    // Generic
+    "dependency", "dependencies", "require", "version", "package",
+    "packages", "project", "include", "source", "library", "libraries",
+    // CMake
+    "find_package", "add_library", "add_executable", "target_link_libraries",
+    "cmake_minimum_required",
+    // Make
+    "gcc", "g++", "clang", ".PHONY", "target",
+    // Conan / Python
+    "conanfile", "self.requires",
+    // Java
+    "groupId", "artifactId", "implementation", "compile",
+    // Node.js
+    "devDependencies",
+];
+
+/// Layer 1: byte entropy above this marks binary or compressed content.
+pub const ENTROPY_THRESHOLD: f64 = 6.0;
+/// Layer 1: default `Config::manifest_mds_threshold`.
+pub const MDS_CANDIDACY_THRESHOLD: f32 = 0.05;
+/// Layer 3: default `Config::manifest_confidence_threshold`.
+pub const FINAL_CONFIDENCE_THRESHOLD: f32 = 0.1;
+
+/// Files the funnel never scores: source code and prose, whose keywords are incidental.
+pub const MANIFEST_SCAN_SKIP_PATTERN: &str = r"(?i)\.(c|h|cc|hh|cpp|hpp|rs|go|py|js|jsx|mjs|cjs|ts|tsx|java|kt|rb|php|scala|cs|swift|m|mm|lua|sh|bash|zsh|fish|ps1|sql|html|xhtml|css|scss|less|md|rst|tex|adoc|svg|lock|sum)$";
+
+/// Layer 2: Tree-sitter queries for manifest structures, as `(structure, grammar, query)`.
+/// Non-standard manifests have no grammar of their own, so each is parsed with the bundled
+/// grammar closest to its shape: call syntax as CMake or Python, sections, `key = value` /
+/// `key: value` rows, JSON objects and quoted coordinates as Python. A file is valid when
+/// any query matches; the first match names the structure.
+pub const AST_VALIDATION_QUERIES: &[(&str, &str, &str)] = &[
+    (
+        "build call",
+        "cmake",
+        r#"(normal_command (identifier) @name (#match? @name "^(find_package|add_library|add_executable|target_link_libraries|pkg_check_modules|requires|tool_requires|dependency|bazel_dep|implementation|api|compile|depends_on)$"))"#,
+    ),
+    (
+        "build call",
+        "python",
+        r#"(call function: [(identifier) @name (attribute attribute: (identifier) @name)] (#match? @name "^(requires|tool_requires|build_requires|test_requires|dependency|declare_dependency|bazel_dep|implementation|api|compile|depends_on|find_package|pkg_check_modules)$"))"#,
+    ),
+    (
+        "dependency section",
+        "python",
+        r#"((list) @section (#match? @section "(?i)^\\[\\s*\"?(dependencies|dev-dependencies|build-dependencies|requires|tool_requires|build_requires|packages|libraries|libs|deps)\"?\\s*\\]$"))"#,
+    ),
+    (
+        "dependency key",
+        "python",
+        r#"(assignment left: (identifier) @key (#match? @key "(?i)^(dependencies|devDependencies|requires|depends|deps|packages|libraries)$"))"#,
+    ),
+    (
+        "dependency key",
+        "python",
+        r#"(pair key: (string) @key (#match? @key "^[\"'](dependencies|devDependencies|peerDependencies|requires|depends|deps|packages|libraries)[\"']$"))"#,
+    ),
+    ("gradle coordinate", "python", r#"((string) @coordinate (#match? @coordinate "^[\"'][\\w.-]+:[\\w.-]+:\\d[\\w.-]*[\"']$"))"#),
+];
+
+/// Layer 2, after `AST_VALIDATION_QUERIES`: shapes no bundled grammar parses (Makefile
+/// rules, requirement pins, POM coordinates), as line-anchored patterns.
+pub const MANIFEST_STRUCTURE_RULES: &[(&str, &str)] = &[
+    ("make rule", r"(?m)^[\w./$()%-]+\s*:[^=\n]*\n\t\S"),
+    ("pinned requirement", r"(?m)^\s*[A-Za-z][\w.+-]*\s*(==|>=|<=|~=|\^|@|/)\s*v?\d+(\.\d+)+"),
+    ("maven coordinates", r"<(groupId|artifactId)>"),
+];
+
+/// Layer 3: path multipliers; every matching rule applies.
+pub const PATH_CONTEXT_RULES: &[(&str, f32)] = &[
+    (r"^[^/]+$", 1.5),
+    (r"(^|/)(build|cmake|config|ci|tools|deps|packaging)/", 1.25),
+    (r"(^|/)(tests?|__tests__|spec)/", 0.5),
+    (r"(^|/)(docs?|assets|img|images|examples?|samples?|fixtures|testdata)/", 0.25),
+];
@@ -0,0 +1,280 @@
+// saccade/core/src/cpp_manifests.rs
+//
+// C/C++ package manifests for DEPS:
+//   conanfile.py   `requires`/`tool_requires`/`build_requires`/`test_requires` attributes
+//                  (string, tuple or list) and `self.requires(...)`-style calls, via Tree-sitter
+//   conanfile.txt  `[requires]`, `[tool_requires]`, `[build_requires]`, `[test_requires]` sections
+//   vcpkg.json     `dependencies` (strings or objects) and `overrides`
+// Each yields `DeclaredDependency` rows, rendered by `build_manifests::BuildManifest`.
+
+use crate::build_manifests::DeclaredDependency;
+use serde_json::Value;
+use tree_sitter::{Node, Parser, Query, QueryCursor, QueryMatch};
+
+const CONAN_REQUIREMENT_KINDS: &[&str] = &["requires", "tool_requires", "build_requires", "test_requires", "python_requires"];
+
+const CONANFILE_PY_QUERY: &str = r#"
+(assignment
+  left: (identifier) @name
+  right: (_) @value)
+
+(call
+  function: (attribute
+    object: (identifier) @object
+    attribute: (identifier) @method)
+  arguments: (argument_list) @args)
+"#;
+
+/// `zlib/1.2.13@user/channel#rev` → name, version and notes.
+fn conan_reference(reference: &str, kind: &str) -> Option<DeclaredDependency> {
+    let reference = reference.trim();
+    if reference.is_empty() {
+        return None;
+    }
+    let (reference, revision) = match reference.split_once('#') {
+        Some((r, rev)) => (r, Some(format!("#{}", rev))),
+        None => (reference, None),
+    };
+    let (reference, user_channel) = match reference.split_once('@') {
+        Some((r, uc)) => (r, Some(format!("@{}", uc))),
+        None => (reference, None),
+    };
+    let (name, version) = match reference.split_once('/') {
+        Some((name, version)) => (name, Some(version.to_string())),
+        None => (reference, None),
+    };
+    Some(DeclaredDependency {
+        name: name.to_string(),
+        constraint: version,
+        kind: kind.to_string(),
+        notes: user_channel.into_iter().chain(revision).collect(),
+    })
+}
+
+pub fn parse_conanfile_txt(content: &str) -> Vec<DeclaredDependency> {
+    let mut out = Vec::new();
+    let mut section = String::new();
+    for line in content.lines() {
+        let line = strip_conan_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            section = line.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+            continue;
+        }
+        if CONAN_REQUIREMENT_KINDS.contains(&section.as_str()) {
+            out.extend(conan_reference(line, &section));
+        }
+    }
+    out
+}
+
+/// A `#` starts a comment at the line start or after whitespace; `zlib/1.2.13#rev` keeps its revision.
+fn strip_conan_comment(line: &str) -> &str {
+    let mut prev = None;
+    for (i, c) in line.char_indices() {
+        if c == '#' && prev.is_none_or(char::is_whitespace) {
+            return &line[..i];
+        }
+        prev = Some(c);
+    }
+    line
+}
+
+pub fn parse_conanfile_py(content: &str) -> Vec<DeclaredDependency> {
+    let language = tree_sitter_python::language();
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else { return Vec::new() };
+    let Ok(query) = Query::new(&language, CONANFILE_PY_QUERY) else { return Vec::new() };
+    let text = |node: Node| node.utf8_text(content.as_bytes()).unwrap_or_default();
+
+    let mut out = Vec::new();
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(&query, tree.root_node(), content.as_bytes()) {
+        if let (Some(name), Some(value)) = (capture(&query, &m, "name"), capture(&query, &m, "value")) {
+            // Class attribute: `requires = "a/1.0"` or `requires = ("a/1.0", "b/2.0")`.
+            let kind = text(name);
+            if CONAN_REQUIREMENT_KINDS.contains(&kind) {
+                out.extend(string_literals(value, content).iter().filter_map(|r| conan_reference(r, kind)));
+            }
+        } else if let (Some(object), Some(method), Some(args)) =
+            (capture(&query, &m, "object"), capture(&query, &m, "method"), capture(&query, &m, "args"))
+        {
+            // `self.requires("a/1.0", override=True)` inside `requirements()`.
+            let kind = text(method);
+            if text(object) != "self" || !CONAN_REQUIREMENT_KINDS.contains(&kind) {
+                continue;
+            }
+            let mut walker = args.walk();
+            let mut reference = None;
+            let mut notes = Vec::new();
+            for arg in args.named_children(&mut walker) {
+                match arg.kind() {
+                    "string" if reference.is_none() => reference = string_literals(arg, content).into_iter().next(),
+                    "keyword_argument" => notes.push(text(arg).split_whitespace().collect::<String>()),
+                    _ => {}
+                }
+            }
+            if let Some(mut dep) = reference.and_then(|r| conan_reference(&r, kind)) {
+                dep.notes.extend(notes);
+                out.push(dep);
+            }
+        }
+    }
+    out
+}
+
+fn capture<'t>(query: &Query, m: &QueryMatch<'_, 't>, name: &str) -> Option<Node<'t>> {
+    let index = query.capture_index_for_name(name)?;
+    m.captures.iter().find(|c| c.index == index).map(|c| c.node)
+}
+
+/// String literals in `node`: itself if a string, or the elements of a tuple/list.
+fn string_literals(node: Node, content: &str) -> Vec<String> {
+    let unquote = |n: Node| {
+        let raw = n.utf8_text(content.as_bytes()).unwrap_or_default();
+        raw.trim_start_matches(|c: char| c.is_ascii_alphabetic()).trim_matches(|c| c == '"' || c == '\'').to_string()
+    };
+    match node.kind() {
+        "string" => vec![unquote(node)],
+        "tuple" | "list" | "parenthesized_expression" => {
+            let mut walker = node.walk();
+            node.named_children(&mut walker).filter(|c| c.kind() == "string").map(unquote).collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+pub fn parse_vcpkg_json(json: &Value) -> Vec<DeclaredDependency> {
+    let mut out = Vec::new();
+    for dep in json.get("dependencies").and_then(Value::as_array).into_iter().flatten() {
+        let dependency = match dep {
+            Value::String(name) => {
+                DeclaredDependency { name: name.clone(), constraint: None, kind: "dependency".to_string(), notes: Vec::new() }
+            }
+            Value::Object(obj) => {
+                let Some(name) = obj.get("name").and_then(Value::as_str) else { continue };
+                let mut notes = Vec::new();
+                if let Some(features) = obj.get("features").and_then(Value::as_array) {
+                    let names: Vec<&str> = features
+                        .iter()
+                        .filter_map(|f| f.as_str().or_else(|| f.get("name")?.as_str()))
+                        .collect();
+                    if !names.is_empty() {
+                        notes.push(format!("features: {}", names.join(" ")));
+                    }
+                }
+                if obj.get("default-features").and_then(Value::as_bool) == Some(false) {
+                    notes.push("no default features".to_string());
+                }
+                if let Some(platform) = obj.get("platform").and_then(Value::as_str) {
+                    notes.push(format!("platform: {}", platform));
+                }
+                let kind = if obj.get("host").and_then(Value::as_bool) == Some(true) { "host" } else { "dependency" };
+                DeclaredDependency {
+                    name: name.to_string(),
+                    constraint: obj.get("version>=").and_then(Value::as_str).map(|v| format!(">={}", v)),
+                    kind: kind.to_string(),
+                    notes,
+                }
+            }
+            _ => continue,
+        };
+        out.push(dependency);
+    }
+    for o in json.get("overrides").and_then(Value::as_array).into_iter().flatten() {
+        let Some(name) = o.get("name").and_then(Value::as_str) else { continue };
+        let version = ["version", "version-string", "version-semver", "version-date"]
+            .iter()
+            .find_map(|key| o.get(*key).and_then(Value::as_str));
+        out.push(DeclaredDependency {
+            name: name.to_string(),
+            constraint: version.map(|v| format!("={}", v)),
+            kind: "override".to_string(),
+            notes: Vec::new(),
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(deps: &[DeclaredDependency]) -> Vec<(&str, Option<&str>, &str)> {
+        deps.iter().map(|d| (d.name.as_str(), d.constraint.as_deref(), d.kind.as_str())).collect()
+    }
+
+    #[test]
+    fn conanfile_py_attributes_and_calls() {
+        let py = r#"
+from conan import ConanFile
+
+class App(ConanFile):
+    requires = ("zlib/1.2.13", "fmt/[>=10 <11]@acme/stable")
+    tool_requires = "cmake/3.27.1"
+
+    def requirements(self):
+        self.requires("boost/1.83.0", override=True)
+        if self.options.with_ssl:
+            self.requires("openssl/3.1.4#a1b2")
+
+    def build_requirements(self):
+        self.test_requires("gtest/1.14.0")
+"#;
+        let deps = parse_conanfile_py(py);
+        assert_eq!(
+            rows(&deps),
+            [
+                ("zlib", Some("1.2.13"), "requires"),
+                ("fmt", Some("[>=10 <11]"), "requires"),
+                ("cmake", Some("3.27.1"), "tool_requires"),
+                ("boost", Some("1.83.0"), "requires"),
+                ("openssl", Some("3.1.4"), "requires"),
+                ("gtest", Some("1.14.0"), "test_requires"),
+            ]
+        );
+        assert_eq!(deps[1].notes, ["@acme/stable"]);
+        assert_eq!(deps[3].notes, ["override=True"]);
+        assert_eq!(deps[4].notes, ["#a1b2"]);
+    }
+
+    #[test]
+    fn conanfile_txt_and_vcpkg_manifest() {
+        let txt = "[requires]\nzlib/1.2.13\npoco/1.12.4 # net\nopenssl/3.2.0#9f2c1e # pinned\n# boost/1.84.0\n\n[tool_requires]\nninja/1.11.1\n\n[generators]\nCMakeDeps\n";
+        let deps = parse_conanfile_txt(txt);
+        assert_eq!(
+            rows(&deps),
+            [
+                ("zlib", Some("1.2.13"), "requires"),
+                ("poco", Some("1.12.4"), "requires"),
+                ("openssl", Some("3.2.0"), "requires"),
+                ("ninja", Some("1.11.1"), "tool_requires"),
+            ]
+        );
+        assert_eq!(deps[2].notes, ["#9f2c1e"]);
+
+        let vcpkg: Value = serde_json::from_str(
+            r#"{"name": "app", "builtin-baseline": "3426db05",
+                "dependencies": ["fmt", {"name": "curl", "version>=": "8.4.0", "features": ["ssl"], "platform": "!windows"},
+                                 {"name": "vcpkg-cmake", "host": true}],
+                "overrides": [{"name": "zlib", "version": "1.2.13"}]}"#,
+        )
+        .unwrap();
+        let deps = parse_vcpkg_json(&vcpkg);
+        assert_eq!(
+            rows(&deps),
+            [
+                ("fmt", None, "dependency"),
+                ("curl", Some(">=8.4.0"), "dependency"),
+                ("vcpkg-cmake", None, "host"),
+                ("zlib", Some("=1.2.13"), "override"),
+            ]
+        );
+        assert_eq!(deps[1].notes, ["features: ssl", "platform: !windows"]);
+    }
+}
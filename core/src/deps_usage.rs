@@ -0,0 +1,645 @@
+// saccade/core/src/deps_usage.rs
+//
+// DEPS-USAGE: which files use each declared third-party dependency, plus
+// declared dependencies nothing imports and imports nothing declares.
+//
+// Declared = manifests among the kept files (Cargo.toml, package.json,
+// pyproject.toml, requirements*.txt, go.mod) and direct entries of root
+// lockfiles. Internal workspace packages and path dependencies are not
+// third-party and are left out. Usage per language:
+//   Rust    any `crate_name::` path on a line (covers `use`, qualified calls, macros)
+//   JS/TS   bare import/require specifiers (`react`, `@scope/pkg/sub`)
+//   Python  top-level module of `import` / `from` (with common dist-name aliases)
+//   Go      import paths under a required module path
+
+use crate::imports::{self, ImportLang};
+use crate::lockfiles::{
+    node_requested, normalize_python_name, parse_cargo_manifest, parse_go_modules, parse_requirements_txt,
+    pyproject_requirements, LockFormat, Lockfile,
+};
+use crate::manifest_text::{go_module_path, inline_table_value, toml_entries, toml_value, unquote};
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_FILE_SIZE_FOR_USAGE: u64 = 1024 * 1024; // 1 MB
+const MAX_FILES_PER_DEPENDENCY: usize = 12;
+const MAX_SITES_PER_UNDECLARED: usize = 5;
+
+static RUST_PATH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?:^|[^A-Za-z0-9_:])([A-Za-z_][A-Za-z0-9_]*)::").unwrap());
+
+const RUST_BUILTIN_CRATES: &[&str] = &["std", "core", "alloc", "crate", "self", "super", "proc_macro", "test", "Self"];
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert", "async_hooks", "buffer", "child_process", "cluster", "console", "constants", "crypto", "dgram",
+    "diagnostics_channel", "dns", "domain", "events", "fs", "http", "http2", "https", "inspector", "module", "net",
+    "os", "path", "perf_hooks", "process", "punycode", "querystring", "readline", "repl", "stream",
+    "string_decoder", "sys", "timers", "tls", "trace_events", "tty", "url", "util", "v8", "vm", "wasi",
+    "worker_threads", "zlib",
+];
+
+const PYTHON_STDLIB: &[&str] = &[
+    "__future__", "abc", "argparse", "array", "ast", "asyncio", "atexit", "base64", "binascii", "bisect",
+    "builtins", "bz2", "calendar", "cmath", "cmd", "codecs", "collections", "colorsys", "concurrent",
+    "configparser", "contextlib", "contextvars", "copy", "copyreg", "cProfile", "csv", "ctypes", "curses",
+    "dataclasses", "datetime", "dbm", "decimal", "difflib", "dis", "doctest", "email", "encodings", "enum",
+    "errno", "faulthandler", "fcntl", "filecmp", "fileinput", "fnmatch", "fractions", "ftplib", "functools",
+    "gc", "getopt", "getpass", "gettext", "glob", "graphlib", "grp", "gzip", "hashlib", "heapq", "hmac", "html",
+    "http", "imaplib", "importlib", "inspect", "io", "ipaddress", "itertools", "json", "keyword", "linecache",
+    "locale", "logging", "lzma", "mailbox", "marshal", "math", "mimetypes", "mmap", "multiprocessing",
+    "netrc", "numbers", "operator", "optparse", "os", "pathlib", "pdb", "pickle", "pkgutil", "platform",
+    "plistlib", "poplib", "posixpath", "pprint", "profile", "pstats", "pty", "pwd", "py_compile", "queue",
+    "quopri", "random", "re", "readline", "reprlib", "resource", "rlcompleter", "runpy", "sched", "secrets",
+    "select", "selectors", "shelve", "shlex", "shutil", "signal", "site", "smtplib", "socket", "socketserver",
+    "sqlite3", "ssl", "stat", "statistics", "string", "stringprep", "struct", "subprocess", "symtable", "sys",
+    "sysconfig", "syslog", "tarfile", "tempfile", "termios", "textwrap", "threading", "time", "timeit",
+    "tkinter", "token", "tokenize", "tomllib", "trace", "traceback", "tracemalloc", "tty", "turtle", "types",
+    "typing", "unicodedata", "unittest", "urllib", "uuid", "venv", "warnings", "wave", "weakref", "webbrowser",
+    "winreg", "wsgiref", "xml", "xmlrpc", "zipapp", "zipfile", "zipimport", "zlib", "zoneinfo",
+];
+
+/// Import name → distribution name, where they differ.
+const PYTHON_IMPORT_ALIASES: &[(&str, &str)] = &[
+    ("attr", "attrs"),
+    ("bs4", "beautifulsoup4"),
+    ("cv2", "opencv-python"),
+    ("dateutil", "python-dateutil"),
+    ("docx", "python-docx"),
+    ("dotenv", "python-dotenv"),
+    ("jwt", "pyjwt"),
+    ("magic", "python-magic"),
+    ("OpenSSL", "pyopenssl"),
+    ("PIL", "pillow"),
+    ("serial", "pyserial"),
+    ("sklearn", "scikit-learn"),
+    ("yaml", "pyyaml"),
+    ("Crypto", "pycryptodome"),
+    ("psycopg2", "psycopg2-binary"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Ecosystem {
+    Rust,
+    Node,
+    Python,
+    Go,
+}
+
+impl Ecosystem {
+    fn of(lang: ImportLang) -> Self {
+        match lang {
+            ImportLang::Rust => Ecosystem::Rust,
+            ImportLang::JavaScript => Ecosystem::Node,
+            ImportLang::Python => Ecosystem::Python,
+            ImportLang::Go => Ecosystem::Go,
+        }
+    }
+}
+
+impl std::fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Ecosystem::Rust => "rust",
+            Ecosystem::Node => "node",
+            Ecosystem::Python => "python",
+            Ecosystem::Go => "go",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyUsage {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// Manifests/lockfiles declaring it.
+    pub declared_in: Vec<String>,
+    /// `(file, importing lines)`, most lines first.
+    pub files: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndeclaredImport {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    /// `(file, line)` of each import.
+    pub sites: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Default)]
+pub struct DepsUsage {
+    pub used: Vec<DependencyUsage>,
+    /// Direct dependencies with no importing file among the kept files.
+    pub unused: Vec<DependencyUsage>,
+    pub undeclared: Vec<UndeclaredImport>,
+}
+
+#[derive(Debug, Default)]
+struct Declared {
+    /// Third-party name → where it is declared and whether any declaration is direct.
+    deps: BTreeMap<(Ecosystem, String), (BTreeSet<String>, bool)>,
+    /// Workspace package names (Rust crates, npm names, Python projects, Go modules).
+    internal: HashSet<(Ecosystem, String)>,
+    /// Ecosystems with at least one manifest; only these get "undeclared" checks.
+    ecosystems: HashSet<Ecosystem>,
+}
+
+impl Declared {
+    fn add(&mut self, ecosystem: Ecosystem, name: &str, source: &str, direct: bool) {
+        let entry = self.deps.entry((ecosystem, name.to_string())).or_default();
+        entry.0.insert(source.to_string());
+        entry.1 |= direct;
+    }
+}
+
+impl DepsUsage {
+    /// Manifests and sources among `files`, plus direct dependencies from lockfiles in `.`.
+    pub fn analyze(files: &[PathBuf]) -> Self {
+        let read = |p: &PathBuf| {
+            let small = fs::metadata(p).is_ok_and(|m| m.len() <= MAX_FILE_SIZE_FOR_USAGE);
+            small.then(|| fs::read_to_string(p).ok().map(|c| (p.clone(), c))).flatten()
+        };
+        let manifests: Vec<(PathBuf, String)> =
+            files.iter().filter(|p| is_manifest(p)).filter_map(read).collect();
+        let sources: Vec<(PathBuf, String)> =
+            files.par_iter().filter(|p| imports::import_lang(p).is_some()).filter_map(read).collect();
+        let locked: Vec<Lockfile> =
+            LockFormat::ALL.iter().filter_map(|&format| Lockfile::read(Path::new("."), format)).collect();
+        Self::from_sources(&manifests, &locked, &sources)
+    }
+
+    pub fn from_sources(manifests: &[(PathBuf, String)], locked: &[Lockfile], sources: &[(PathBuf, String)]) -> Self {
+        let declared = declared_dependencies(manifests, locked);
+        let local_modules = local_module_names(sources);
+
+        // (ecosystem, declared name) → file → lines
+        let mut usage: HashMap<(Ecosystem, String), BTreeMap<String, usize>> = HashMap::new();
+        let mut undeclared: BTreeMap<(Ecosystem, String), Vec<(String, usize)>> = BTreeMap::new();
+        let per_file: Vec<(String, Vec<Hit>)> = sources
+            .par_iter()
+            .map(|(path, content)| {
+                (path.to_string_lossy().replace('\\', "/"), file_hits(path, content, &declared, &local_modules))
+            })
+            .collect();
+        for (file, hits) in per_file {
+            for hit in hits {
+                match hit {
+                    Hit::Declared(key) => *usage.entry(key).or_default().entry(file.clone()).or_default() += 1,
+                    Hit::Undeclared(key, line) => undeclared.entry(key).or_default().push((file.clone(), line)),
+                }
+            }
+        }
+
+        let mut report = DepsUsage::default();
+        for (key, (sources, direct)) in declared.deps {
+            let mut files: Vec<(String, usize)> = usage.remove(&key).unwrap_or_default().into_iter().collect();
+            files.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            let entry =
+                DependencyUsage { ecosystem: key.0, name: key.1, declared_in: sources.into_iter().collect(), files };
+            if !entry.files.is_empty() {
+                report.used.push(entry);
+            } else if direct {
+                report.unused.push(entry);
+            }
+        }
+        report.used.sort_by(|a, b| {
+            let lines = |u: &DependencyUsage| u.files.iter().map(|f| f.1).sum::<usize>();
+            (a.ecosystem, std::cmp::Reverse(a.files.len()), std::cmp::Reverse(lines(a)), &a.name).cmp(&(
+                b.ecosystem,
+                std::cmp::Reverse(b.files.len()),
+                std::cmp::Reverse(lines(b)),
+                &b.name,
+            ))
+        });
+        report.undeclared = undeclared
+            .into_iter()
+            .map(|((ecosystem, name), sites)| UndeclaredImport { ecosystem, name, sites })
+            .collect();
+        report
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.used.is_empty() && self.unused.is_empty() && self.undeclared.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("========================================\n");
+        out.push_str("DEPENDENCY USAGE (declared dependency → importing files)\n");
+        out.push_str("========================================\n\n");
+        if self.used.is_empty() {
+            out.push_str("(no imports of declared dependencies found)\n");
+        }
+        for dep in &self.used {
+            let lines: usize = dep.files.iter().map(|f| f.1).sum();
+            out.push_str(&format!(
+                "[{}] {} — {} file{}, {} line{}  (declared in {})\n",
+                dep.ecosystem,
+                dep.name,
+                dep.files.len(),
+                plural(dep.files.len()),
+                lines,
+                plural(lines),
+                dep.declared_in.join(", ")
+            ));
+            let shown: Vec<String> = dep
+                .files
+                .iter()
+                .take(MAX_FILES_PER_DEPENDENCY)
+                .map(|(file, n)| format!("{} ({})", file, n))
+                .collect();
+            out.push_str(&format!("    {}", shown.join(", ")));
+            if dep.files.len() > MAX_FILES_PER_DEPENDENCY {
+                out.push_str(&format!(", … {} more", dep.files.len() - MAX_FILES_PER_DEPENDENCY));
+            }
+            out.push('\n');
+        }
+
+        if !self.unused.is_empty() {
+            out.push_str("\nUNUSED DECLARED DEPENDENCIES (no import among kept files; pruned test dirs may use them)\n");
+            for dep in &self.unused {
+                out.push_str(&format!("  [{}] {}  ({})\n", dep.ecosystem, dep.name, dep.declared_in.join(", ")));
+            }
+        }
+        if !self.undeclared.is_empty() {
+            out.push_str("\nUNDECLARED IMPORTS (imported but not declared in any manifest)\n");
+            for import in &self.undeclared {
+                let sites: Vec<String> = import
+                    .sites
+                    .iter()
+                    .take(MAX_SITES_PER_UNDECLARED)
+                    .map(|(file, line)| format!("{}:{}", file, line))
+                    .collect();
+                let more = import.sites.len().saturating_sub(MAX_SITES_PER_UNDECLARED);
+                out.push_str(&format!("  [{}] {} — {}", import.ecosystem, import.name, sites.join(", ")));
+                if more > 0 {
+                    out.push_str(&format!(", … {} more", more));
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 { "" } else { "s" }
+}
+
+fn is_manifest(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else { return false };
+    matches!(name, "Cargo.toml" | "package.json" | "pyproject.toml" | "go.mod")
+        || (name.starts_with("requirements") && name.ends_with(".txt"))
+}
+
+fn declared_dependencies(manifests: &[(PathBuf, String)], locked: &[Lockfile]) -> Declared {
+    let mut declared = Declared::default();
+    let mut entries: Vec<(Ecosystem, String, String, bool)> = Vec::new();
+    // `alias = { package = "real" }`: code imports `alias`, Cargo.lock names `real`.
+    let mut renamed: HashSet<String> = HashSet::new();
+    for (path, content) in manifests {
+        let source = path.to_string_lossy().replace('\\', "/");
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        match name {
+            "Cargo.toml" => {
+                declared.ecosystems.insert(Ecosystem::Rust);
+                let toml = toml_entries(content);
+                if let Some(package) = toml_value(&toml, "package", "name") {
+                    declared.internal.insert((Ecosystem::Rust, unquote(package).replace('-', "_")));
+                }
+                renamed.extend(cargo_renamed_packages(&toml).map(|p| p.replace('-', "_")));
+                for (dep, req) in parse_cargo_manifest(content) {
+                    if !req.starts_with("path ") {
+                        entries.push((Ecosystem::Rust, dep.replace('-', "_"), source.clone(), true));
+                    }
+                }
+            }
+            "package.json" => {
+                let Ok(manifest) = serde_json::from_str::<Value>(content) else { continue };
+                declared.ecosystems.insert(Ecosystem::Node);
+                if let Some(package) = manifest.get("name").and_then(Value::as_str) {
+                    declared.internal.insert((Ecosystem::Node, package.to_string()));
+                }
+                for (dep, spec) in node_requested(&manifest) {
+                    let local = ["workspace:", "file:", "link:"].iter().any(|p| spec.starts_with(p));
+                    if !local {
+                        entries.push((Ecosystem::Node, dep, source.clone(), true));
+                    }
+                }
+            }
+            "pyproject.toml" => {
+                declared.ecosystems.insert(Ecosystem::Python);
+                let toml = toml_entries(content);
+                for table in ["project", "tool.poetry"] {
+                    if let Some(package) = toml_value(&toml, table, "name") {
+                        declared.internal.insert((Ecosystem::Python, normalize_python_name(unquote(package))));
+                    }
+                }
+                for (dep, _) in pyproject_requirements(content) {
+                    entries.push((Ecosystem::Python, dep, source.clone(), true));
+                }
+            }
+            "go.mod" => {
+                declared.ecosystems.insert(Ecosystem::Go);
+                if let Some(module) = go_module_path(content) {
+                    declared.internal.insert((Ecosystem::Go, module.to_string()));
+                }
+                for module in parse_go_modules(content, None) {
+                    entries.push((Ecosystem::Go, module.name, source.clone(), module.direct));
+                }
+            }
+            _ => {
+                declared.ecosystems.insert(Ecosystem::Python);
+                for (dep, _) in parse_requirements_txt(content) {
+                    entries.push((Ecosystem::Python, dep, source.clone(), true));
+                }
+            }
+        }
+    }
+    for lockfile in locked {
+        let ecosystem = match lockfile.format.ecosystem() {
+            "rust" => Ecosystem::Rust,
+            "node" => Ecosystem::Node,
+            "python" => Ecosystem::Python,
+            _ => Ecosystem::Go,
+        };
+        let source = lockfile.format.file_name().to_string();
+        for package in lockfile.direct() {
+            let name = match ecosystem {
+                Ecosystem::Rust if renamed.contains(&package.name.replace('-', "_")) => continue,
+                Ecosystem::Rust => package.name.replace('-', "_"),
+                Ecosystem::Python => normalize_python_name(&package.name),
+                _ => package.name.clone(),
+            };
+            entries.push((ecosystem, name, source.clone(), true));
+        }
+    }
+    for (ecosystem, name, source, direct) in entries {
+        if !declared.internal.contains(&(ecosystem, name.clone())) {
+            declared.add(ecosystem, &name, &source, direct);
+        }
+    }
+    declared
+}
+
+/// Real package names behind renamed Cargo dependencies, inline or `[dependencies.alias]`.
+fn cargo_renamed_packages(toml: &[(String, String, String)]) -> impl Iterator<Item = &str> {
+    toml.iter().filter_map(|(table, key, value)| {
+        let kind = table.rsplit('.').next().unwrap_or(table);
+        if key == "package" && table.contains("dependencies.") {
+            Some(unquote(value))
+        } else if kind.ends_with("dependencies") && value.starts_with('{') {
+            inline_table_value(value, "package")
+        } else {
+            None
+        }
+    })
+}
+
+/// Rust module and Python module/package names defined in the repo, so a
+/// `use parser::X` or `import utils` is not mistaken for a third-party import.
+/// Rust: file stems and module dirs under `src/` (or `tests/`, `examples/`, `benches/`).
+/// Python: file stems and dirs holding an `__init__.py`.
+fn local_module_names(sources: &[(PathBuf, String)]) -> HashSet<(Ecosystem, String)> {
+    let mut names = HashSet::new();
+    for (path, _) in sources {
+        let Some(lang) = imports::import_lang(path) else { continue };
+        let ecosystem = Ecosystem::of(lang);
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let dirs = path.parent().into_iter().flat_map(Path::ancestors).filter_map(|d| d.file_name()?.to_str());
+        match lang {
+            ImportLang::Rust => {
+                names.insert((ecosystem, stem.to_string()));
+                let dirs: Vec<&str> = dirs.collect();
+                if let Some(root) = dirs.iter().position(|d| matches!(*d, "src" | "tests" | "examples" | "benches")) {
+                    names.extend(dirs[..root].iter().map(|d| (ecosystem, d.to_string())));
+                }
+            }
+            ImportLang::Python if stem == "__init__" => {
+                if let Some(package) = dirs.into_iter().next() {
+                    names.insert((ecosystem, package.to_string()));
+                }
+            }
+            ImportLang::Python => {
+                names.insert((ecosystem, stem.to_string()));
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+enum Hit {
+    /// One importing line of a declared dependency.
+    Declared((Ecosystem, String)),
+    /// An import of something no manifest declares, with its line.
+    Undeclared((Ecosystem, String), usize),
+}
+
+fn file_hits(
+    path: &Path,
+    content: &str,
+    declared: &Declared,
+    local_modules: &HashSet<(Ecosystem, String)>,
+) -> Vec<Hit> {
+    let Some(lang) = imports::import_lang(path) else { return Vec::new() };
+    let ecosystem = Ecosystem::of(lang);
+    let is_declared = |name: &str| declared.deps.contains_key(&(ecosystem, name.to_string()));
+    let is_internal = |name: &str| declared.internal.contains(&(ecosystem, name.to_string()));
+    let check_undeclared = declared.ecosystems.contains(&ecosystem);
+    let mut hits = Vec::new();
+
+    if lang == ImportLang::Rust {
+        for line in content.lines() {
+            let crates: BTreeSet<&str> = RUST_PATH_RE
+                .captures_iter(line)
+                .filter_map(|c| c.get(1).map(|m| m.as_str()))
+                .filter(|name| is_declared(name))
+                .collect();
+            hits.extend(crates.into_iter().map(|name| Hit::Declared((ecosystem, name.to_string()))));
+        }
+    }
+
+    let imports = imports::extract_imports(path, content);
+    // `mod foo;` here makes `use foo::…` local even when foo.rs is not a kept file.
+    let declared_mods: HashSet<&str> =
+        imports.iter().filter(|i| i.mod_decl).map(|i| i.specifier.as_str()).collect();
+    for import in &imports {
+        if import.mod_decl {
+            continue;
+        }
+        let package = match lang {
+            ImportLang::Rust => {
+                let first = import.specifier.trim_start_matches("::").split("::").next().unwrap_or_default();
+                if RUST_BUILTIN_CRATES.contains(&first)
+                    || declared_mods.contains(first)
+                    || local_modules.contains(&(ecosystem, first.to_string()))
+                {
+                    continue;
+                }
+                // Declared crates were already counted line by line above.
+                if is_declared(first) {
+                    continue;
+                }
+                first.to_string()
+            }
+            ImportLang::JavaScript => match node_package(&import.specifier) {
+                Some(package) => package,
+                None => continue,
+            },
+            ImportLang::Python => {
+                let top = import.specifier.split('.').next().unwrap_or_default();
+                if top.is_empty() || PYTHON_STDLIB.contains(&top) || local_modules.contains(&(ecosystem, top.to_string())) {
+                    continue;
+                }
+                python_distribution(top, |d| is_declared(d))
+            }
+            ImportLang::Go => {
+                let spec = import.specifier.as_str();
+                if !spec.split('/').next().unwrap_or_default().contains('.') {
+                    continue; // standard library
+                }
+                let owner = declared
+                    .deps
+                    .keys()
+                    .chain(declared.internal.iter())
+                    .filter(|(e, m)| *e == ecosystem && (spec == m || spec.starts_with(&format!("{}/", m))))
+                    .map(|(_, m)| m.clone())
+                    .max_by_key(|m| m.len());
+                owner.unwrap_or_else(|| spec.split('/').take(3).collect::<Vec<_>>().join("/"))
+            }
+        };
+        if is_internal(&package) {
+            continue;
+        }
+        if is_declared(&package) {
+            hits.push(Hit::Declared((ecosystem, package)));
+        } else if check_undeclared {
+            hits.push(Hit::Undeclared((ecosystem, package), import.line));
+        }
+    }
+    hits
+}
+
+/// `@scope/pkg/sub` → `@scope/pkg`, `lodash/fp` → `lodash`; `None` for relative
+/// paths, aliases (`@/x`, `~/x`), URLs and Node builtins.
+fn node_package(specifier: &str) -> Option<String> {
+    if specifier.starts_with(['.', '/', '~', '#']) || specifier.starts_with("@/") || specifier.contains(':') {
+        return None;
+    }
+    let mut parts = specifier.split('/');
+    let first = parts.next()?;
+    let package = if first.starts_with('@') { format!("{}/{}", first, parts.next()?) } else { first.to_string() };
+    (!NODE_BUILTINS.contains(&package.as_str())).then_some(package)
+}
+
+/// Declared distribution for a top-level import name: the import name itself,
+/// its known alias (`yaml` → `pyyaml`), or the normalised name as a fallback.
+fn python_distribution(top: &str, is_declared: impl Fn(&str) -> bool) -> String {
+    let normalized = normalize_python_name(top);
+    let alias = PYTHON_IMPORT_ALIASES.iter().find(|(import, _)| *import == top).map(|(_, dist)| dist.to_string());
+    [Some(normalized.clone()), alias.clone()]
+        .into_iter()
+        .flatten()
+        .find(|candidate| is_declared(candidate))
+        .or(alias)
+        .unwrap_or(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (file, line or line count) pairs.
+    type Sites<'a> = Vec<(&'a str, usize)>;
+
+    fn sources(files: &[(&str, &str)]) -> Vec<(PathBuf, String)> {
+        files.iter().map(|(p, c)| (PathBuf::from(p), c.to_string())).collect()
+    }
+
+    #[test]
+    fn maps_rust_and_python_usage_and_flags_unused_and_undeclared() {
+        let manifests = sources(&[
+            ("core/Cargo.toml", "[package]\nname = \"app-core\"\n\n[dependencies]\nregex = \"1\"\nwalkdir = \"2\"\nchrono = \"0.4\"\n"),
+            ("cli/Cargo.toml", "[package]\nname = \"app\"\n\n[dependencies]\napp-core = { path = \"../core\" }\n"),
+            ("requirements.txt", "PyYAML>=6\nrequests==2.32.3\n"),
+        ]);
+        let code = sources(&[
+            ("core/src/lib.rs", "pub mod parser;\nuse regex::Regex;\nuse crate::parser::Item;\n"),
+            ("core/src/parser.rs", "pub fn walk() {\n    for e in walkdir::WalkDir::new(\".\") {}\n    let r = regex::Regex::new(\"x\");\n}\n"),
+            ("cli/src/main.rs", "use app_core::parser;\nuse anyhow::Result;\n"),
+            ("tools/sync.py", "import os\nimport yaml\nfrom helpers import load\nimport numpy as np\n"),
+            ("tools/helpers.py", "def load(): pass\n"),
+        ]);
+        let report = DepsUsage::from_sources(&manifests, &[], &code);
+
+        let used: Vec<(&str, Sites)> = report
+            .used
+            .iter()
+            .map(|u| (u.name.as_str(), u.files.iter().map(|(f, n)| (f.as_str(), *n)).collect()))
+            .collect();
+        assert_eq!(
+            used,
+            [
+                ("regex", vec![("core/src/lib.rs", 1), ("core/src/parser.rs", 1)]),
+                ("walkdir", vec![("core/src/parser.rs", 1)]),
+                ("pyyaml", vec![("tools/sync.py", 1)]),
+            ]
+        );
+        let unused: Vec<&str> = report.unused.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(unused, ["chrono", "requests"]);
+        let undeclared: Vec<(String, &str, Sites)> = report
+            .undeclared
+            .iter()
+            .map(|u| (u.ecosystem.to_string(), u.name.as_str(), u.sites.iter().map(|(f, n)| (f.as_str(), *n)).collect()))
+            .collect();
+        assert_eq!(
+            undeclared,
+            [
+                ("rust".to_string(), "anyhow", vec![("cli/src/main.rs", 2)]),
+                ("python".to_string(), "numpy", vec![("tools/sync.py", 4)]),
+            ]
+        );
+        assert!(report.render().contains("[rust] regex — 2 files, 2 lines  (declared in core/Cargo.toml)\n"));
+    }
+
+    #[test]
+    fn cargo_renames_and_ancestor_dirs_do_not_hide_imports() {
+        let manifests = sources(&[(
+            "Cargo.toml",
+            "[package]\nname = \"app\"\n\n[dependencies]\nyaml = { package = \"serde_yaml\", version = \"0.9\" }\n\n[dependencies.json]\npackage = \"serde_json\"\nversion = \"1\"\n",
+        )]);
+        let locked = [Lockfile::new(
+            PathBuf::from("Cargo.lock"),
+            LockFormat::Cargo,
+            ["serde_yaml", "serde_json"]
+                .iter()
+                .map(|name| crate::lockfiles::LockedPackage { name: name.to_string(), version: "1.0.0".to_string(), direct: true })
+                .collect(),
+        )];
+        let code = sources(&[
+            ("src/lib.rs", "use yaml::Value;\nuse json::Map;\n"),
+            ("scripts/tokio/run.rs", "use tokio::main;\n"),
+            ("build.rs", "use cc::Build;\n"),
+        ]);
+        let report = DepsUsage::from_sources(&manifests, &locked, &code);
+        let used: Vec<&str> = report.used.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(used, ["json", "yaml"]);
+        assert!(report.unused.is_empty());
+        let undeclared: Vec<&str> = report.undeclared.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(undeclared, ["cc", "tokio"]);
+    }
+
+    #[test]
+    fn node_specifiers_reduce_to_package_names() {
+        assert_eq!(node_package("@tanstack/react-query/devtools").as_deref(), Some("@tanstack/react-query"));
+        assert_eq!(node_package("lodash/fp").as_deref(), Some("lodash"));
+        for skipped in ["./util", "../x", "@/components/Button", "fs", "node:path", "~/lib"] {
+            assert_eq!(node_package(skipped), None, "{}", skipped);
+        }
+    }
+}
@@ -1,15 +1,15 @@
 // saccade/core/src/detection.rs
 
 use crate::error::Result;
-use std::collections::HashSet;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tree_sitter::{Parser, Query};
 
 /// Represents the detected, high-confidence build systems in a repository.
 /// This acts as the "Environmental Signal/Cue" for the Dynamic Configuration Architecture.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum BuildSystemType {
     Rust,
     Node,
@@ -17,6 +17,18 @@ pub enum BuildSystemType {
     Go,
     CMake,
     Conan,
+    Vcpkg,
+    Bazel,
+    Meson,
+    Make,
+    Nix,
+    Gradle,
+    Maven,
+    SwiftPM,
+    Dart,
+    Elixir,
+    Haskell,
+    Zig,
 }
 
 impl fmt::Display for BuildSystemType {
@@ -35,6 +47,44 @@ const CMAKE_AST_QUERY: &str = r#"
 (identifier) @cmd
 "#;
 
+// Bazel files are Starlark, which the Python grammar parses: rule and macro calls.
+// Meson files parse the same way.
+const STARLARK_AST_QUERY: &str = r#"
+(call function: (identifier) @fn)
+"#;
+
+// Calls that confirm a BUILD / WORKSPACE / MODULE.bazel file belongs to Bazel.
+const BAZEL_CONFIRMATION_CALLS: &[&str] = &[
+    "module", "bazel_dep", "use_extension", "use_repo", "workspace", "load", "http_archive", "git_repository",
+    "local_repository", "package", "exports_files", "filegroup", "genrule", "cc_library", "cc_binary",
+    "cc_test", "java_library", "java_binary", "py_library", "py_binary", "go_library", "go_binary",
+    "rust_library", "rust_binary", "proto_library", "sh_binary",
+];
+
+// Meson's DSL is close enough to Starlark for the same grammar: its functions are plain calls.
+const MESON_CONFIRMATION_CALLS: &[&str] = &[
+    "project", "executable", "library", "shared_library", "static_library", "both_libraries", "dependency",
+    "declare_dependency", "subdir", "subproject", "test", "install_headers", "configure_file", "custom_target",
+];
+
+// Top-level Gradle blocks and calls (Groovy and Kotlin DSL).
+const GRADLE_TOP_LEVEL: &[&str] = &[
+    "plugins", "buildscript", "dependencies", "repositories", "allprojects", "subprojects", "android", "java",
+    "kotlin", "application", "publishing", "configurations", "sourceSets", "pluginManagement",
+    "dependencyResolutionManagement", "include", "includeBuild", "apply", "rootProject",
+];
+
+// Direct children of `<project>` that only a Maven POM has.
+const MAVEN_PROJECT_CHILDREN: &[&str] = &["modelVersion", "groupId", "artifactId", "parent", "modules", "packaging"];
+
+// Top-level pubspec keys besides `name`.
+const PUBSPEC_KEYS: &[&str] = &["environment", "dependencies", "dev_dependencies", "flutter", "publish_to", "version"];
+
+const CABAL_STANZAS: &[&str] = &["library", "executable", "test-suite", "benchmark", "foreign-library", "common"];
+
+const MAKE_DIRECTIVES: &[&str] =
+    &["include", "-include", "sinclude", "ifeq", "ifneq", "ifdef", "ifndef", "define", "override", "export", "vpath"];
+
 // Keywords that, when found as commands, confirm a file is a CMake manifest.
 const CMAKE_CONFIRMATION_KEYWORDS: &[&str] = &[
     "add_executable",
@@ -44,6 +94,96 @@ const CMAKE_CONFIRMATION_KEYWORDS: &[&str] = &[
     "find_package",
 ];
 
+/// Why a file confirmed a build system.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evidence {
+    pub path: PathBuf,
+    /// `file name`, `AST call \`cc_library\``, `structure \`use Mix.Project\``, …
+    pub signal: String,
+    /// 1-based line of the matching node or pattern, when there is one.
+    pub line: Option<usize>,
+    pub confidence: f32,
+}
+
+/// A build system and every file that confirmed it.
+#[derive(Debug, Clone)]
+pub struct SystemEvidence {
+    pub system: BuildSystemType,
+    /// Highest confidence among `evidence`.
+    pub confidence: f32,
+    pub evidence: Vec<Evidence>,
+}
+
+/// A file whose name pointed to a build system but whose content did not confirm it.
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub path: PathBuf,
+    pub system: BuildSystemType,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DetectionReport {
+    /// In `BuildSystemType` order.
+    pub systems: Vec<SystemEvidence>,
+    pub rejected: Vec<Rejection>,
+}
+
+impl DetectionReport {
+    pub fn detected(&self) -> Vec<BuildSystemType> {
+        self.systems.iter().map(|s| s.system).collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("DETECTION (build system → confirming files)\n");
+        if self.systems.is_empty() {
+            out.push_str("(no build systems detected)\n");
+        }
+        for system in &self.systems {
+            out.push_str(&format!(
+                "{} [{:.2}] {} file(s)\n",
+                system.system,
+                system.confidence,
+                system.evidence.len()
+            ));
+            for e in system.evidence.iter().take(MAX_EVIDENCE_SHOWN) {
+                let location = match e.line {
+                    Some(line) => format!("{}:{}", display_path(&e.path), line),
+                    None => display_path(&e.path),
+                };
+                out.push_str(&format!("  {}  {} [{:.2}]\n", location, e.signal, e.confidence));
+            }
+            if system.evidence.len() > MAX_EVIDENCE_SHOWN {
+                out.push_str(&format!("  … {} more\n", system.evidence.len() - MAX_EVIDENCE_SHOWN));
+            }
+        }
+        if !self.rejected.is_empty() {
+            out.push_str("Rejected:\n");
+            for r in self.rejected.iter().take(MAX_REJECTIONS_SHOWN) {
+                out.push_str(&format!("  {}  not {}: {}\n", display_path(&r.path), r.system, r.reason));
+            }
+            if self.rejected.len() > MAX_REJECTIONS_SHOWN {
+                out.push_str(&format!("  … {} more\n", self.rejected.len() - MAX_REJECTIONS_SHOWN));
+            }
+        }
+        out
+    }
+}
+
+const MAX_EVIDENCE_SHOWN: usize = 5;
+const MAX_REJECTIONS_SHOWN: usize = 10;
+
+// Confidence by kind of signal: a Tree-sitter AST beats a structural scan beats a bare file name.
+const AST_CONFIDENCE: f32 = 1.0;
+const STRUCTURE_CONFIDENCE: f32 = 0.95;
+const FILE_NAME_CONFIDENCE: f32 = 0.9;
+
+/// Outcome of one check on one file; `None` from a check means it does not apply.
+enum Verdict {
+    Confirmed(BuildSystemType, Evidence),
+    Rejected(Rejection),
+}
+
 impl Detector {
     pub fn new() -> Self {
         Self
@@ -51,68 +191,73 @@ impl Detector {
 
     /// The main detection entry point. It orchestrates the identification of all
     /// supported build systems within the provided file list.
-    pub fn detect_build_systems(&self, files: &[std::path::PathBuf]) -> Result<Vec<BuildSystemType>> {
-        let mut detected = HashSet::new();
+    pub fn detect_build_systems(&self, files: &[PathBuf]) -> Result<Vec<BuildSystemType>> {
+        Ok(self.detect(files)?.detected())
+    }
+
+    /// Detection with the evidence behind each system and the candidates that were rejected.
+    pub fn detect(&self, files: &[PathBuf]) -> Result<DetectionReport> {
+        let mut found: BTreeMap<BuildSystemType, Vec<Evidence>> = BTreeMap::new();
+        let mut report = DetectionReport::default();
 
         for file in files {
-            if self.is_cargo(file) {
-                detected.insert(BuildSystemType::Rust);
-            }
-            if self.is_npm(file) {
-                detected.insert(BuildSystemType::Node);
-            }
-            if self.is_python(file) {
-                detected.insert(BuildSystemType::Python);
-            }
-            if self.is_go(file) {
-                detected.insert(BuildSystemType::Go);
-            }
-            if self.is_cmake_validated(file)? {
-                detected.insert(BuildSystemType::CMake);
-            }
-            if self.is_conan(file) {
-                detected.insert(BuildSystemType::Conan);
+            let verdicts = [
+                self.is_simple_manifest(file),
+                self.is_cmake_validated(file)?,
+                self.validated_build_file(file)?,
+            ];
+            for verdict in verdicts.into_iter().flatten() {
+                match verdict {
+                    Verdict::Confirmed(system, evidence) => found.entry(system).or_default().push(evidence),
+                    Verdict::Rejected(rejection) => report.rejected.push(rejection),
+                }
             }
         }
 
-        Ok(detected.into_iter().collect())
+        report.systems = found
+            .into_iter()
+            .map(|(system, evidence)| SystemEvidence {
+                system,
+                confidence: evidence.iter().map(|e| e.confidence).fold(0.0, f32::max),
+                evidence,
+            })
+            .collect();
+        Ok(report)
     }
 
     // --- Simple, filename-based detectors for unambiguous ecosystems ---
-    fn is_cargo(&self, path: &Path) -> bool {
-        path.ends_with("Cargo.toml")
-    }
-    fn is_npm(&self, path: &Path) -> bool {
-        path.ends_with("package.json")
-    }
-    fn is_python(&self, path: &Path) -> bool {
-        matches!(
-            path.file_name().and_then(|n| n.to_str()),
-            Some("requirements.txt" | "pyproject.toml" | "Pipfile")
-        )
-    }
-    fn is_go(&self, path: &Path) -> bool {
-        path.ends_with("go.mod")
+    fn is_simple_manifest(&self, path: &Path) -> Option<Verdict> {
+        let system = match path.file_name()?.to_str()? {
+            "Cargo.toml" => BuildSystemType::Rust,
+            "package.json" => BuildSystemType::Node,
+            "requirements.txt" | "pyproject.toml" | "Pipfile" => BuildSystemType::Python,
+            "go.mod" => BuildSystemType::Go,
+            "conanfile.txt" | "conanfile.py" => BuildSystemType::Conan,
+            // vcpkg manifest mode.
+            "vcpkg.json" => BuildSystemType::Vcpkg,
+            _ => return None,
+        };
+        Some(Verdict::Confirmed(system, evidence(path, "file name".to_string(), None, FILE_NAME_CONFIDENCE)))
     }
 
     /// High-confidence structural validation for CMake files using Tree-sitter.
-    fn is_cmake_validated(&self, path: &Path) -> Result<bool> {
+    fn is_cmake_validated(&self, path: &Path) -> Result<Option<Verdict>> {
         // A fast-path to avoid reading every file. Only check likely candidates.
         let path_str = path.to_string_lossy();
         if !path_str.contains("CMakeLists.txt") && !path_str.ends_with(".cmake") {
-            return Ok(false);
+            return Ok(None);
         }
 
         let content = match fs::read_to_string(path) {
             Ok(c) => c,
-            Err(_) => return Ok(false),
+            Err(_) => return Ok(None),
         };
 
         let mut parser = Parser::new();
         parser.set_language(&tree_sitter_cmake::language()).map_err(|e| crate::error::SaccadeError::Other(e.to_string()))?;
         let tree = match parser.parse(&content, None) {
             Some(t) => t,
-            None => return Ok(false),
+            None => return Ok(Some(rejection(path, BuildSystemType::CMake, "does not parse as CMake"))),
         };
 
         let query = Query::new(&tree_sitter_cmake::language(), CMAKE_AST_QUERY).map_err(|e| crate::error::SaccadeError::Other(e.to_string()))?;
@@ -123,20 +268,647 @@ impl Detector {
             for capture in m.captures {
                 if let Ok(cmd) = capture.node.utf8_text(content.as_bytes()) {
                     if CMAKE_CONFIRMATION_KEYWORDS.contains(&cmd) {
-                        return Ok(true);
+                        let line = capture.node.start_position().row + 1;
+                        let signal = format!("AST command `{}`", cmd);
+                        return Ok(Some(Verdict::Confirmed(BuildSystemType::CMake, evidence(path, signal, Some(line), AST_CONFIDENCE))));
                     }
                 }
             }
         }
 
-        Ok(false)
+        Ok(Some(rejection(path, BuildSystemType::CMake, "no project/target/find_package command")))
+    }
+
+    /// Bazel, Meson, Make, Nix, Gradle, Maven, SwiftPM, Dart, Elixir, Haskell and Zig:
+    /// a file-name match confirmed by the file's structure, as with CMake. Bazel and Meson
+    /// are parsed with Tree-sitter; the rest, which have no grammar here, by a structural
+    /// scan that ignores comments and strings.
+    fn validated_build_file(&self, path: &Path) -> Result<Option<Verdict>> {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else { return Ok(None) };
+        let Some(system) = build_file_system(file_name) else { return Ok(None) };
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(_) => return Ok(None),
+        };
+        let ast = |calls| -> Result<Option<(String, usize, f32)>> {
+            Ok(first_starlark_call(&content, calls)?.map(|(call, line)| (format!("AST call `{}`", call), line, AST_CONFIDENCE)))
+        };
+        let confirmed = match system {
+            BuildSystemType::Bazel => ast(BAZEL_CONFIRMATION_CALLS)?,
+            BuildSystemType::Meson => ast(MESON_CONFIRMATION_CALLS)?,
+            _ => structure_signal(system, file_name, &content).map(|(signal, line)| (signal, line, STRUCTURE_CONFIDENCE)),
+        };
+        Ok(Some(match confirmed {
+            Some((signal, line, confidence)) => Verdict::Confirmed(system, evidence(path, signal, Some(line), confidence)),
+            None => rejection(path, system, &format!("no {}", expected_structure(system, file_name))),
+        }))
+    }
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn evidence(path: &Path, signal: String, line: Option<usize>, confidence: f32) -> Evidence {
+    Evidence { path: path.to_path_buf(), signal, line, confidence }
+}
+
+fn rejection(path: &Path, system: BuildSystemType, reason: &str) -> Verdict {
+    Verdict::Rejected(Rejection { path: path.to_path_buf(), system, reason: reason.to_string() })
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+/// The first call to one of `calls` in Starlark-like `content` (Bazel, Meson) and its line.
+fn first_starlark_call(content: &str, calls: &[&str]) -> Result<Option<(String, usize)>> {
+    let language = tree_sitter_python::language();
+    let mut parser = Parser::new();
+    parser.set_language(&language).map_err(|e| crate::error::SaccadeError::Other(e.to_string()))?;
+    let tree = match parser.parse(content, None) {
+        Some(t) => t,
+        None => return Ok(None),
+    };
+    let query = Query::new(&language, STARLARK_AST_QUERY).map_err(|e| crate::error::SaccadeError::Other(e.to_string()))?;
+    let mut cursor = tree_sitter::QueryCursor::new();
+    let confirmed = cursor.matches(&query, tree.root_node(), content.as_bytes()).find_map(|m| {
+        m.captures.iter().find_map(|c| {
+            let name = c.node.utf8_text(content.as_bytes()).ok()?;
+            calls.contains(&name).then(|| (name.to_string(), c.node.start_position().row + 1))
+        })
+    });
+    Ok(confirmed)
+}
+
+// --- Structural checks for build files with no grammar here ---
+
+/// The structure that confirms `system` in `content`, and its line.
+fn structure_signal(system: BuildSystemType, file_name: &str, content: &str) -> Option<(String, usize)> {
+    match (system, file_name) {
+        (BuildSystemType::Make, _) => make_structure(content),
+        (BuildSystemType::Nix, _) => nix_structure(content),
+        (BuildSystemType::Gradle, _) => gradle_structure(content),
+        (BuildSystemType::Maven, _) => maven_structure(content),
+        (BuildSystemType::SwiftPM, _) => swift_package_structure(content),
+        (BuildSystemType::Dart, _) => pubspec_structure(content),
+        (BuildSystemType::Elixir, _) => mix_structure(content),
+        (BuildSystemType::Haskell, "stack.yaml") => yaml_top_level(content)
+            .into_iter()
+            .find(|(key, value, _)| matches!(*key, "resolver" | "snapshot") && !value.is_empty())
+            .map(|(key, _, line)| (format!("top-level `{}`", key), line)),
+        (BuildSystemType::Haskell, "cabal.project") => cabal_fields(content)
+            .into_iter()
+            .find(|(field, _, _)| matches!(field.as_str(), "packages" | "optional-packages" | "extra-packages"))
+            .map(|(field, _, line)| (format!("`{}:` field", field), line)),
+        (BuildSystemType::Haskell, _) => cabal_structure(content),
+        (BuildSystemType::Zig, "build.zig.zon") => zon_structure(content),
+        (BuildSystemType::Zig, _) => zig_build_structure(content),
+        _ => None,
+    }
+}
+
+/// What a file of this system and name must contain, for rejections.
+fn expected_structure(system: BuildSystemType, file_name: &str) -> &'static str {
+    match (system, file_name) {
+        (BuildSystemType::Bazel, _) => "Bazel rule or macro call",
+        (BuildSystemType::Meson, _) => "Meson function call",
+        (BuildSystemType::Make, _) => "rule with a recipe, special target, directive or variable assignment",
+        (BuildSystemType::Nix, _) => "`outputs` attribute in a top-level attribute set",
+        (BuildSystemType::Gradle, _) => "top-level Gradle block or call",
+        (BuildSystemType::Maven, _) => "`<project>` root with Maven coordinates",
+        (BuildSystemType::SwiftPM, _) => "`Package(…)` with `import PackageDescription`",
+        (BuildSystemType::Dart, _) => "top-level `name` with environment or dependencies",
+        (BuildSystemType::Elixir, _) => "`defmodule … do` that uses Mix.Project and defines `project`",
+        (BuildSystemType::Haskell, "stack.yaml") => "top-level `resolver` or `snapshot`",
+        (BuildSystemType::Haskell, "cabal.project") => "`packages:` field",
+        (BuildSystemType::Haskell, _) => "`name:` field and component stanza",
+        (BuildSystemType::Zig, "build.zig.zon") => "`.name` and `.version` in the top-level struct",
+        (BuildSystemType::Zig, _) => "top-level `pub fn build`",
+        _ => "expected structure",
+    }
+}
+
+/// `content` with comments and string bodies blanked to spaces (quotes and newlines kept),
+/// so byte offsets and lines still line up but only code is left to match.
+fn mask_code(content: &str, line_comment: &str, quotes: &[&str]) -> String {
+    fn blank(out: &mut String, text: &str) {
+        for c in text.chars() {
+            if c == '\n' {
+                out.push('\n');
+            } else {
+                out.push_str(&" ".repeat(c.len_utf8()));
+            }
+        }
+    }
+    let mut out = String::with_capacity(content.len());
+    let mut i = 0;
+    while i < content.len() {
+        let rest = &content[i..];
+        let comment = if rest.starts_with(line_comment) {
+            Some(rest.find('\n').unwrap_or(rest.len()))
+        } else if rest.starts_with("/*") {
+            Some(rest.find("*/").map_or(rest.len(), |end| end + 2))
+        } else {
+            None
+        };
+        if let Some(len) = comment {
+            blank(&mut out, &rest[..len]);
+            i += len;
+        } else if let Some(quote) = quotes.iter().find(|q| rest.starts_with(**q)) {
+            let body = &rest[quote.len()..];
+            let len = string_body_len(body, quote);
+            let closed = body[len..].starts_with(quote);
+            out.push_str(quote);
+            blank(&mut out, &body[..len]);
+            if closed {
+                out.push_str(quote);
+            }
+            i += quote.len() + len + if closed { quote.len() } else { 0 };
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            i += c.len_utf8();
+        }
+    }
+    out
+}
+
+/// Bytes of a string body before its closing `quote`; one-character quotes honour `\` escapes.
+fn string_body_len(body: &str, quote: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote.len() == 1 {
+            escaped = true;
+        } else if body[i..].starts_with(quote) {
+            return i;
+        }
+    }
+    body.len()
+}
+
+/// `(offset, bracket depth, identifier)` for every identifier in masked code.
+fn words(masked: &str) -> Vec<(usize, usize, &str)> {
+    let mut out = Vec::new();
+    let (mut depth, mut start) = (0usize, None);
+    for (i, c) in masked.char_indices() {
+        let ident = c.is_ascii_alphanumeric() || c == '_';
+        match start {
+            None if ident && !c.is_ascii_digit() => start = Some(i),
+            Some(s) if !ident => {
+                out.push((s, depth, &masked[s..i]));
+                start = None;
+            }
+            _ => {}
+        }
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        out.push((s, depth, &masked[s..]));
+    }
+    out
+}
+
+/// Masked code after the word at `at`, from its first non-space character.
+fn after<'a>(masked: &'a str, at: usize, word: &str) -> &'a str {
+    masked[at + word.len()..].trim_start()
+}
+
+/// Offset of the bracket that closes the one at `open`.
+fn matching_close(masked: &str, open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in masked[open..].char_indices() {
+        match c {
+            '{' | '(' | '[' => depth += 1,
+            '}' | ')' | ']' => {
+                depth = depth.saturating_sub(1);
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+/// A rule whose recipe follows (on a tab line or after `;`), a special target like `.PHONY`,
+/// a directive, or a variable assignment. Prose with a colon has none of these.
+fn make_structure(content: &str) -> Option<(String, usize)> {
+    let lines: Vec<&str> = content.lines().collect();
+    lines.iter().enumerate().find_map(|(i, line)| {
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let code = line.split('#').next().unwrap_or_default().trim_end();
+        let first = code.split_whitespace().next()?;
+        let signal = if MAKE_DIRECTIVES.contains(&first) {
+            format!("directive `{}`", first)
+        } else if let Some((name, op)) = make_assignment(code) {
+            format!("assignment `{} {}`", name, op)
+        } else {
+            let (targets, rest) = code.split_once(':')?;
+            let targets = targets.trim();
+            let special = targets.len() > 1
+                && targets.starts_with('.')
+                && targets[1..].chars().all(|c| c.is_ascii_uppercase() || c == '_');
+            let recipe = rest.contains(';') || lines.get(i + 1).is_some_and(|next| next.starts_with('\t'));
+            if special {
+                format!("special target `{}`", targets)
+            } else if recipe && !targets.is_empty() {
+                format!("rule `{}:` with a recipe", targets)
+            } else {
+                return None;
+            }
+        };
+        Some((signal, i + 1))
+    })
+}
+
+/// `(name, operator)` for `NAME := …` and the other assignment operators, or `NAME = …`
+/// with an upper-case name.
+fn make_assignment(code: &str) -> Option<(&str, &'static str)> {
+    let is_name = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'));
+    for op in ["::=", ":=", "?=", "+=", "!="] {
+        if let Some(at) = code.find(op) {
+            let name = code[..at].trim();
+            return is_name(name).then_some((name, op));
+        }
     }
+    let (name, _) = code.split_once('=')?;
+    let name = name.trim();
+    (is_name(name) && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')).then_some((name, "="))
+}
 
-    /// Simple, filename-based detector for Conan.
-    fn is_conan(&self, path: &Path) -> bool {
-        matches!(
-            path.file_name().and_then(|n| n.to_str()),
-            Some("conanfile.txt" | "conanfile.py")
-        )
+/// `outputs = …` in the attribute set that is the whole file.
+fn nix_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "#", &["''", "\""]);
+    let code = masked.trim_end();
+    let open = code.find(|c: char| !c.is_whitespace())?;
+    if !code[open..].starts_with('{') || matching_close(code, open)? != code.len() - 1 {
+        return None;
     }
-}
\ No newline at end of file
+    words(code)
+        .into_iter()
+        .find(|&(at, depth, word)| depth == 1 && word == "outputs" && after(code, at, word).starts_with('=') && !after(code, at, word).starts_with("=="))
+        .map(|(at, _, _)| ("attribute `outputs` of the top-level set".to_string(), line_of(content, at)))
+}
+
+/// A Gradle block or call (`plugins {`, `dependencies {`, `include(…)`, `rootProject.name`) at depth 0.
+fn gradle_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "//", &["\"\"\"", "'''", "\"", "'"]);
+    words(&masked).into_iter().find_map(|(at, depth, word)| {
+        if depth != 0 || !GRADLE_TOP_LEVEL.contains(&word) || masked[..at].trim_end().ends_with('.') {
+            return None;
+        }
+        let rest = after(&masked, at, word);
+        let top_level = match word {
+            "rootProject" => rest.starts_with(".name"),
+            "apply" => rest.starts_with('(') || rest.starts_with("plugin") || rest.starts_with("from"),
+            "include" | "includeBuild" => rest.starts_with(['(', '"', '\'']),
+            _ => rest.starts_with(['{', '(']),
+        };
+        top_level.then(|| (format!("top-level `{}`", word), line_of(content, at)))
+    })
+}
+
+/// A `<project>` root element with a Maven coordinate or module list as a direct child.
+fn maven_structure(content: &str) -> Option<(String, usize)> {
+    let mut depth = 0usize;
+    let mut i = 0;
+    while let Some(start) = content[i..].find('<') {
+        let at = i + start;
+        let rest = &content[at..];
+        let skip_to = |end: &str| rest.find(end).map_or(rest.len(), |e| e + end.len());
+        if rest.starts_with("<!--") {
+            i = at + skip_to("-->");
+            continue;
+        }
+        if rest.starts_with("<?") || rest.starts_with("<!") {
+            i = at + skip_to(">");
+            continue;
+        }
+        let end = rest.find('>')?;
+        let tag = &rest[1..end];
+        i = at + end + 1;
+        if tag.starts_with('/') {
+            depth = depth.saturating_sub(1);
+            continue;
+        }
+        let name = tag.split(|c: char| c.is_whitespace() || c == '/').next().unwrap_or_default();
+        match depth {
+            0 if name != "project" => return None,
+            1 if MAVEN_PROJECT_CHILDREN.contains(&name) => {
+                return Some((format!("`<project>` child `<{}>`", name), line_of(content, at)));
+            }
+            _ => {}
+        }
+        if !tag.ends_with('/') {
+            depth += 1;
+        }
+    }
+    None
+}
+
+/// `import PackageDescription` and a top-level `… = Package(`.
+fn swift_package_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "//", &["\"\"\"", "\""]);
+    if !masked.lines().any(|l| l.split_whitespace().eq(["import", "PackageDescription"])) {
+        return None;
+    }
+    words(&masked)
+        .into_iter()
+        .find(|&(at, depth, word)| {
+            depth == 0 && word == "Package" && after(&masked, at, word).starts_with('(') && masked[..at].trim_end().ends_with('=')
+        })
+        .map(|(at, _, _)| ("`Package(…)` after `import PackageDescription`".to_string(), line_of(content, at)))
+}
+
+/// `(key, value, line)` for the unindented `key: value` lines of a YAML file.
+fn yaml_top_level(content: &str) -> Vec<(&str, &str, usize)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with([' ', '\t', '#', '-']))
+        .filter_map(|(i, line)| {
+            let (key, value) = line.split_once(':')?;
+            let key = key.trim().trim_matches(['"', '\'']);
+            let value = value.split(" #").next().unwrap_or_default().trim();
+            (!key.is_empty() && !key.contains(char::is_whitespace)).then_some((key, value, i + 1))
+        })
+        .collect()
+}
+
+/// A top-level `name:` next to `environment:`, `dependencies:` or another pubspec key.
+fn pubspec_structure(content: &str) -> Option<(String, usize)> {
+    let keys = yaml_top_level(content);
+    let (_, _, line) = keys.iter().find(|(key, value, _)| *key == "name" && !value.is_empty())?;
+    let (other, _, _) = keys.iter().find(|(key, _, _)| PUBSPEC_KEYS.contains(key))?;
+    Some((format!("top-level `name` and `{}`", other), *line))
+}
+
+/// `defmodule … do` at column 0 whose body has `use Mix.Project` and `def project`.
+fn mix_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "#", &["\"\"\"", "'''", "\"", "'"]);
+    let lines: Vec<&str> = masked.lines().collect();
+    let (start, module) = lines
+        .iter()
+        .enumerate()
+        .find_map(|(i, line)| Some((i, line.strip_prefix("defmodule ")?.trim_end().strip_suffix(" do")?.trim())))?;
+    let body = &lines[start + 1..];
+    let uses = body.iter().position(|line| {
+        let line = line.trim();
+        line == "use Mix.Project" || line.starts_with("use Mix.Project,")
+    })?;
+    let defines_project = body.iter().any(|line| {
+        line.trim_start().strip_prefix("def project").is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '(', ',']))
+    });
+    defines_project.then(|| (format!("`use Mix.Project` in `defmodule {}`", module), start + uses + 2))
+}
+
+/// `(field, value, line)` for the unindented `field: value` lines of a Cabal file; names are case-insensitive.
+fn cabal_fields(content: &str) -> Vec<(String, &str, usize)> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with(char::is_whitespace) && !line.starts_with("--"))
+        .filter_map(|(i, line)| {
+            let (field, value) = line.split_once(':')?;
+            let field = field.trim();
+            (!field.is_empty() && !field.contains(char::is_whitespace)).then(|| (field.to_ascii_lowercase(), value.trim(), i + 1))
+        })
+        .collect()
+}
+
+/// A package description: a `name:` field and a `library`/`executable`/… stanza.
+fn cabal_structure(content: &str) -> Option<(String, usize)> {
+    cabal_fields(content).iter().find(|(field, value, _)| field == "name" && !value.is_empty())?;
+    content.lines().enumerate().find_map(|(i, line)| {
+        if line.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let word = line.split_whitespace().next()?.to_ascii_lowercase();
+        CABAL_STANZAS.iter().find(|s| **s == word).map(|s| (format!("`name:` field and `{}` stanza", s), i + 1))
+    })
+}
+
+/// `pub fn build(` at the top level of `build.zig`.
+fn zig_build_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "//", &["\""]);
+    words(&masked)
+        .into_iter()
+        .find(|&(at, depth, word)| {
+            depth == 0
+                && word == "build"
+                && after(&masked, at, word).starts_with('(')
+                && masked[..at].trim_end().strip_suffix("fn").is_some_and(|before| before.trim_end().ends_with("pub"))
+        })
+        .map(|(at, _, _)| ("top-level `pub fn build`".to_string(), line_of(content, at)))
+}
+
+/// A `build.zig.zon` struct literal with `.name` and `.version` fields at its top level.
+fn zon_structure(content: &str) -> Option<(String, usize)> {
+    let masked = mask_code(content, "//", &["\""]);
+    if !masked.trim_start().starts_with(".{") {
+        return None;
+    }
+    let fields: Vec<(usize, &str)> = words(&masked)
+        .into_iter()
+        .filter(|&(at, depth, word)| depth == 1 && masked[..at].ends_with('.') && after(&masked, at, word).starts_with('='))
+        .map(|(at, _, word)| (at, word))
+        .collect();
+    let (name_at, _) = fields.iter().find(|(_, field)| *field == "name")?;
+    fields
+        .iter()
+        .any(|(_, field)| *field == "version")
+        .then(|| ("`.name` and `.version` in the top-level struct".to_string(), line_of(content, *name_at)))
+}
+
+/// Build system whose manifest or build file has this name, for the newer
+/// file-name-plus-structure checks.
+fn build_file_system(file_name: &str) -> Option<BuildSystemType> {
+    let system = match file_name {
+        "MODULE.bazel" | "WORKSPACE" | "WORKSPACE.bazel" | "BUILD" | "BUILD.bazel" => BuildSystemType::Bazel,
+        "meson.build" => BuildSystemType::Meson,
+        "Makefile" | "makefile" | "GNUmakefile" => BuildSystemType::Make,
+        "flake.nix" => BuildSystemType::Nix,
+        "build.gradle" | "build.gradle.kts" | "settings.gradle" | "settings.gradle.kts" => BuildSystemType::Gradle,
+        "pom.xml" => BuildSystemType::Maven,
+        "Package.swift" => BuildSystemType::SwiftPM,
+        "pubspec.yaml" => BuildSystemType::Dart,
+        "mix.exs" => BuildSystemType::Elixir,
+        "stack.yaml" | "cabal.project" => BuildSystemType::Haskell,
+        "build.zig" | "build.zig.zon" => BuildSystemType::Zig,
+        name if name.ends_with(".cabal") => BuildSystemType::Haskell,
+        name if name.ends_with(".mk") => BuildSystemType::Make,
+        _ => return None,
+    };
+    Some(system)
+}
+
+/// Build system for a file that marks a project root, for STRUCTURE annotations.
+/// Per-package files (Bazel `BUILD`, `*.mk` fragments) are not roots.
+pub fn project_root_system(file_name: &str) -> Option<BuildSystemType> {
+    let system = match file_name {
+        "Cargo.toml" => BuildSystemType::Rust,
+        "package.json" => BuildSystemType::Node,
+        "go.mod" => BuildSystemType::Go,
+        "requirements.txt" | "pyproject.toml" | "Pipfile" => BuildSystemType::Python,
+        "CMakeLists.txt" => BuildSystemType::CMake,
+        "conanfile.py" | "conanfile.txt" => BuildSystemType::Conan,
+        "vcpkg.json" => BuildSystemType::Vcpkg,
+        "BUILD" | "BUILD.bazel" => return None,
+        name if name.ends_with(".mk") => return None,
+        name => return build_file_system(name),
+    };
+    Some(system)
+}
+
+/// Whether the static file-name checks above already recognize `file_name`.
+pub fn is_known_build_file(file_name: &str) -> bool {
+    build_file_system(file_name).is_some() || project_root_system(file_name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_carries_evidence_and_rejections() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let files = [
+            write("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            write("CMakeLists.txt", "# placeholder\nset(X 1)\n"),
+            write("meson.build", "# build\nproject('demo', 'c')\n"),
+        ];
+        let report = Detector::new().detect(&files).unwrap();
+
+        assert_eq!(report.detected(), [BuildSystemType::Rust, BuildSystemType::Meson]);
+        let meson = &report.systems[1].evidence[0];
+        assert_eq!((meson.line, meson.signal.as_str(), meson.confidence), (Some(2), "AST call `project`", AST_CONFIDENCE));
+        assert_eq!(report.systems[0].confidence, FILE_NAME_CONFIDENCE);
+        assert_eq!(report.rejected.len(), 1);
+        assert_eq!(report.rejected[0].system, BuildSystemType::CMake);
+        assert!(report.render().contains("Rejected:\n"));
+    }
+
+    #[test]
+    fn each_build_file_is_confirmed_by_its_structure_and_rejected_without_it() {
+        // (file name, system, confirming content, signal, same name without the structure)
+        let cases: &[(&str, BuildSystemType, &str, &str, &str)] = &[
+            (
+                "BUILD",
+                BuildSystemType::Bazel,
+                "load(\"@rules_cc//cc:defs.bzl\", \"cc_library\")\ncc_library(name = \"core\", srcs = [\"core.cc\"])\n",
+                "AST call `load`",
+                "Build instructions: run make.\n",
+            ),
+            (
+                "meson.build",
+                BuildSystemType::Meson,
+                "project('demo', 'c')\nexecutable('demo', 'main.c')\n",
+                "AST call `project`",
+                "# Meson files moved to tools/\nmessage = 'moved'\n",
+            ),
+            (
+                "Makefile",
+                BuildSystemType::Make,
+                "# Build everything\nall: build\n\nlint: ## Run linters\n\tcargo clippy\n",
+                "rule `lint:` with a recipe",
+                "Note: run ./configure first.\nSee: docs/BUILD.md\n",
+            ),
+            ("rules.mk", BuildSystemType::Make, "CFLAGS += -O2 -Wall\n", "assignment `CFLAGS +=`", "Usage: include this from the top Makefile.\n"),
+            (
+                "flake.nix",
+                BuildSystemType::Nix,
+                "# demo\n{\n  description = \"outputs = none\";\n  outputs = { self, nixpkgs }: {\n    packages = { };\n  };\n}\n",
+                "attribute `outputs` of the top-level set",
+                "{ pkgs ? import <nixpkgs> {} }:\npkgs.mkShell {\n  outputs = [ \"out\" ];\n}\n",
+            ),
+            (
+                "build.gradle",
+                BuildSystemType::Gradle,
+                "// Root project\nplugins {\n    id 'java'\n}\n\ndependencies {\n    implementation 'com.google.guava:guava:33.0.0-jre'\n}\n",
+                "top-level `plugins`",
+                "// plugins { id 'java' }\nprintln 'dependencies {'\n",
+            ),
+            ("settings.gradle.kts", BuildSystemType::Gradle, "rootProject.name = \"demo\"\ninclude(\":app\")\n", "top-level `rootProject`", "val name = \"demo\"\n"),
+            (
+                "pom.xml",
+                BuildSystemType::Maven,
+                "<?xml version=\"1.0\"?>\n<!-- <settings> -->\n<project xmlns=\"http://maven.apache.org/POM/4.0.0\">\n  <modelVersion>4.0.0</modelVersion>\n  <artifactId>demo</artifactId>\n</project>\n",
+                "`<project>` child `<modelVersion>`",
+                "<settings>\n  <profiles><profile><artifactId>x</artifactId></profile></profiles>\n</settings>\n",
+            ),
+            (
+                "Package.swift",
+                BuildSystemType::SwiftPM,
+                "// swift-tools-version:5.9\nimport PackageDescription\n\nlet package = Package(\n    name: \"Demo\"\n)\n",
+                "`Package(…)` after `import PackageDescription`",
+                "import Foundation\n// import PackageDescription\n// let package = Package(name: \"Demo\")\n",
+            ),
+            ("pubspec.yaml", BuildSystemType::Dart, "name: demo\nenvironment:\n  sdk: ^3.3.0\n", "top-level `name` and `environment`", "name: demo\nservices:\n  - web\n"),
+            (
+                "mix.exs",
+                BuildSystemType::Elixir,
+                "defmodule Demo.MixProject do\n  use Mix.Project\n\n  def project do\n    [app: :demo, version: \"0.1.0\"]\n  end\nend\n",
+                "`use Mix.Project` in `defmodule Demo.MixProject`",
+                "defmodule Demo.Tasks do\n  # use Mix.Project\n  def project, do: :ok\nend\n",
+            ),
+            (
+                "demo.cabal",
+                BuildSystemType::Haskell,
+                "cabal-version: 3.0\nname: demo\nversion: 0.1.0\n\nlibrary\n  exposed-modules: Demo\n  build-depends: base\n",
+                "`name:` field and `library` stanza",
+                "-- name: demo\nbuild-depends: base\n",
+            ),
+            ("stack.yaml", BuildSystemType::Haskell, "resolver: lts-22.0\npackages:\n  - .\n", "top-level `resolver`", "packages:\n  - .\n"),
+            ("cabal.project", BuildSystemType::Haskell, "packages: ./core ./cli\n", "`packages:` field", "-- packages: ./core\noptimization: 2\n"),
+            (
+                "build.zig",
+                BuildSystemType::Zig,
+                "const std = @import(\"std\");\n\npub fn build(b: *std.Build) void {\n    _ = b;\n}\n",
+                "top-level `pub fn build`",
+                "const std = @import(\"std\");\n// pub fn build(b: *std.Build) void {}\nconst meta = .{ .name = \"demo\" };\n",
+            ),
+            (
+                "build.zig.zon",
+                BuildSystemType::Zig,
+                ".{\n    .name = .demo,\n    .version = \"0.1.0\",\n    .dependencies = .{},\n}\n",
+                "`.name` and `.version` in the top-level struct",
+                ".{\n    .dependencies = .{\n        .dep = .{ .name = \"x\", .version = \"1\" },\n    },\n}\n",
+            ),
+        ];
+        let dir = tempfile::tempdir().unwrap();
+        for (name, system, confirming, signal, other) in cases {
+            let path = dir.path().join(name);
+            fs::write(&path, confirming).unwrap();
+            let report = Detector::new().detect(std::slice::from_ref(&path)).unwrap();
+            assert_eq!(report.detected(), [*system], "{}: {:?}", name, report.rejected);
+            assert_eq!(report.systems[0].evidence[0].signal, *signal, "{}", name);
+
+            fs::write(&path, other).unwrap();
+            let report = Detector::new().detect(std::slice::from_ref(&path)).unwrap();
+            assert!(report.systems.is_empty(), "{} confirmed by {:?}", name, report.systems);
+            assert_eq!(report.rejected[0].system, *system, "{}", name);
+            assert!(report.rejected[0].reason.starts_with("no "));
+        }
+    }
+}
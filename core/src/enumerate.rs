@@ -1,5 +1,6 @@
-use crate::config::{Config, PRUNE_DIRS};
+use crate::config::{Config, PRUNE_DIRS, PRUNE_DIR_PREFIXES};
 use crate::error::{Result, SaccadeError};
+use crate::exclusions::{Exclusion, ExclusionReason};
 use std::path::PathBuf;
 use std::process::Command;
 use walkdir::WalkDir;
@@ -14,6 +15,12 @@ impl FileEnumerator {
     }
 
     pub fn enumerate(&self) -> Result<Vec<PathBuf>> {
+        self.enumerate_with_exclusions().map(|(paths, _)| paths)
+    }
+
+    /// Like `enumerate`, plus the directories pruned by `PRUNE_DIRS` (walk mode only;
+    /// Git mode lists tracked files and never sees ignored ones).
+    pub fn enumerate_with_exclusions(&self) -> Result<(Vec<PathBuf>, Vec<Exclusion>)> {
         use crate::config::GitMode;
 
         match self.config.git_mode {
@@ -22,7 +29,7 @@ impl FileEnumerator {
                 if !self.in_git_repo()? {
                     return Err(SaccadeError::NotInGitRepo);
                 }
-                self.git_ls_files()
+                Ok((self.git_ls_files()?, Vec::new()))
             }
             GitMode::No => {
                 // Force find mode
@@ -32,7 +39,7 @@ impl FileEnumerator {
                 // Prefer Git when available and inside a repo; otherwise fallback to WalkDir
                 if self.in_git_repo()? {
                     if let Ok(files) = self.git_ls_files() {
-                        return Ok(files);
+                        return Ok((files, Vec::new()));
                     }
                 }
                 self.walk_all_files()
@@ -77,15 +84,21 @@ impl FileEnumerator {
         Ok(paths)
     }
 
-    fn walk_all_files(&self) -> Result<Vec<PathBuf>> {
+    fn walk_all_files(&self) -> Result<(Vec<PathBuf>, Vec<Exclusion>)> {
         let mut paths = Vec::new();
         let mut errors = Vec::new();
+        let mut pruned = Vec::new();
 
         let walker = WalkDir::new(".").follow_links(false).into_iter();
 
         for item in walker.filter_entry(|e| {
             let name = e.file_name().to_string_lossy();
-            !PRUNE_DIRS.iter().any(|p| name == *p)
+            let prune = PRUNE_DIRS.iter().any(|p| name == *p) || PRUNE_DIR_PREFIXES.iter().any(|p| name.starts_with(p));
+            if prune {
+                let dir = e.path().strip_prefix(".").unwrap_or(e.path());
+                pruned.push(Exclusion::new(dir, ExclusionReason::PrunedDir, None));
+            }
+            !prune
         }) {
             let entry = match item {
                 Ok(e) => e,
@@ -114,6 +127,6 @@ impl FileEnumerator {
             }
         }
 
-        Ok(paths)
+        Ok((paths, pruned))
     }
 }
\ No newline at end of file
@@ -36,6 +36,9 @@ pub enum SaccadeError {
     #[error("Regex error: {0}")]
     Regex(#[from] regex::Error),
 
+    #[error("Request failed: {0}")]
+    Request(#[from] crate::request::RequestError),
+
     #[error("Mutex lock failed: a thread panicked while holding the lock")]
     MutexPoisoned,
 
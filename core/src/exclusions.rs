@@ -0,0 +1,176 @@
+// saccade/core/src/exclusions.rs
+//
+// Audit trail for files that never reach the pack. Every filter records a reason per
+// rejected path; the report is written to EXCLUDED.txt and PACK.json and summarized
+// in PROJECT, so "the AI can't see it" can be told apart from "it doesn't exist".
+
+use crate::error::{Result, SaccadeError};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const EXCLUDED_FILE_NAME: &str = "EXCLUDED.txt";
+
+/// Why a path was dropped, in pipeline order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ExclusionReason {
+    PrunedDir,
+    Unreadable,
+    BinaryContent,
+    SecretName,
+    BinaryExtension,
+    ExcludePattern,
+    NotIncluded,
+    NotCode,
+    Stage2TooLarge,
+    Stage2Unsupported,
+    Stage2Budget,
+}
+
+impl ExclusionReason {
+    pub fn describe(self) -> &'static str {
+        match self {
+            ExclusionReason::PrunedDir => "pruned directory (PRUNE_DIRS / PRUNE_DIR_PREFIXES, walk mode)",
+            ExclusionReason::Unreadable => "unreadable",
+            ExclusionReason::BinaryContent => "unknown type with byte entropy outside the text band",
+            ExclusionReason::SecretName => "secret file name (SECRET_PATTERN)",
+            ExclusionReason::BinaryExtension => "binary extension (BIN_EXT_PATTERN)",
+            ExclusionReason::ExcludePattern => "matched --exclude",
+            ExclusionReason::NotIncluded => "matched no --include",
+            ExclusionReason::NotCode => "not code (--code-only)",
+            ExclusionReason::Stage2TooLarge => "Stage-2 skipped: file too large",
+            ExclusionReason::Stage2Unsupported => "Stage-2 skipped: no grammar or parse failed",
+            ExclusionReason::Stage2Budget => "Stage-2 dropped: over --stage2-budget",
+        }
+    }
+
+    /// Stage-2 reasons only cost the file its skeleton; it is still listed in PACK.txt.
+    pub fn is_stage2(self) -> bool {
+        matches!(self, ExclusionReason::Stage2TooLarge | ExclusionReason::Stage2Unsupported | ExclusionReason::Stage2Budget)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Exclusion {
+    pub path: PathBuf,
+    pub reason: ExclusionReason,
+    /// The specific pattern, size or entropy behind the reason.
+    pub detail: Option<String>,
+}
+
+impl Exclusion {
+    pub fn new(path: &Path, reason: ExclusionReason, detail: Option<String>) -> Self {
+        Self { path: path.to_path_buf(), reason, detail }
+    }
+
+    pub fn display_path(&self) -> String {
+        self.path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ExclusionReport {
+    pub exclusions: Vec<Exclusion>,
+}
+
+impl ExclusionReport {
+    pub fn extend(&mut self, exclusions: Vec<Exclusion>) {
+        self.exclusions.extend(exclusions);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclusions.is_empty()
+    }
+
+    /// Paths per reason, each group sorted by path.
+    pub fn by_reason(&self) -> BTreeMap<ExclusionReason, Vec<&Exclusion>> {
+        let mut groups: BTreeMap<ExclusionReason, Vec<&Exclusion>> = BTreeMap::new();
+        for e in &self.exclusions {
+            groups.entry(e.reason).or_default().push(e);
+        }
+        for group in groups.values_mut() {
+            group.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        groups
+    }
+
+    /// `4 (2 binary extension (BIN_EXT_PATTERN), 2 matched --exclude)` over the selected reasons.
+    fn summary(&self, stage2: bool) -> String {
+        let groups: Vec<(ExclusionReason, usize)> = self
+            .by_reason()
+            .into_iter()
+            .filter(|(reason, _)| reason.is_stage2() == stage2)
+            .map(|(reason, group)| (reason, group.len()))
+            .collect();
+        let total: usize = groups.iter().map(|(_, n)| n).sum();
+        if total == 0 {
+            return "0".to_string();
+        }
+        let parts: Vec<String> = groups.iter().map(|(reason, n)| format!("{} {}", n, reason.describe())).collect();
+        format!("{} ({})", total, parts.join(", "))
+    }
+
+    /// PROJECT STATS lines.
+    pub fn stats_lines(&self) -> String {
+        format!(
+            "- files.excluded: {}\n- stage2.skipped: {}\n- exclusions.detail: {}\n",
+            self.summary(false),
+            self.summary(true),
+            EXCLUDED_FILE_NAME
+        )
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("========================================\nEXCLUDED FILES\n========================================\n\n");
+        out.push_str("Paths saccade dropped, grouped by reason. Files ignored by .gitignore are never\n");
+        out.push_str("enumerated and are not listed; in Git mode neither are untracked files, since\n");
+        out.push_str("only `git ls-files` output is read. Stage-2 entries are still in PACK.txt; only\n");
+        out.push_str("their skeleton is missing.\n");
+        if self.is_empty() {
+            out.push_str("\n(nothing excluded)\n");
+            return out;
+        }
+        for (reason, group) in self.by_reason() {
+            out.push_str(&format!("\n{} — {}\n", reason.describe(), group.len()));
+            for e in group {
+                match &e.detail {
+                    Some(detail) => out.push_str(&format!("  {}  ({})\n", e.display_path(), detail)),
+                    None => out.push_str(&format!("  {}\n", e.display_path())),
+                }
+            }
+        }
+        out
+    }
+
+    pub fn write(&self, pack_dir: &Path) -> Result<()> {
+        let path = pack_dir.join(EXCLUDED_FILE_NAME);
+        fs::write(&path, self.render()).map_err(|e| SaccadeError::Io { source: e, path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_by_reason_and_splits_stage2_from_hidden_files() {
+        let mut report = ExclusionReport::default();
+        report.extend(vec![
+            Exclusion::new(Path::new("b.png"), ExclusionReason::BinaryExtension, None),
+            Exclusion::new(Path::new("./a.jpg"), ExclusionReason::BinaryExtension, None),
+            Exclusion::new(Path::new("gen/x.rs"), ExclusionReason::ExcludePattern, Some("^gen/".to_string())),
+            Exclusion::new(Path::new("README.md"), ExclusionReason::Stage2Unsupported, Some("no grammar for .md".to_string())),
+        ]);
+        assert_eq!(
+            report.stats_lines(),
+            "- files.excluded: 3 (2 binary extension (BIN_EXT_PATTERN), 1 matched --exclude)\n\
+             - stage2.skipped: 1 (1 Stage-2 skipped: no grammar or parse failed)\n\
+             - exclusions.detail: EXCLUDED.txt\n"
+        );
+        let rendered = report.render();
+        assert!(rendered.contains("binary extension (BIN_EXT_PATTERN) — 2\n  a.jpg\n  b.png\n"));
+        assert!(rendered.contains("  gen/x.rs  (^gen/)\n"));
+    }
+}
@@ -1,7 +1,8 @@
 use crate::config::{Config, BIN_EXT_PATTERN, CODE_BARE_PATTERN, CODE_EXT_PATTERN, SECRET_PATTERN};
 use crate::error::Result;
+use crate::exclusions::{Exclusion, ExclusionReason};
 use regex::Regex;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 pub struct FileFilter {
     config: Config,
@@ -34,28 +35,46 @@ impl FileFilter {
         })
     }
 
-    pub fn filter(&self, files: Vec<std::path::PathBuf>) -> Vec<std::path::PathBuf> {
+    pub fn filter(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
         files.into_iter().filter(|p| self.should_keep(p)).collect()
     }
 
+    /// Kept files, and a reason for each dropped one.
+    pub fn filter_with_exclusions(&self, files: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<Exclusion>) {
+        let mut kept = Vec::new();
+        let mut excluded = Vec::new();
+        for path in files {
+            match self.exclusion(&path) {
+                Some((reason, detail)) => excluded.push(Exclusion::new(&path, reason, detail)),
+                None => kept.push(path),
+            }
+        }
+        (kept, excluded)
+    }
+
     fn should_keep(&self, path: &Path) -> bool {
+        self.exclusion(path).is_none()
+    }
+
+    /// The first check `path` fails, with the pattern behind it where there is one.
+    fn exclusion(&self, path: &Path) -> Option<(ExclusionReason, Option<String>)> {
         // Normalize to forward slashes for cross-platform regex matching
         let path_str = path.to_string_lossy().replace('\\', "/");
 
         // Secrets check
         if self.secret_re.is_match(&path_str) {
-            return false;
+            return Some((ExclusionReason::SecretName, None));
         }
 
         // Binary extensions check
         if self.bin_ext_re.is_match(&path_str) {
-            return false;
+            return Some((ExclusionReason::BinaryExtension, None));
         }
 
         // Exclude patterns
         for pattern in &self.config.exclude_patterns {
             if pattern.is_match(&path_str) {
-                return false;
+                return Some((ExclusionReason::ExcludePattern, Some(pattern.as_str().to_string())));
             }
         }
 
@@ -69,18 +88,18 @@ impl FileFilter {
                 }
             }
             if !matched {
-                return false;
+                return Some((ExclusionReason::NotIncluded, None));
             }
         }
 
         // Code-only mode: keep if (code extension) OR (known bare build file).
         if let (Some(ext_re), Some(bare_re)) = (&self.code_ext_re, &self.code_bare_re) {
             if !(ext_re.is_match(&path_str) || bare_re.is_match(&path_str)) {
-                return false;
+                return Some((ExclusionReason::NotCode, None));
             }
         }
 
-        true
+        None
     }
 }
 
@@ -126,4 +145,14 @@ mod tests {
         // Expect 2 kept: rs + Makefile; png dropped by binary pattern
         assert_eq!(kept.len(), 2);
     }
+
+    #[test]
+    fn exclusions_name_the_failed_check() {
+        let f = FileFilter::new(cfg_code_only()).unwrap();
+        let files = vec![PathBuf::from("src/main.rs"), PathBuf::from("assets/logo.png"), PathBuf::from("notes.randomdata")];
+        let (kept, excluded) = f.filter_with_exclusions(files);
+        assert_eq!(kept, [PathBuf::from("src/main.rs")]);
+        let reasons: Vec<_> = excluded.iter().map(|e| e.reason).collect();
+        assert_eq!(reasons, [ExclusionReason::BinaryExtension, ExclusionReason::NotCode]);
+    }
 }
\ No newline at end of file
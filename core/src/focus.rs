@@ -0,0 +1,298 @@
+// saccade/core/src/focus.rs
+//
+// `--focus "<task>"`: a local BM25 index over paths, identifiers, comments and
+// skeleton text, used to put the files a task most likely touches at the top
+// of the pack. Purely lexical: no embeddings, no network.
+
+use crate::error::Result;
+use crate::graph::FileRanks;
+use crate::parser::{self, ItemRole, SkeletonItem, SkeletonLevel};
+use crate::registry::LanguageRegistry;
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_FILE_SIZE_FOR_INDEX: u64 = 1024 * 1024; // 1 MB
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+/// Path tokens count this many times, so `users/routes.ts` beats a passing mention.
+const PATH_WEIGHT: usize = 3;
+const SNIPPETS_PER_FILE: usize = 3;
+const MAX_SNIPPET_CHARS: usize = 160;
+const MAX_SKELETON_ITEMS: usize = 80;
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "do", "does", "for", "from", "how", "in", "is",
+    "it", "of", "on", "or", "the", "this", "that", "to", "was", "what", "when", "where", "which",
+    "why", "with",
+];
+
+pub struct FocusIndex {
+    docs: Vec<Doc>,
+    /// Term → number of documents containing it.
+    df: HashMap<String, usize>,
+    avg_len: f64,
+}
+
+struct Doc {
+    path: PathBuf,
+    tf: HashMap<String, usize>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct FocusHit {
+    pub path: PathBuf,
+    pub score: f64,
+    /// Query terms found in the file, in query order.
+    pub matched: Vec<String>,
+}
+
+impl FocusIndex {
+    /// Index `(path, searchable text)` pairs; path tokens are added (and weighted) here.
+    pub fn from_documents(documents: Vec<(PathBuf, String)>) -> Self {
+        let docs: Vec<Doc> = documents
+            .into_iter()
+            .map(|(path, text)| {
+                let mut tf: HashMap<String, usize> = HashMap::new();
+                let mut len = 0;
+                for token in tokenize(&path.to_string_lossy()) {
+                    *tf.entry(token).or_default() += PATH_WEIGHT;
+                    len += PATH_WEIGHT;
+                }
+                for token in tokenize(&text) {
+                    *tf.entry(token).or_default() += 1;
+                    len += 1;
+                }
+                Doc { path, tf, len }
+            })
+            .collect();
+        let mut df: HashMap<String, usize> = HashMap::new();
+        for doc in &docs {
+            for term in doc.tf.keys() {
+                *df.entry(term.clone()).or_default() += 1;
+            }
+        }
+        let avg_len = if docs.is_empty() { 0.0 } else { docs.iter().map(|d| d.len).sum::<usize>() as f64 / docs.len() as f64 };
+        Self { docs, df, avg_len }
+    }
+
+    /// Best `top` documents for `query`; documents matching no term are never returned.
+    pub fn search(&self, query: &str, top: usize) -> Vec<FocusHit> {
+        let mut terms = tokenize(query);
+        let mut seen = HashSet::new();
+        terms.retain(|t| seen.insert(t.clone()));
+
+        let n = self.docs.len() as f64;
+        let mut hits: Vec<FocusHit> = self
+            .docs
+            .iter()
+            .filter_map(|doc| {
+                let mut score = 0.0;
+                let mut matched = Vec::new();
+                for term in &terms {
+                    let Some(&tf) = doc.tf.get(term) else { continue };
+                    let df = self.df[term] as f64;
+                    let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+                    let tf = tf as f64;
+                    let norm = K1 * (1.0 - B + B * doc.len as f64 / self.avg_len.max(1.0));
+                    score += idf * tf * (K1 + 1.0) / (tf + norm);
+                    matched.push(term.clone());
+                }
+                (!matched.is_empty()).then(|| FocusHit { path: doc.path.clone(), score, matched })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        hits.truncate(top);
+        hits
+    }
+}
+
+pub struct FocusGenerator {
+    registry: LanguageRegistry,
+    top: usize,
+}
+
+impl FocusGenerator {
+    pub fn new(registry: LanguageRegistry, top: usize) -> Self {
+        Self { registry, top }
+    }
+
+    /// Body of the FOCUS section: the top files with matching lines and full skeletons.
+    pub fn generate_focus(&self, query: &str, files: &[PathBuf], ranks: &FileRanks) -> Result<String> {
+        let sources: Vec<(PathBuf, String, Vec<SkeletonItem>)> = files
+            .par_iter()
+            .filter(|p| fs::metadata(p).is_ok_and(|m| m.len() <= MAX_FILE_SIZE_FOR_INDEX))
+            .filter_map(|p| {
+                let content = fs::read_to_string(p).ok()?;
+                let items = self.skeleton(p, &content);
+                Some((p.clone(), content, items))
+            })
+            .collect();
+
+        let index = FocusIndex::from_documents(
+            sources
+                .iter()
+                .map(|(path, content, items)| {
+                    // Skeleton text is a subset of the content; adding it again weights signatures and docs.
+                    let mut text = content.clone();
+                    items.iter().for_each(|i| {
+                        text.push('\n');
+                        text.push_str(&i.text);
+                    });
+                    (path.clone(), text)
+                })
+                .collect(),
+        );
+        let mut hits = index.search(query, self.top);
+        // Equal lexical scores: prefer the more central file.
+        hits.sort_by(|a, b| {
+            b.score.total_cmp(&a.score).then_with(|| ranks.score(&b.path).total_cmp(&ranks.score(&a.path)))
+        });
+
+        let mut output = format!("Query: \"{}\"\n", query.trim());
+        if hits.is_empty() {
+            output.push_str("\nNo files matched the query terms.\n");
+            return Ok(output);
+        }
+        output.push_str(&format!(
+            "Top {} files by BM25 over paths, identifiers, comments and skeleton text:\n",
+            hits.len()
+        ));
+        let by_path: HashMap<&Path, (&String, &Vec<SkeletonItem>)> =
+            sources.iter().map(|(p, c, i)| (p.as_path(), (c, i))).collect();
+
+        for (n, hit) in hits.iter().enumerate() {
+            let Some(&(content, items)) = by_path.get(hit.path.as_path()) else { continue };
+            output.push_str(&format!(
+                "\n{}. {}  (score {:.2}; matched: {})\n",
+                n + 1,
+                hit.path.to_string_lossy().replace('\\', "/"),
+                hit.score,
+                hit.matched.join(", ")
+            ));
+            output.push_str("   Snippets:\n");
+            for (line_no, line) in snippets(content, &hit.matched) {
+                output.push_str(&format!("     L{}: {}\n", line_no, line));
+            }
+            if !items.is_empty() {
+                output.push_str("   Skeleton:\n");
+                for item in items.iter().take(MAX_SKELETON_ITEMS) {
+                    let text = item.text.trim().replace('\n', "\n             ");
+                    output.push_str(&format!("     [{}-{}] {}\n", item.start_line, item.end_line, text));
+                }
+                if items.len() > MAX_SKELETON_ITEMS {
+                    output.push_str(&format!(
+                        "     … {} more entries in PACK_STAGE2_COMPRESSED.xml\n",
+                        items.len() - MAX_SKELETON_ITEMS
+                    ));
+                }
+            }
+        }
+        Ok(output)
+    }
+
+    /// `Full` skeleton minus plain comments (docs stay).
+    fn skeleton(&self, path: &Path, content: &str) -> Vec<SkeletonItem> {
+        let mut items = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(|ext| self.registry.get(ext))
+            .and_then(|spec| parser::skeleton_items(spec, content, SkeletonLevel::Full))
+            .unwrap_or_default();
+        items.retain(|i| i.role != ItemRole::Comment);
+        items
+    }
+}
+
+/// Lowercased search terms: words split on punctuation, `snake_case` and `camelCase`,
+/// minus one-letter tokens and English stopwords.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .flat_map(split_camel)
+        .map(|t| t.to_lowercase())
+        .filter(|t| t.chars().count() > 1 && !STOPWORDS.contains(&t.as_str()))
+        .collect()
+}
+
+/// `getHTTPResponse2` → `get`, `HTTP`, `Response`, `2`.
+fn split_camel(word: &str) -> Vec<&str> {
+    let chars: Vec<(usize, char)> = word.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for i in 1..chars.len() {
+        let (idx, cur) = chars[i];
+        let prev = chars[i - 1].1;
+        let next_is_lower = chars.get(i + 1).is_some_and(|(_, c)| c.is_lowercase());
+        let boundary = (prev.is_lowercase() && cur.is_uppercase())
+            || (prev.is_uppercase() && cur.is_uppercase() && next_is_lower)
+            || (prev.is_alphabetic() != cur.is_alphabetic());
+        if boundary {
+            parts.push(&word[start..idx]);
+            start = idx;
+        }
+    }
+    if start < word.len() {
+        parts.push(&word[start..]);
+    }
+    parts
+}
+
+/// Up to `SNIPPETS_PER_FILE` lines with the most distinct matched terms, in file order.
+fn snippets(content: &str, terms: &[String]) -> Vec<(usize, String)> {
+    let terms: HashSet<&str> = terms.iter().map(String::as_str).collect();
+    let mut scored: Vec<(usize, usize, &str)> = content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let found: HashSet<String> = tokenize(line).into_iter().filter(|t| terms.contains(t.as_str())).collect();
+            (!found.is_empty()).then_some((found.len(), i + 1, line))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored.truncate(SNIPPETS_PER_FILE);
+    scored.sort_by_key(|s| s.1);
+    scored
+        .into_iter()
+        .map(|(_, line_no, line)| {
+            let line = line.trim();
+            let clipped: String = line.chars().take(MAX_SNIPPET_CHARS).collect();
+            (line_no, if clipped.len() < line.len() { format!("{}…", clipped) } else { clipped })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tokenizer_splits_identifiers_and_drops_stopwords() {
+        assert_eq!(
+            tokenize("why does /users/:id return 500 in getHTTPResponse_v2"),
+            ["users", "id", "return", "500", "get", "http", "response"]
+        );
+    }
+
+    #[test]
+    fn bm25_prefers_path_and_identifier_matches() {
+        let index = FocusIndex::from_documents(vec![
+            (PathBuf::from("src/routes/users.ts"), "router.get('/users/:id', getUserById)".to_string()),
+            (PathBuf::from("src/db/pool.ts"), "export function connect() { /* users table */ }".to_string()),
+            (PathBuf::from("README.md"), "How to install".to_string()),
+        ]);
+        let hits = index.search("why does /users/:id return 500", 5);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].path, PathBuf::from("src/routes/users.ts"));
+        assert_eq!(hits[0].matched, ["users", "id"]);
+        assert!(hits[0].score > hits[1].score);
+    }
+
+    #[test]
+    fn snippets_pick_lines_with_most_terms() {
+        let content = "fn a() {}\nlet user = load(id);\n// user lookup\nfn b() {}\n";
+        let terms = vec!["user".to_string(), "id".to_string()];
+        assert_eq!(snippets(content, &terms), [(2, "let user = load(id);".to_string()), (3, "// user lookup".to_string())]);
+    }
+}
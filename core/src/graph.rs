@@ -0,0 +1,498 @@
+// saccade/core/src/graph.rs
+//
+// Intra-repo import graph and PageRank-based file importance.
+//
+// Nodes are the kept files; an edge a → b means "a imports b". Only imports
+// that resolve to another kept file become edges; third-party and stdlib
+// imports are dropped. Resolution is lexical and deliberately conservative:
+//   Rust    `mod x;`, `crate::`, `self::`, `super::`, workspace crate names
+//   JS/TS   relative specifiers only ("./x", "../x"), with extension/index probing
+//   Python  absolute imports from the file's package root, and relative imports
+//   Go      import paths under a `module` declared by a kept go.mod
+
+use crate::imports::{self, Import, ImportLang};
+use crate::manifest_text::{go_module_path, toml_entries, toml_value, unquote};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MAX_FILE_SIZE_FOR_IMPORTS: u64 = 2 * 1024 * 1024; // 2 MB
+const DAMPING: f64 = 0.85;
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-9;
+
+const JS_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+pub struct ImportGraph {
+    files: Vec<PathBuf>,
+    /// Out-edges per file index.
+    edges: Vec<BTreeSet<usize>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileRank {
+    pub path: PathBuf,
+    /// PageRank score; all scores sum to 1.
+    pub score: f64,
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+/// Per-file importance, looked up by path. Empty ranks score every file 0.
+#[derive(Debug, Clone, Default)]
+pub struct FileRanks {
+    ranks: Vec<FileRank>,
+    by_path: HashMap<PathBuf, usize>,
+}
+
+impl FileRanks {
+    pub fn get(&self, path: &Path) -> Option<&FileRank> {
+        self.by_path.get(path).map(|&i| &self.ranks[i])
+    }
+
+    pub fn score(&self, path: &Path) -> f64 {
+        self.get(path).map_or(0.0, |r| r.score)
+    }
+
+    /// Highest score first; ties broken by path so output is stable.
+    pub fn ranked(&self) -> Vec<&FileRank> {
+        let mut ranked: Vec<&FileRank> = self.ranks.iter().collect();
+        ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        ranked
+    }
+
+    /// Score relative to a uniform distribution (1.0 = an average file).
+    pub fn relative(&self, rank: &FileRank) -> f64 {
+        rank.score * self.ranks.len() as f64
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranks.is_empty()
+    }
+}
+
+impl ImportGraph {
+    /// Read and parse every file, then resolve its imports against the others.
+    pub fn build(files: &[PathBuf]) -> Self {
+        let imports: Vec<Vec<Import>> = files
+            .par_iter()
+            .map(|path| {
+                if imports::import_lang(path).is_none() {
+                    return Vec::new();
+                }
+                if fs::metadata(path).map_or(true, |m| m.len() > MAX_FILE_SIZE_FOR_IMPORTS) {
+                    return Vec::new();
+                }
+                fs::read_to_string(path).map(|c| imports::extract_imports(path, &c)).unwrap_or_default()
+            })
+            .collect();
+        Self::from_imports(files, &imports, |p| fs::read_to_string(p).ok())
+    }
+
+    /// Resolve already-extracted imports (`imports[i]` belongs to `files[i]`).
+    /// `read` supplies Cargo.toml / go.mod contents for crate and module names.
+    pub fn from_imports(files: &[PathBuf], imports: &[Vec<Import>], read: impl Fn(&Path) -> Option<String>) -> Self {
+        let resolver = Resolver::new(files, &read);
+        let edges = files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| {
+                let lang = imports::import_lang(file);
+                let mut targets = BTreeSet::new();
+                for import in imports.get(i).map(Vec::as_slice).unwrap_or(&[]) {
+                    for target in resolver.resolve(file, lang, import) {
+                        if target != i {
+                            targets.insert(target);
+                        }
+                    }
+                }
+                targets
+            })
+            .collect();
+        Self { files: files.to_vec(), edges }
+    }
+
+    pub fn files(&self) -> &[PathBuf] {
+        &self.files
+    }
+
+    /// Every (importer, imported) pair, by file index.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.edges.iter().enumerate().flat_map(|(from, tos)| tos.iter().map(move |&to| (from, to)))
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.iter().map(BTreeSet::len).sum()
+    }
+
+    /// Power-iteration PageRank; files with no imports spread their rank evenly.
+    pub fn pagerank(&self) -> Vec<f64> {
+        let n = self.files.len();
+        if n == 0 {
+            return Vec::new();
+        }
+        let uniform = 1.0 / n as f64;
+        let mut rank = vec![uniform; n];
+        for _ in 0..MAX_ITERATIONS {
+            let mut next = vec![(1.0 - DAMPING) * uniform; n];
+            let mut dangling = 0.0;
+            for (i, outs) in self.edges.iter().enumerate() {
+                if outs.is_empty() {
+                    dangling += rank[i];
+                } else {
+                    let share = DAMPING * rank[i] / outs.len() as f64;
+                    for &j in outs {
+                        next[j] += share;
+                    }
+                }
+            }
+            let spread = DAMPING * dangling * uniform;
+            next.iter_mut().for_each(|r| *r += spread);
+            let delta: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if delta < TOLERANCE {
+                break;
+            }
+        }
+        rank
+    }
+
+    pub fn ranks(&self) -> FileRanks {
+        let scores = self.pagerank();
+        let mut fan_in = vec![0usize; self.files.len()];
+        for (_, to) in self.edges() {
+            fan_in[to] += 1;
+        }
+        let ranks: Vec<FileRank> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, path)| FileRank { path: path.clone(), score: scores[i], fan_in: fan_in[i], fan_out: self.edges[i].len() })
+            .collect();
+        let by_path = ranks.iter().enumerate().map(|(i, r)| (r.path.clone(), i)).collect();
+        FileRanks { ranks, by_path }
+    }
+}
+
+/// Lookup tables built once per graph.
+struct Resolver {
+    index: HashMap<PathBuf, usize>,
+    /// Rust crate ident (dashes → underscores) → crate `src` dir.
+    rust_crates: HashMap<String, PathBuf>,
+    /// Python dotted module → (sys.path root, file index).
+    python_modules: HashMap<String, Vec<(PathBuf, usize)>>,
+    /// Go module path → module dir.
+    go_modules: Vec<(String, PathBuf)>,
+    /// Dir → non-test .go files in it.
+    go_packages: HashMap<PathBuf, Vec<usize>>,
+}
+
+impl Resolver {
+    fn new(files: &[PathBuf], read: &dyn Fn(&Path) -> Option<String>) -> Self {
+        let index: HashMap<PathBuf, usize> = files.iter().enumerate().map(|(i, p)| (normalize(p), i)).collect();
+        let mut resolver = Self {
+            index,
+            rust_crates: HashMap::new(),
+            python_modules: HashMap::new(),
+            go_modules: Vec::new(),
+            go_packages: HashMap::new(),
+        };
+        let py_dirs_with_init: HashSet<PathBuf> = resolver
+            .index
+            .keys()
+            .filter(|p| p.file_name().is_some_and(|n| n == "__init__.py"))
+            .filter_map(|p| p.parent().map(Path::to_path_buf))
+            .collect();
+
+        for (i, path) in files.iter().map(|p| normalize(p)).enumerate() {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            match name {
+                "Cargo.toml" => {
+                    if let Some(krate) = read(&files[i]).as_deref().and_then(cargo_package_name) {
+                        resolver.rust_crates.insert(krate.replace('-', "_"), dir.join("src"));
+                    }
+                }
+                "go.mod" => {
+                    if let Some(module) = read(&files[i]).as_deref().and_then(go_module_path).map(str::to_string) {
+                        resolver.go_modules.push((module, dir.clone()));
+                    }
+                }
+                _ => {}
+            }
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("py") => {
+                    let (root, module) = python_module(&path, &py_dirs_with_init);
+                    resolver.python_modules.entry(module).or_default().push((root, i));
+                }
+                Some("go") if !name.ends_with("_test.go") => resolver.go_packages.entry(dir).or_default().push(i),
+                _ => {}
+            }
+        }
+        // Longest module path first so nested modules win over their parents.
+        resolver.go_modules.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        resolver
+    }
+
+    fn lookup(&self, path: &Path) -> Option<usize> {
+        self.index.get(&normalize(path)).copied()
+    }
+
+    fn resolve(&self, file: &Path, lang: Option<ImportLang>, import: &Import) -> Vec<usize> {
+        let file = normalize(file);
+        let single = |hit: Option<usize>| -> Vec<usize> { hit.into_iter().collect() };
+        match lang {
+            Some(ImportLang::Rust) => single(self.resolve_rust(&file, import)),
+            Some(ImportLang::JavaScript) => single(self.resolve_js(&file, &import.specifier)),
+            Some(ImportLang::Python) => single(self.resolve_python(&file, &import.specifier)),
+            Some(ImportLang::Go) => self.resolve_go(&import.specifier),
+            None => Vec::new(),
+        }
+    }
+
+    fn resolve_rust(&self, file: &Path, import: &Import) -> Option<usize> {
+        let mod_dir = rust_module_dir(file);
+        if import.mod_decl {
+            return self.rust_module_file(&mod_dir.join(&import.specifier));
+        }
+        let segs: Vec<&str> = import.specifier.split("::").filter(|s| !s.is_empty()).collect();
+        let (first, rest) = segs.split_first()?;
+        match *first {
+            "crate" => self.rust_path(&self.rust_crate_root(file)?, rest, true),
+            "self" => self.rust_path(&mod_dir, rest, true),
+            "super" => {
+                let mut base = mod_dir.parent()?.to_path_buf();
+                let mut rest = rest;
+                while let Some((&"super", tail)) = rest.split_first() {
+                    base = base.parent()?.to_path_buf();
+                    rest = tail;
+                }
+                self.rust_path(&base, rest, true)
+            }
+            krate if self.rust_crates.contains_key(krate) => self.rust_path(&self.rust_crates[krate], rest, true),
+            // 2018-style path to a sibling module declared in this file or at the crate root.
+            _ => self
+                .rust_path(&mod_dir, &segs, false)
+                .or_else(|| self.rust_crate_root(file).and_then(|root| self.rust_path(&root, &segs, false))),
+        }
+    }
+
+    /// Longest prefix of `segs` under `base` that is a module file; optionally the base module itself.
+    fn rust_path(&self, base: &Path, segs: &[&str], fallback_to_base: bool) -> Option<usize> {
+        (1..=segs.len())
+            .rev()
+            .find_map(|i| self.rust_module_file(&segs[..i].iter().fold(base.to_path_buf(), |p, s| p.join(s))))
+            .or_else(|| if fallback_to_base { self.rust_module_file(base) } else { None })
+    }
+
+    /// `dir.rs`, `dir/mod.rs`, or the crate root file when `dir` is a crate's `src`.
+    fn rust_module_file(&self, dir: &Path) -> Option<usize> {
+        let candidates = [dir.with_extension("rs"), dir.join("mod.rs"), dir.join("lib.rs"), dir.join("main.rs")];
+        candidates.iter().find_map(|c| self.lookup(c))
+    }
+
+    fn rust_crate_root(&self, file: &Path) -> Option<PathBuf> {
+        file.ancestors()
+            .skip(1)
+            .find(|dir| self.lookup(&dir.join("lib.rs")).or_else(|| self.lookup(&dir.join("main.rs"))).is_some())
+            .map(Path::to_path_buf)
+    }
+
+    fn resolve_js(&self, file: &Path, spec: &str) -> Option<usize> {
+        if !(spec.starts_with("./") || spec.starts_with("../") || spec == "." || spec == "..") {
+            return None;
+        }
+        let base = normalize(&file.parent().unwrap_or(Path::new("")).join(spec));
+        if let Some(hit) = self.lookup(&base) {
+            return Some(hit);
+        }
+        // `import "./x.js"` in TS resolves to x.ts.
+        let stem = match base.extension().and_then(|e| e.to_str()) {
+            Some(ext) if JS_EXTENSIONS.contains(&ext) => base.with_extension(""),
+            _ => base.clone(),
+        };
+        JS_EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from(format!("{}.{}", stem.display(), ext)))
+            .chain(JS_EXTENSIONS.iter().map(|ext| base.join(format!("index.{}", ext))))
+            .find_map(|c| self.lookup(&c))
+    }
+
+    fn resolve_python(&self, file: &Path, spec: &str) -> Option<usize> {
+        let dots = spec.chars().take_while(|&c| c == '.').count();
+        let segs: Vec<&str> = spec[dots..].split('.').filter(|s| !s.is_empty()).collect();
+        if dots > 0 {
+            let mut base = file.parent()?.to_path_buf();
+            for _ in 1..dots {
+                base = base.parent()?.to_path_buf();
+            }
+            return (0..=segs.len()).rev().find_map(|i| {
+                let p = segs[..i].iter().fold(base.clone(), |p, s| p.join(s));
+                let module_file = if i == 0 { None } else { self.lookup(&p.with_extension("py")) };
+                module_file.or_else(|| self.lookup(&p.join("__init__.py")))
+            });
+        }
+        let (own_root, _) = python_module(file, &HashSet::new());
+        (1..=segs.len()).rev().find_map(|i| {
+            let candidates = self.python_modules.get(&segs[..i].join("."))?;
+            match candidates.as_slice() {
+                [(_, only)] => Some(*only),
+                many => many.iter().find(|(root, _)| file.starts_with(root) || *root == own_root).map(|(_, i)| *i),
+            }
+        })
+    }
+
+    fn resolve_go(&self, spec: &str) -> Vec<usize> {
+        for (module, dir) in &self.go_modules {
+            let rest = if spec == module {
+                ""
+            } else if let Some(rest) = spec.strip_prefix(module.as_str()).and_then(|r| r.strip_prefix('/')) {
+                rest
+            } else {
+                continue;
+            };
+            let pkg_dir = normalize(&dir.join(rest));
+            return self.go_packages.get(&pkg_dir).cloned().unwrap_or_default();
+        }
+        Vec::new()
+    }
+}
+
+/// Directory holding a Rust file's child modules.
+fn rust_module_dir(file: &Path) -> PathBuf {
+    let parent = file.parent().unwrap_or(Path::new("")).to_path_buf();
+    match file.file_name().and_then(|n| n.to_str()) {
+        Some("lib.rs" | "main.rs" | "mod.rs") => parent,
+        _ => parent.join(file.file_stem().unwrap_or_default()),
+    }
+}
+
+/// (sys.path root, dotted module name). The root is the parent of the topmost
+/// package (dir with `__init__.py`) containing the file.
+fn python_module(path: &Path, dirs_with_init: &HashSet<PathBuf>) -> (PathBuf, String) {
+    let mut root = path.parent().unwrap_or(Path::new("")).to_path_buf();
+    while dirs_with_init.contains(&root) {
+        match root.parent() {
+            Some(parent) => root = parent.to_path_buf(),
+            None => break,
+        }
+    }
+    let rel = path.strip_prefix(&root).unwrap_or(path).with_extension("");
+    let mut parts: Vec<String> = rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+    if parts.last().is_some_and(|p| p == "__init__") {
+        parts.pop();
+    }
+    (root, parts.join("."))
+}
+
+/// `name = "..."` under `[package]`.
+fn cargo_package_name(toml: &str) -> Option<String> {
+    toml_value(&toml_entries(toml), "package", "name").map(|name| unquote(name).to_string())
+}
+
+/// Lexically resolve `.`/`..` so `a/./b/../c.rs` and `a/c.rs` compare equal.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other.as_os_str()),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &str)]) -> ImportGraph {
+        let paths: Vec<PathBuf> = files.iter().map(|(p, _)| PathBuf::from(p)).collect();
+        let imports: Vec<Vec<Import>> = files.iter().map(|(p, c)| imports::extract_imports(Path::new(p), c)).collect();
+        let contents: HashMap<PathBuf, String> = files.iter().map(|(p, c)| (PathBuf::from(p), c.to_string())).collect();
+        ImportGraph::from_imports(&paths, &imports, |p| contents.get(p).cloned())
+    }
+
+    fn edge_names(g: &ImportGraph) -> Vec<(String, String)> {
+        let name = |i: usize| g.files()[i].to_string_lossy().into_owned();
+        g.edges().map(|(a, b)| (name(a), name(b))).collect()
+    }
+
+    #[test]
+    fn resolves_rust_modules_and_workspace_crates() {
+        let g = graph(&[
+            ("core/Cargo.toml", "[package]\nname = \"my-core\"\n"),
+            ("core/src/lib.rs", "pub mod util;\npub mod net;\n"),
+            ("core/src/util.rs", "use crate::net::client::Client;\n"),
+            ("core/src/net/mod.rs", "mod client;\n"),
+            ("core/src/net/client.rs", "use super::super::util;\n"),
+            ("cli/src/main.rs", "use my_core::util::helper;\nuse serde::Serialize;\n"),
+        ]);
+        let edges = edge_names(&g);
+        for expected in [
+            ("core/src/lib.rs", "core/src/util.rs"),
+            ("core/src/lib.rs", "core/src/net/mod.rs"),
+            ("core/src/util.rs", "core/src/net/client.rs"),
+            ("core/src/net/mod.rs", "core/src/net/client.rs"),
+            ("core/src/net/client.rs", "core/src/util.rs"),
+            ("cli/src/main.rs", "core/src/util.rs"),
+        ] {
+            assert!(edges.contains(&(expected.0.to_string(), expected.1.to_string())), "missing {:?} in {:?}", expected, edges);
+        }
+        assert_eq!(g.edge_count(), 6);
+    }
+
+    #[test]
+    fn resolves_js_python_and_go() {
+        let g = graph(&[
+            ("web/app.ts", "import { a } from './lib/a.js';\nimport b from './lib';\nimport React from 'react';\n"),
+            ("web/lib/a.ts", ""),
+            ("web/lib/index.ts", ""),
+            ("pkg/__init__.py", ""),
+            ("pkg/core.py", "from .util import helper\nimport pkg.models\nimport os\n"),
+            ("pkg/util.py", ""),
+            ("pkg/models/__init__.py", ""),
+            ("go.mod", "module example.com/app\n\ngo 1.22\n"),
+            ("main.go", "package main\nimport \"example.com/app/internal/x\"\n"),
+            ("internal/x/x.go", "package x\n"),
+            ("internal/x/x_test.go", "package x\n"),
+        ]);
+        let edges = edge_names(&g);
+        assert_eq!(
+            edges,
+            [
+                ("web/app.ts", "web/lib/a.ts"),
+                ("web/app.ts", "web/lib/index.ts"),
+                ("pkg/core.py", "pkg/util.py"),
+                ("pkg/core.py", "pkg/models/__init__.py"),
+                ("main.go", "internal/x/x.go"),
+            ]
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+        );
+    }
+
+    #[test]
+    fn hubs_outrank_leaves() {
+        let g = graph(&[
+            ("src/lib.rs", "mod hub;\nmod a;\nmod b;\n"),
+            ("src/hub.rs", ""),
+            ("src/a.rs", "use crate::hub;\n"),
+            ("src/b.rs", "use crate::hub;\n"),
+        ]);
+        let ranks = g.ranks();
+        let top = ranks.ranked()[0];
+        assert_eq!(top.path, PathBuf::from("src/hub.rs"));
+        assert_eq!(top.fan_in, 3);
+        let total: f64 = g.pagerank().iter().sum();
+        assert!((total - 1.0).abs() < 1e-6);
+        assert!(ranks.score(Path::new("src/lib.rs")) < top.score);
+    }
+}
@@ -1,87 +1,346 @@
 // saccade/core/src/heuristics.rs
+//
+// Layer-1 pre-filter plus the manifest detection funnel from
+// docs/dynamic-dependancy-detection.md:
+//   Layer 1  entropy pre-filter, Manifest Density Score (unique PAMP keywords / lines)
+//   Layer 2  Tree-sitter structural queries (line rules for grammar-less shapes);
+//            failures go to the ERAD rejection cache
+//   Layer 3  path-context multiplier → final confidence
+// Files the static name checks already recognize are not scored. Each file is read once:
+// its bytes serve the entropy check and Layer 1, and candidates keep the text for Layer 2.
 
-use crate::config::{CODE_BARE_PATTERN, CODE_EXT_PATTERN};
+use crate::config::{
+    AST_VALIDATION_QUERIES, CODE_BARE_PATTERN, CODE_EXT_PATTERN, ENTROPY_THRESHOLD, FINAL_CONFIDENCE_THRESHOLD,
+    MANIFEST_SCAN_SKIP_PATTERN, MANIFEST_STRUCTURE_RULES, MDS_CANDIDACY_THRESHOLD, PAMP_KEYWORDS, PATH_CONTEXT_RULES,
+};
+use crate::detection::is_known_build_file;
+use crate::exclusions::{Exclusion, ExclusionReason};
+use crate::lockfiles::LockFormat;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Parser, Query, QueryCursor, Tree};
 
 // --- Configuration Constants for Heuristics ---
 const MIN_TEXT_ENTROPY: f64 = 3.5;
 const MAX_TEXT_ENTROPY: f64 = 5.5;
-
-const BUILD_SYSTEM_PAMPS: &[&str] = &[
-    "find_package", "add_executable", "target_link_libraries", "cmake_minimum_required",
-    "project(", "add-apt-repository", "conanfile.py", "dependency", "require",
-    "include", "import", "version", "dependencies",
-];
+/// Larger files are never manifests worth scoring; their MDS would be negligible anyway.
+const MAX_CANDIDATE_BYTES: u64 = 256 * 1024;
 
 // Pre-compiled regexes for known code files
 static CODE_EXT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(CODE_EXT_PATTERN).unwrap());
 static CODE_BARE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(CODE_BARE_PATTERN).unwrap());
+static SCAN_SKIP_RE: Lazy<Regex> = Lazy::new(|| Regex::new(MANIFEST_SCAN_SKIP_PATTERN).unwrap());
+static STRUCTURE_RULES: Lazy<Vec<(&'static str, Regex)>> =
+    Lazy::new(|| MANIFEST_STRUCTURE_RULES.iter().map(|(name, pattern)| (*name, Regex::new(pattern).unwrap())).collect());
+/// `AST_VALIDATION_QUERIES` compiled for their grammars, in order.
+static AST_QUERIES: Lazy<Vec<(&'static str, &'static str, Query)>> = Lazy::new(|| {
+    AST_VALIDATION_QUERIES
+        .iter()
+        .map(|(name, grammar, source)| (*name, *grammar, Query::new(&host_grammar(grammar), source).unwrap()))
+        .collect()
+});
+static CONTEXT_RULES: Lazy<Vec<(Regex, f32)>> =
+    Lazy::new(|| PATH_CONTEXT_RULES.iter().map(|(pattern, factor)| (Regex::new(pattern).unwrap(), *factor)).collect());
 
-pub struct HeuristicFilter;
+/// The ERAD rejection cache: candidates that failed structural validation this run.
+pub type EradCache = HashSet<PathBuf>;
 
-impl HeuristicFilter {
-    pub fn new() -> Self { Self }
+/// A file scored by the funnel. `confidence` is `mds_score × context_score`.
+#[derive(Debug, Clone)]
+pub struct HeuristicCandidate {
+    pub path: PathBuf,
+    pub mds_score: f32,
+    /// PAMP keywords found, in `PAMP_KEYWORDS` order.
+    pub keywords: Vec<&'static str>,
+    /// Name of the first Layer-2 structure rule that matched.
+    pub structure: Option<&'static str>,
+    /// The line that matched it.
+    pub evidence: Option<String>,
+    pub context_score: f32,
+    pub confidence: f32,
+}
+
+pub struct HeuristicFilter {
+    mds_threshold: f32,
+    confidence_threshold: f32,
+    /// Layer-1 survivors and their text, waiting for `manifest_candidates`.
+    scored: Vec<(HeuristicCandidate, String)>,
+    erad_cache: EradCache,
+    /// Files dropped by the entropy band, for the exclusion report.
+    excluded: Vec<Exclusion>,
+}
 
-    pub fn filter(&self, files: Vec<std::path::PathBuf>) -> Vec<std::path::PathBuf> {
-        files.into_iter().filter(|path| self.should_keep(path)).collect()
+impl HeuristicFilter {
+    pub fn new() -> Self {
+        Self {
+            mds_threshold: MDS_CANDIDACY_THRESHOLD,
+            confidence_threshold: FINAL_CONFIDENCE_THRESHOLD,
+            scored: Vec::new(),
+            erad_cache: EradCache::new(),
+            excluded: Vec::new(),
+        }
     }
 
-    /// Determines whether a file should be kept based on layered heuristic rules.
-    fn should_keep(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
+    pub fn with_thresholds(mut self, mds: f32, confidence: f32) -> Self {
+        self.mds_threshold = mds;
+        self.confidence_threshold = confidence;
+        self
+    }
 
-        // Rule 1: Always keep known source code/config/markup files.
-        // This prevents small test files or simple scripts from being discarded by entropy.
-        if CODE_EXT_RE.is_match(&path_str) || CODE_BARE_RE.is_match(&path_str) {
-            return true;
+    /// Keeps text files and scores each kept one as a manifest candidate (Layer 1).
+    pub fn filter(&mut self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        let mut kept = Vec::new();
+        for path in files {
+            let known = is_known_text(&path);
+            let scorable = is_scorable(&path);
+            let read = (!known || scorable).then(|| fs::read(&path).map(|bytes| {
+                let entropy = entropy(&bytes);
+                (bytes, entropy)
+            }));
+            if !known {
+                if let Some((reason, detail)) = read.as_ref().and_then(exclusion) {
+                    self.excluded.push(Exclusion::new(&path, reason, detail));
+                    continue;
+                }
+            }
+            if let Some(Ok((bytes, entropy))) = read.filter(|_| scorable) {
+                self.scored.extend(self.score(&path, bytes, entropy));
+            }
+            kept.push(path);
         }
+        kept
+    }
 
-        // Rule 2: For unknown file types, apply entropy analysis to reject binaries.
-        if let Ok(entropy) = calculate_entropy(path) {
-            if entropy < MIN_TEXT_ENTROPY || entropy > MAX_TEXT_ENTROPY {
-                return false;
+    /// Layers 2 and 3 over the Layer-1 candidates still in `files`, best first.
+    pub fn manifest_candidates(&mut self, files: &[PathBuf]) -> Vec<HeuristicCandidate> {
+        let files: HashSet<&PathBuf> = files.iter().collect();
+        let mut out = Vec::new();
+        for (mut candidate, content) in std::mem::take(&mut self.scored) {
+            if !files.contains(&candidate.path) || self.erad_cache.contains(&candidate.path) {
+                continue;
+            }
+            let Some((rule, line)) = validate_structure(&content) else {
+                self.erad_cache.insert(candidate.path);
+                continue;
+            };
+            candidate.structure = Some(rule);
+            candidate.evidence = Some(line);
+            candidate.context_score = get_context_score(&candidate.path);
+            candidate.confidence = candidate.mds_score * candidate.context_score;
+            if candidate.confidence >= self.confidence_threshold {
+                out.push(candidate);
             }
-        } else {
-            return false; // Could not read file, reject.
         }
+        out.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then_with(|| a.path.cmp(&b.path)));
+        out
+    }
 
-        // Rule 3: If an unknown file passes entropy, check for PAMPs.
-        // This is how we discover non-standard manifests like `custom_build.cfg`.
-        if let Ok(content) = fs::read_to_string(path) {
-            let lower_content = content.to_lowercase();
-            for pamp in BUILD_SYSTEM_PAMPS {
-                if lower_content.contains(pamp) {
-                    return true;
-                }
-            }
+    /// Candidates rejected by structural validation so far.
+    pub fn rejected(&self) -> usize {
+        self.erad_cache.len()
+    }
+
+    /// Files dropped so far, with the reason; drains the list.
+    pub fn take_exclusions(&mut self) -> Vec<Exclusion> {
+        std::mem::take(&mut self.excluded)
+    }
+
+    /// Layer 1: MDS for a file `is_scorable` accepted, from the bytes `filter` read.
+    fn score(&self, path: &Path, bytes: Vec<u8>, entropy: f64) -> Option<(HeuristicCandidate, String)> {
+        if bytes.is_empty() || entropy > ENTROPY_THRESHOLD {
+            return None;
         }
-        
-        // Default: If it's an unknown file type that passed entropy but has no PAMPs,
-        // we assume it's likely just a text file (e.g., notes.txt) and keep it for now.
-        // The next filter stage will handle it.
-        true
+        let content = String::from_utf8(bytes).ok()?;
+        let (mds_score, keywords) = manifest_density_score(&content);
+        let candidate = HeuristicCandidate {
+            path: path.to_path_buf(),
+            mds_score,
+            keywords,
+            structure: None,
+            evidence: None,
+            context_score: 1.0,
+            confidence: mds_score,
+        };
+        (mds_score >= self.mds_threshold).then_some((candidate, content))
+    }
+}
+
+impl Default for HeuristicFilter {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-/// Calculates the Shannon entropy of a file's content.
-fn calculate_entropy(path: &Path) -> std::io::Result<f64> {
-    let bytes = fs::read(path)?;
-    if bytes.is_empty() { return Ok(0.0); }
+/// Known source code, config and markup. These are always kept, so small test files or
+/// simple scripts are never discarded by entropy.
+fn is_known_text(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    CODE_EXT_RE.is_match(&path_str) || CODE_BARE_RE.is_match(&path_str)
+}
+
+/// Whether Layer 1 scores the file: not covered by a static name check, not source or
+/// prose, and small enough to be a manifest.
+fn is_scorable(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else { return false };
+    !is_standard_manifest(name)
+        && !SCAN_SKIP_RE.is_match(name)
+        && fs::metadata(path).is_ok_and(|m| m.len() <= MAX_CANDIDATE_BYTES)
+}
+
+/// Why an unknown file type is dropped, from its read: entropy outside the text band
+/// (binary), or unreadable. Files that pass are kept; whether they are manifests is the
+/// funnel's call.
+fn exclusion(read: &std::io::Result<(Vec<u8>, f64)>) -> Option<(ExclusionReason, Option<String>)> {
+    match read {
+        Ok((_, entropy)) if (MIN_TEXT_ENTROPY..=MAX_TEXT_ENTROPY).contains(entropy) => None,
+        Ok((_, entropy)) => Some((ExclusionReason::BinaryContent, Some(format!("entropy {:.2}", entropy)))),
+        Err(e) => Some((ExclusionReason::Unreadable, Some(e.to_string()))),
+    }
+}
+
+/// Names whose dependencies DEPS already reads: build files, lockfiles and their companions.
+fn is_standard_manifest(name: &str) -> bool {
+    is_known_build_file(name)
+        || LockFormat::ALL.iter().any(|format| format.file_name() == name)
+        || (name.starts_with("requirements") && name.ends_with(".txt"))
+        || matches!(name, "go.sum" | "go.work" | "flake.lock" | "Package.resolved" | "libs.versions.toml" | "pnpm-workspace.yaml")
+}
+
+/// `unique PAMP keywords / (lines + 1)`, and the keywords found.
+pub fn manifest_density_score(content: &str) -> (f32, Vec<&'static str>) {
+    let lower = content.to_lowercase();
+    let keywords: Vec<&'static str> =
+        PAMP_KEYWORDS.iter().copied().filter(|k| lower.contains(&k.to_lowercase())).collect();
+    let lines = content.lines().count();
+    (keywords.len() as f32 / (lines + 1) as f32, keywords)
+}
+
+/// Layer 2: the first `AST_VALIDATION_QUERIES` structure in `content`, else the first
+/// `MANIFEST_STRUCTURE_RULES` line rule, with the line it is on.
+pub fn validate_structure(content: &str) -> Option<(&'static str, String)> {
+    let evidence = |offset: usize| -> String {
+        let line = content[offset..].trim_start().lines().next().unwrap_or_default();
+        line.chars().take(80).collect()
+    };
+    // Each grammar parses the file once, on its first query.
+    let mut trees: HashMap<&str, Option<Tree>> = HashMap::new();
+    let mut cursor = QueryCursor::new();
+    for (name, grammar, query) in AST_QUERIES.iter() {
+        let tree = trees.entry(grammar).or_insert_with(|| {
+            let mut parser = Parser::new();
+            parser.set_language(&host_grammar(grammar)).ok()?;
+            parser.parse(content, None)
+        });
+        let Some(tree) = tree else { continue };
+        let node = cursor.matches(query, tree.root_node(), content.as_bytes()).find_map(|m| m.captures.first().map(|c| c.node));
+        if let Some(node) = node {
+            let line_start = content[..node.start_byte()].rfind('\n').map_or(0, |i| i + 1);
+            return Some((*name, evidence(line_start)));
+        }
+    }
+    STRUCTURE_RULES.iter().find_map(|(name, re)| Some((*name, evidence(re.find(content)?.start()))))
+}
+
+/// Grammar a Layer-2 query names.
+fn host_grammar(name: &str) -> Language {
+    match name {
+        "cmake" => tree_sitter_cmake::language(),
+        _ => tree_sitter_python::language(),
+    }
+}
+
+/// Layer 3: product of every matching path rule's multiplier.
+pub fn get_context_score(path: &Path) -> f32 {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let path = path.trim_start_matches("./");
+    CONTEXT_RULES.iter().filter(|(re, _)| re.is_match(path)).map(|(_, factor)| factor).product()
+}
+
+fn entropy(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() { return 0.0; }
 
     let mut freq_map = HashMap::new();
-    for &byte in &bytes {
+    for &byte in bytes {
         *freq_map.entry(byte).or_insert(0) += 1;
     }
 
     let len = bytes.len() as f64;
-    let entropy = freq_map.values().fold(0.0, |acc, &count| {
+    freq_map.values().fold(0.0, |acc, &count| {
         let probability = count as f64 / len;
         acc - probability * probability.log2()
-    });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn funnel_scores_validates_and_weights_by_path() {
+        let manifest = "[dependencies]\nzlib/1.2.13\nfmt/10.2.1\n# library versions for the project\n";
+        let (mds, keywords) = manifest_density_score(manifest);
+        assert_eq!(keywords, ["dependencies", "version", "project", "library"]);
+        assert!((mds - 0.8).abs() < f32::EPSILON);
+        assert_eq!(validate_structure(manifest), Some(("dependency section", "[dependencies]".to_string())));
+
+        // Right words, wrong shape: rejected by Layer 2.
+        assert_eq!(validate_structure("This project has a dependency on a library version.\n"), None);
+
+        assert_eq!(get_context_score(Path::new("./custom_build.cfg")), 1.5);
+        assert_eq!(get_context_score(Path::new("build/deps.cfg")), 1.25);
+        assert_eq!(get_context_score(Path::new("docs/examples/deps.cfg")), 0.25);
+        assert!(is_standard_manifest("Cargo.lock") && is_standard_manifest("meson.build"));
+        assert!(!is_standard_manifest("custom_build.cfg"));
+    }
+
+    #[test]
+    fn layer_two_queries_match_manifest_shapes_in_their_host_grammar() {
+        let cases = [
+            ("find_package(ZLIB REQUIRED)\ntarget_link_libraries(app ZLIB::ZLIB)\n", "build call", "find_package(ZLIB REQUIRED)"),
+            ("def requirements(self):\n    self.requires(\"fmt/10.2.1\")\n", "build call", "self.requires(\"fmt/10.2.1\")"),
+            ("[Deps]\nzlib\n", "dependency section", "[Deps]"),
+            ("name: tool\ndepends: libfoo\n", "dependency key", "depends: libfoo"),
+            ("{\n  \"name\": \"x\",\n  \"peerDependencies\": {\"react\": \"18\"}\n}\n", "dependency key", "\"peerDependencies\": {\"react\": \"18\"}"),
+            ("libs {\n  'com.google.guava:guava:33.0.0'\n}\n", "gradle coordinate", "'com.google.guava:guava:33.0.0'"),
+            ("all: app\n\tcc -o app main.c -lz\n", "make rule", "all: app"),
+            ("<deps>\n  <artifactId>core</artifactId>\n</deps>\n", "maven coordinates", "<artifactId>core</artifactId>"),
+        ];
+        for (content, structure, line) in cases {
+            assert_eq!(validate_structure(content), Some((structure, line.to_string())), "{}", content);
+        }
+        // The words without the shape: prose, a comment, a string value.
+        for content in [
+            "The dependencies are listed in the project wiki.\n",
+            "# find_package(ZLIB)\n",
+            "{\"note\": \"dependencies: none\"}\n",
+        ] {
+            assert_eq!(validate_structure(content), None, "{}", content);
+        }
+    }
+
+    #[test]
+    fn funnel_reads_each_file_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let manifest = dir.path().join("custom_build.cfg");
+        let prose = dir.path().join("NOTES");
+        let binary = dir.path().join("blob.dat");
+        fs::write(&manifest, "[dependencies]\nzlib/1.2.13\nfmt/10.2.1\n# library versions for the project\n").unwrap();
+        fs::write(&prose, "The project dependency list:\nsee the library version table.\n").unwrap();
+        fs::write(&binary, (0..=255u8).cycle().take(4096).collect::<Vec<u8>>()).unwrap();
 
-    Ok(entropy)
-}
\ No newline at end of file
+        let mut filter = HeuristicFilter::new();
+        let kept = filter.filter(vec![manifest.clone(), prose.clone(), binary.clone()]);
+        assert_eq!(kept, [manifest.clone(), prose.clone()]);
+        assert_eq!(filter.take_exclusions()[0].reason, ExclusionReason::BinaryContent);
+
+        // Layer 2 works on the text Layer 1 read, not on a second read.
+        fs::write(&manifest, "rewritten after Layer 1\n").unwrap();
+        let candidates = filter.manifest_candidates(&kept);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].structure, Some("dependency section"));
+        assert_eq!(filter.rejected(), 1);
+    }
+}
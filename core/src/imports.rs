@@ -0,0 +1,225 @@
+// saccade/core/src/imports.rs
+//
+// Pulls raw import specifiers out of source files with Tree-sitter. Nothing
+// here knows about the repo layout; `graph.rs` resolves specifiers to files.
+//
+// Specifier shapes per language:
+//   Rust    `crate::a::b::C`, `super::x`, `serde::Deserialize`; `mod foo;` → "foo" (mod_decl)
+//   JS/TS   the module string as written: "./util", "../lib/index.js", "react"
+//   Python  dotted module, relative dots kept: "pkg.mod", ".utils", "..core.x"
+//   Go      the import path: "github.com/org/repo/internal/x", "fmt"
+
+use crate::manifest_text::unquote;
+use std::path::Path;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor};
+
+const RUST_IMPORTS: &str = r#"
+(use_declaration argument: (_) @use)
+(mod_item name: (identifier) @mod !body)
+"#;
+
+const JS_IMPORTS: &str = r#"
+(import_statement source: (string) @src)
+(export_statement source: (string) @src)
+(call_expression
+  function: (identifier) @fn
+  arguments: (arguments . (string) @src)
+  (#eq? @fn "require"))
+"#;
+
+const PYTHON_IMPORTS: &str = r#"
+(import_statement) @import
+(import_from_statement) @from
+"#;
+
+const GO_IMPORTS: &str = r#"
+(import_spec path: (_) @src)
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImportLang {
+    Rust,
+    JavaScript,
+    Python,
+    Go,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub specifier: String,
+    /// 1-based line of the import statement.
+    pub line: usize,
+    /// Rust `mod foo;` (a child module file), as opposed to a `use` path.
+    pub mod_decl: bool,
+}
+
+/// Language whose imports we understand, by file extension.
+pub fn import_lang(path: &Path) -> Option<ImportLang> {
+    match path.extension()?.to_str()?.to_ascii_lowercase().as_str() {
+        "rs" => Some(ImportLang::Rust),
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => Some(ImportLang::JavaScript),
+        "py" => Some(ImportLang::Python),
+        "go" => Some(ImportLang::Go),
+        _ => None,
+    }
+}
+
+/// All import specifiers in `content`, in source order. Empty for unknown languages.
+pub fn extract_imports(path: &Path, content: &str) -> Vec<Import> {
+    let Some(lang) = import_lang(path) else { return Vec::new() };
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_ascii_lowercase();
+    let (language, query_src): (Language, &str) = match (lang, ext.as_str()) {
+        (ImportLang::Rust, _) => (tree_sitter_rust::language(), RUST_IMPORTS),
+        (ImportLang::JavaScript, "ts") => (tree_sitter_typescript::language_typescript(), JS_IMPORTS),
+        (ImportLang::JavaScript, "tsx") => (tree_sitter_typescript::language_tsx(), JS_IMPORTS),
+        (ImportLang::JavaScript, _) => (tree_sitter_javascript::language(), JS_IMPORTS),
+        (ImportLang::Python, _) => (tree_sitter_python::language(), PYTHON_IMPORTS),
+        (ImportLang::Go, _) => (tree_sitter_go::language(), GO_IMPORTS),
+    };
+
+    let mut parser = Parser::new();
+    if parser.set_language(&language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else { return Vec::new() };
+    let query = match Query::new(&language, query_src) {
+        Ok(q) => q,
+        Err(e) => {
+            eprintln!("WARN: import query failed for {:?} at row {} col {}: {}", lang, e.row, e.column, e.message);
+            return Vec::new();
+        }
+    };
+
+    let src = content.as_bytes();
+    let mut imports = Vec::new();
+    let mut cursor = QueryCursor::new();
+    for m in cursor.matches(&query, tree.root_node(), src) {
+        for c in m.captures {
+            let capture = query.capture_names()[c.index as usize];
+            let node = c.node;
+            let line = node.start_position().row + 1;
+            let text = node.utf8_text(src).unwrap_or("");
+            let mut push = |specifier: String, mod_decl: bool| {
+                if !specifier.is_empty() {
+                    imports.push(Import { specifier, line, mod_decl });
+                }
+            };
+            match capture {
+                "use" => expand_use_tree(text).into_iter().for_each(|s| push(s, false)),
+                "mod" => push(text.to_string(), true),
+                "src" => push(unquote(text).to_string(), false),
+                "import" => python_names(node, src).into_iter().for_each(|s| push(s, false)),
+                "from" => python_from(node, src).into_iter().for_each(|s| push(s, false)),
+                _ => {}
+            }
+        }
+    }
+    imports
+}
+
+/// `crate::a::{b, c::{D, E as F}, self}` → `crate::a::b`, `crate::a::c::D`, `crate::a::c::E`, `crate::a`.
+fn expand_use_tree(text: &str) -> Vec<String> {
+    let text: String = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut out = Vec::new();
+    expand_into("", &text, &mut out);
+    out
+}
+
+fn expand_into(prefix: &str, tree: &str, out: &mut Vec<String>) {
+    let tree = tree.trim();
+    let join = |p: &str, s: &str| if p.is_empty() { s.to_string() } else { format!("{}::{}", p, s) };
+    if let Some(open) = tree.find('{') {
+        let head = tree[..open].trim_end_matches("::").trim();
+        let close = tree.rfind('}').unwrap_or(tree.len());
+        let inner = &tree[open + 1..close.max(open + 1)];
+        let base = if head.is_empty() { prefix.to_string() } else { join(prefix, head) };
+        for part in split_top_level(inner) {
+            expand_into(&base, part, out);
+        }
+        return;
+    }
+    let path = tree.split(" as ").next().unwrap_or("").trim();
+    match path {
+        "" => {}
+        "self" => out.push(prefix.to_string()),
+        "*" => out.push(prefix.to_string()),
+        _ => out.push(join(prefix, path.trim_end_matches("::*"))),
+    }
+}
+
+/// Split on commas that are not nested inside braces.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0usize);
+    for (i, ch) in s.char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Module names of a Python `import` statement (`import a.b as c, d` → `a.b`, `d`).
+fn python_names(node: Node, src: &[u8]) -> Vec<String> {
+    let mut cursor = node.walk();
+    node.children_by_field_name("name", &mut cursor)
+        .filter_map(|n| {
+            let target = if n.kind() == "aliased_import" { n.child_by_field_name("name")? } else { n };
+            target.utf8_text(src).ok().map(str::to_string)
+        })
+        .collect()
+}
+
+/// `from .pkg import a, b` → `.pkg.a`, `.pkg.b`; `from . import x` → `.x`; `from m import *` → `m`.
+/// Imported names may be symbols rather than modules; resolution falls back to the longest prefix.
+fn python_from(node: Node, src: &[u8]) -> Vec<String> {
+    let Some(module) = node.child_by_field_name("module_name").and_then(|m| m.utf8_text(src).ok()) else {
+        return Vec::new();
+    };
+    let names = python_names(node, src);
+    if names.is_empty() {
+        return vec![module.to_string()];
+    }
+    names
+        .into_iter()
+        .map(|n| if module.ends_with('.') { format!("{}{}", module, n) } else { format!("{}.{}", module, n) })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn specs(path: &str, content: &str) -> Vec<String> {
+        extract_imports(Path::new(path), content).into_iter().map(|i| i.specifier).collect()
+    }
+
+    #[test]
+    fn rust_use_trees_are_expanded() {
+        let src = "use crate::a::{b, c::{D, E as F}, self};\nuse std::fs;\nmod parser;\nmod inline { }\n";
+        let imports = extract_imports(Path::new("lib.rs"), src);
+        let got: Vec<&str> = imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(got, ["crate::a::b", "crate::a::c::D", "crate::a::c::E", "crate::a", "std::fs", "parser"]);
+        assert!(imports.last().unwrap().mod_decl);
+        assert_eq!(imports.last().unwrap().line, 3);
+    }
+
+    #[test]
+    fn js_python_and_go_specifiers() {
+        let js = "import x from './util';\nexport { y } from \"../lib/index.js\";\nconst z = require('react');\n";
+        assert_eq!(specs("a.ts", js), ["./util", "../lib/index.js", "react"]);
+
+        let py = "import os.path as p, sys\nfrom .utils import helper\nfrom .. import core\nfrom pkg.mod import *\n";
+        assert_eq!(specs("m.py", py), ["os.path", "sys", ".utils.helper", "..core", "pkg.mod"]);
+
+        let go = "package main\nimport (\n  \"fmt\"\n  x \"example.com/app/internal/x\"\n)\n";
+        assert_eq!(specs("main.go", go), ["fmt", "example.com/app/internal/x"]);
+    }
+}
@@ -1,34 +1,73 @@
 // In saccade/core/src/lib.rs
 
+pub mod build_manifests;
+pub mod cmake;
 pub mod config;
+pub mod cpp_manifests;
+pub mod deps_usage;
 pub mod detection;
 pub mod enumerate;
 pub mod error;
+pub mod exclusions;
 pub mod filter;
+pub mod focus;
+pub mod graph;
 pub mod guide;
 pub mod heuristics;
+pub mod imports;
+pub mod licenses;
+pub mod lockfiles;
 pub mod manifest;
+pub mod manifest_text;
+pub mod module_graph;
 pub mod parser;
+pub mod pii;
+pub mod refs;
+pub mod registry;
+pub mod report;
 pub mod request;
+pub mod runbook;
+pub mod safety;
+pub mod secrets;
 pub mod stage0;
 pub mod stage1;
 pub mod stage2;
+pub mod symbols;
+pub mod tools;
+pub mod workspace;
 
 use config::Config;
-use detection::Detector;
+use deps_usage::DepsUsage;
+use detection::{DetectionReport, Detector};
 use enumerate::FileEnumerator;
 use error::{Result, SaccadeError};
+use exclusions::ExclusionReport;
 use filter::FileFilter;
+use focus::FocusGenerator;
+use graph::{FileRanks, ImportGraph};
 use guide::GuideGenerator;
-use heuristics::HeuristicFilter;
+use heuristics::{HeuristicCandidate, HeuristicFilter};
+use licenses::{LicenseInventory, LocalSources};
 use manifest::{ManifestGenerator, ProjectInfoContext};
+use module_graph::ModuleGraph;
+use pii::PiiRedactor;
+use refs::RefsReport;
+use registry::LanguageRegistry;
+use report::PackReport;
+use request::{RequestContext, RequestFile, ResolvedRequest};
+use runbook::Runbook;
+use safety::SafetyReport;
+use secrets::RedactionReport;
 use stage0::Stage0Generator;
 use stage1::Stage1Generator;
 use stage2::Stage2Generator;
+use symbols::SymbolIndex;
+use tools::ToolExecutor;
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::Duration;
 
 pub(crate) const PACK_FILE_NAME: &str = "PACK.txt";
 
@@ -36,11 +75,35 @@ pub struct SaccadePack {
     config: Config,
 }
 
+/// Whole-repo analyses computed once and shared by the pack sections.
+struct RepoAnalysis {
+    ranks: FileRanks,
+    module_graph: ModuleGraph,
+    /// Manifests found by content rather than by name, best first.
+    manifest_candidates: Vec<HeuristicCandidate>,
+    detection: DetectionReport,
+    /// Every dropped path with its reason; Stage-2 adds its skips before PACK.txt is written.
+    exclusions: ExclusionReport,
+}
+
+/// The files a pack keeps, and what selecting them found along the way.
+struct FileSelection {
+    raw_count: usize,
+    files: Vec<PathBuf>,
+    manifest_candidates: Vec<HeuristicCandidate>,
+    exclusions: ExclusionReport,
+}
+
 struct PackContent {
+    focus: Option<String>,
     project: String,
+    runbook: String,
     structure: String,
+    graph: String,
     apis: String,
     deps: String,
+    deps_usage: String,
+    licenses: String,
     guide: String,
 }
 
@@ -49,12 +112,12 @@ impl SaccadePack {
 
     pub fn generate(&self) -> Result<()> {
         self.config.validate()?;
-        let (raw_count, filtered_files) = self.enumerate_and_filter_files()?;
+        let FileSelection { raw_count, files: filtered_files, manifest_candidates, exclusions } = self.enumerate_and_filter_files()?;
 
         // --- DCA: Layer 2 Detection acts as the Environmental Signal ---
         eprintln!("🔬  [Layer 2] Performing structural validation (AST analysis)...");
-        let detector = Detector::new();
-        let detected_systems = detector.detect_build_systems(&filtered_files)?;
+        let detection = Detector::new().detect(&filtered_files)?;
+        let detected_systems = detection.detected();
         eprintln!("    • Detected build systems: [{}]", detected_systems.iter().map(|s| s.to_string()).collect::<Vec<_>>().join(", "));
         // --- End DCA Step ---
 
@@ -67,31 +130,136 @@ impl SaccadePack {
         }
 
         self.prepare_output_directory()?;
-        let pack_content = self.generate_pack_content(raw_count, &filtered_files, &rust_crates, &frontend_dirs, &detected_systems)?;
-        self.write_pack_file(&pack_content, &filtered_files)?;
-        let stage2_result = self.generate_stage2(&filtered_files);
+        let mut analysis = self.analyze(&filtered_files, manifest_candidates, detection, exclusions);
+        analysis.module_graph.write(&self.config.pack_dir)?;
+        let mut redactions = RedactionReport::default();
+        let mut safety = SafetyReport::new(self.config.safety_mode);
+        let stage2_result = self.generate_stage2(&filtered_files, &mut analysis, &mut redactions, &mut safety);
         if let Err(e) = &stage2_result {
             eprintln!("    WARN: Internal parser failed: {}", e);
         }
-        self.print_summary(&filtered_files, !pack_content.deps.is_empty(), &stage2_result)?;
+        let pack_content = self.generate_pack_content(raw_count, &filtered_files, &rust_crates, &frontend_dirs, &detected_systems, &analysis)?;
+        self.write_pack_file(&pack_content, &mut safety, &mut redactions)?;
+        PackReport::new(&filtered_files, &analysis.ranks)
+            .with_manifest_candidates(&analysis.manifest_candidates)
+            .with_detection(&analysis.detection)
+            .with_exclusions(&analysis.exclusions)
+            .write(&self.config.pack_dir)?;
+        analysis.exclusions.write(&self.config.pack_dir)?;
+        self.log_redactions(&redactions);
+        self.log_safety(&safety);
+        self.print_summary(&filtered_files, !pack_content.deps.is_empty(), &stage2_result, &redactions)?;
         Ok(())
     }
 
-    fn enumerate_and_filter_files(&self) -> Result<(usize, Vec<PathBuf>)> {
+    /// `saccade detect`: which build systems the pack would see, and why.
+    pub fn detect(&self) -> Result<DetectionReport> {
+        self.config.validate()?;
+        let selection = self.enumerate_and_filter_files()?;
+        Detector::new().detect(&selection.files)
+    }
+
+    /// `saccade refs <symbol>`: every reference among the files a pack would keep.
+    pub fn find_references(&self, symbol: &str) -> Result<RefsReport> {
+        self.config.validate()?;
+        let files = self.enumerate_and_filter_files()?.files;
+        eprintln!("🔎  Searching {} files for references to '{}'…", files.len(), symbol);
+        let registry = LanguageRegistry::load(&self.config.language_dirs);
+        let mut report = refs::find_references(&files, Path::new("."), symbol, &registry);
+        let pii = self.pii_redactor();
+        for r in report.definitions.iter_mut().chain(report.references.iter_mut()) {
+            if self.config.redact_secrets {
+                r.snippet = secrets::redact(&r.snippet).0;
+            }
+            r.snippet = pii.redact(&r.snippet).0;
+        }
+        Ok(report)
+    }
+
+    /// Resolve a REQUEST_FILE against the files a pack would keep.
+    pub fn resolve_request(&self, request: &RequestFile) -> Result<ResolvedRequest> {
+        self.config.validate()?;
+        let files: Vec<PathBuf> = self
+            .enumerate_and_filter_files()?
+            .files
+            .into_iter()
+            .map(|p| p.strip_prefix(".").map(Path::to_path_buf).unwrap_or(p))
+            .collect();
+        Ok(request.resolve_with(&files, Path::new("."), &self.request_context())?)
+    }
+
+    /// The configured grammars and content filters, plus the SYMBOLS.txt of the last pack run if there is one.
+    pub fn request_context(&self) -> RequestContext {
+        let context = RequestContext::new()
+            .with_registry(LanguageRegistry::load(&self.config.language_dirs))
+            .with_redaction(self.config.redact_secrets)
+            .with_safety(self.config.safety_mode)
+            .with_pii(self.pii_redactor());
+        let symbols_path = self.config.pack_dir.join(symbols::SYMBOLS_FILE_NAME);
+        if !symbols_path.is_file() {
+            return context;
+        }
+        match SymbolIndex::load(&symbols_path) {
+            Ok(index) => context.with_symbols(index),
+            Err(e) => {
+                eprintln!("WARN: ignoring {}: {}", symbols_path.display(), e);
+                context
+            }
+        }
+    }
+
+    /// The configured PII pipeline: built-in rules plus `pii_rules`.
+    pub fn pii_redactor(&self) -> PiiRedactor {
+        PiiRedactor::new(self.config.pii_mode).with_rules(&self.config.pii_rules)
+    }
+
+    fn enumerate_and_filter_files(&self) -> Result<FileSelection> {
         eprintln!("📂  Enumerating files…");
         let enumerator = FileEnumerator::new(self.config.clone());
-        let raw_files = enumerator.enumerate()?;
-        eprintln!("    • Found {} files (raw)", raw_files.len());
+        let (raw_files, pruned) = enumerator.enumerate_with_exclusions()?;
+        let raw_count = raw_files.len();
+        eprintln!("    • Found {} files (raw)", raw_count);
+        let mut exclusions = ExclusionReport::default();
+        exclusions.extend(pruned);
 
         eprintln!("🔬  [Layer 1] Applying heuristic filters (entropy, content patterns)…");
-        let heuristic_files = HeuristicFilter::new().filter(raw_files);
+        let mut heuristics = HeuristicFilter::new()
+            .with_thresholds(self.config.manifest_mds_threshold, self.config.manifest_confidence_threshold);
+        let heuristic_files = heuristics.filter(raw_files);
+        exclusions.extend(heuristics.take_exclusions());
         eprintln!("    • Kept {} files after heuristic pre-filtering", heuristic_files.len());
 
         eprintln!("🧹  Filtering (secrets, binaries, includes/excludes, code-only={})…", self.config.code_only);
         let filter = FileFilter::new(self.config.clone())?;
-        let filtered_files = filter.filter(heuristic_files);
+        let (filtered_files, excluded) = filter.filter_with_exclusions(heuristic_files);
+        exclusions.extend(excluded);
         eprintln!("    • Kept {} files after final filtering", filtered_files.len());
-        Ok((filtered_files.len(), filtered_files))
+
+        let manifest_candidates = heuristics.manifest_candidates(&filtered_files);
+        eprintln!(
+            "    • {} non-standard manifest(s) by content score ({} rejected structurally)",
+            manifest_candidates.len(),
+            heuristics.rejected()
+        );
+        if self.config.verbose {
+            for c in &manifest_candidates {
+                eprintln!("      {:.2}  {} (MDS {:.2} × context {:.2})", c.confidence, c.path.display(), c.mds_score, c.context_score);
+            }
+        }
+        Ok(FileSelection { raw_count, files: filtered_files, manifest_candidates, exclusions })
+    }
+
+    fn analyze(&self, files: &[PathBuf], manifest_candidates: Vec<HeuristicCandidate>, detection: DetectionReport, exclusions: ExclusionReport) -> RepoAnalysis {
+        eprintln!("🕸️   Building import graph…");
+        let graph = ImportGraph::build(files);
+        eprintln!("    • Resolved {} intra-repo imports", graph.edge_count());
+        RepoAnalysis {
+            ranks: graph.ranks(),
+            module_graph: ModuleGraph::build(&graph, self.config.graph_granularity, &self.config.layers),
+            manifest_candidates,
+            detection,
+            exclusions,
+        }
     }
 
     fn prepare_output_directory(&self) -> Result<()> {
@@ -101,47 +269,153 @@ impl SaccadePack {
         })
     }
 
-    fn generate_pack_content(&self, raw_count: usize, files: &[PathBuf], rust_crates: &[PathBuf], frontend_dirs: &[PathBuf], detected_systems: &[detection::BuildSystemType]) -> Result<PackContent> {
+    fn generate_pack_content(&self, raw_count: usize, files: &[PathBuf], rust_crates: &[PathBuf], frontend_dirs: &[PathBuf], detected_systems: &[detection::BuildSystemType], analysis: &RepoAnalysis) -> Result<PackContent> {
         eprintln!("📦  Generating consolidated pack content…");
-        let info_ctx = ProjectInfoContext { raw_count, filtered_count: files.len(), pack_dir: &self.config.pack_dir, in_git: is_in_git_repo(), files, detected_systems };
-        let stage1 = Stage1Generator::new();
+        let info_ctx = ProjectInfoContext { raw_count, filtered_count: files.len(), pack_dir: &self.config.pack_dir, in_git: is_in_git_repo(), files, detected_systems, detection: &analysis.detection, exclusions: &analysis.exclusions };
+        let tools = ToolExecutor::new()
+            .with_timeout(Duration::from_secs(self.config.tool_timeout_secs))
+            .with_offline(self.config.offline);
+        let stage1 = Stage1Generator::new()
+            .with_tools(tools)
+            .with_manifest_candidates(analysis.manifest_candidates.clone());
+        let focus = match &self.config.focus {
+            Some(query) => {
+                eprintln!("🎯  Ranking files for focus query…");
+                let registry = LanguageRegistry::load(&self.config.language_dirs);
+                Some(FocusGenerator::new(registry, self.config.focus_top).generate_focus(query, files, &analysis.ranks)?)
+            }
+            None => None,
+        };
         Ok(PackContent {
+            focus,
             project: ManifestGenerator::new(self.config.clone()).generate_project_info(&info_ctx)?,
-            structure: Stage0Generator::new(self.config.clone()).generate_combined_structure(files, detected_systems)?,
+            runbook: {
+                let runbook = Runbook::compile(Path::new("."), files, &analysis.detection);
+                if runbook.is_empty() { String::new() } else { runbook.render() }
+            },
+            structure: Stage0Generator::new(self.config.clone()).generate_combined_structure(files, detected_systems, &analysis.ranks)?,
+            graph: analysis.module_graph.render(),
             apis: stage1.generate_combined_apis(rust_crates, frontend_dirs, files)?,
             deps: stage1.generate_all_deps(detected_systems)?,
+            deps_usage: {
+                let usage = DepsUsage::analyze(files);
+                if usage.is_empty() { String::new() } else { usage.render() }
+            },
+            licenses: {
+                let inventory = LicenseInventory::collect(files, &LocalSources::detect(Path::new(".")));
+                if inventory.is_empty() { String::new() } else { inventory.render() }
+            },
             guide: GuideGenerator::new().generate_guide()?,
         })
     }
 
-    fn write_pack_file(&self, content: &PackContent, _filtered_files: &[PathBuf]) -> Result<()> {
-        let mut combined = format!("=======PROJECT=======\n{}\n=======END-OF-PROJECT=======\n\n", content.project);
-        combined.push_str(&format!("=======STRUCTURE=======\n{}\n=======END-OF-STRUCTURE=======\n\n", content.structure));
-        combined.push_str(&format!("=======APIS=======\n{}\n=======END-OF-APIS=======\n\n", content.apis));
+    /// Scans every repository-derived section for prompt injection, adds SAFETY after PROJECT
+    /// when anything was found, then redacts secrets and PII across the whole file.
+    fn write_pack_file(&self, content: &PackContent, safety: &mut SafetyReport, redactions: &mut RedactionReport) -> Result<()> {
+        let mut sections: Vec<(&str, String)> = Vec::new();
+        if let Some(focus) = &content.focus {
+            sections.push(("FOCUS", focus.clone()));
+        }
+        sections.push(("PROJECT", content.project.clone()));
+        if !content.runbook.is_empty() {
+            sections.push(("RUNBOOK", content.runbook.clone()));
+        }
+        sections.push(("STRUCTURE", content.structure.clone()));
+        sections.push(("GRAPH", content.graph.clone()));
+        sections.push(("APIS", content.apis.clone()));
         if !content.deps.trim().is_empty() {
-            combined.push_str(&format!("=======DEPS=======\n{}\n=======END-OF-DEPS=======\n\n", content.deps));
+            sections.push(("DEPS", content.deps.clone()));
         }
-        combined.push_str(&format!("=======GUIDE=======\n{}\n=======END-OF-GUIDE=======\n", content.guide));
+        if !content.deps_usage.is_empty() {
+            sections.push(("DEPS-USAGE", content.deps_usage.clone()));
+        }
+        if !content.licenses.is_empty() {
+            sections.push(("LICENSES", content.licenses.clone()));
+        }
+        for (name, body) in &mut sections {
+            *body = safety.scan(body, name, 1);
+        }
+        if !safety.is_empty() {
+            let at = sections.iter().position(|(name, _)| *name == "PROJECT").map_or(0, |i| i + 1);
+            sections.insert(at, ("SAFETY", safety.render()));
+        }
+        sections.push(("GUIDE", content.guide.clone()));
+
+        let mut combined = sections
+            .iter()
+            .map(|(name, body)| format!("======={}=======\n{}\n=======END-OF-{}=======\n", name, body, name))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.config.redact_secrets {
+            combined = redactions.redact(&combined, PACK_FILE_NAME, 1);
+        }
+        combined = redactions.redact_pii(&self.pii_redactor(), &combined, PACK_FILE_NAME, 1);
         let pack_path = self.config.pack_dir.join(PACK_FILE_NAME);
         fs::write(&pack_path, combined).map_err(|e| SaccadeError::Io { source: e, path: pack_path })
     }
 
-    fn generate_stage2(&self, filtered_files: &[PathBuf]) -> Result<Option<String>> {
+    fn generate_stage2(&self, filtered_files: &[PathBuf], analysis: &mut RepoAnalysis, redactions: &mut RedactionReport, safety: &mut SafetyReport) -> Result<Option<String>> {
         eprintln!("🔧  [Stage 2] Generating compressed skeleton with internal parser…");
         let stage2_path = self.config.pack_dir.join("PACK_STAGE2_COMPRESSED.xml");
-        Stage2Generator::new().with_verbose(self.config.verbose).generate(filtered_files, &stage2_path)
+        let registry = LanguageRegistry::load(&self.config.language_dirs);
+        if self.config.verbose {
+            eprintln!("    Stage-2: Languages: [{}]", registry.language_names().join(", "));
+        }
+        let stage2 = Stage2Generator::new()
+            .with_verbose(self.config.verbose)
+            .with_registry(registry)
+            .with_level(self.config.skeleton_level)
+            .with_ranks(analysis.ranks.clone())
+            .with_token_budget(self.config.stage2_token_budget)
+            .with_symbols_output(self.config.pack_dir.join(symbols::SYMBOLS_FILE_NAME))
+            .with_redaction(self.config.redact_secrets)
+            .with_safety(self.config.safety_mode)
+            .with_pii(self.pii_redactor());
+        let result = stage2.generate(filtered_files, &stage2_path);
+        redactions.extend(stage2.take_redactions());
+        safety.extend(stage2.take_safety_findings());
+        analysis.exclusions.extend(stage2.take_exclusions());
+        result
+    }
+
+    /// Counts per rule; the SAFETY section has the locations.
+    fn log_safety(&self, safety: &SafetyReport) {
+        if !safety.is_empty() {
+            eprintln!("🛡️   {} prompt-injection finding(s), listed in SAFETY: {}", safety.len(), safety.summary());
+        }
     }
 
-    fn print_summary(&self, filtered_files: &[PathBuf], has_deps: bool, stage2_result: &Result<Option<String>>) -> Result<()> {
+    /// Counts per kind; with `--verbose`, where each one was. Secrets themselves are never logged.
+    fn log_redactions(&self, redactions: &RedactionReport) {
+        if redactions.is_empty() {
+            return;
+        }
+        eprintln!("🔒  Redacted {} secret/PII value(s) from pack content: {}", redactions.len(), redactions.summary());
+        if self.config.verbose {
+            for line in redactions.render().lines() {
+                eprintln!("      {}", line);
+            }
+        }
+    }
+
+    fn print_summary(&self, filtered_files: &[PathBuf], has_deps: bool, stage2_result: &Result<Option<String>>, redactions: &RedactionReport) -> Result<()> {
         let total_bytes: u64 = filtered_files.iter().filter_map(|p| fs::metadata(p).ok().map(|m| m.len())).sum();
         eprintln!("\n📊 Pack Summary\n────────────────────────────────");
         eprintln!("  Output File : {}", self.config.pack_dir.join(PACK_FILE_NAME).display());
+        eprintln!("  JSON Report : {}", self.config.pack_dir.join(report::PACK_JSON_FILE_NAME).display());
         eprintln!("  Files Kept  : {} files", filtered_files.len());
         eprintln!("  Size (est.) : {} bytes  (~{} tokens)", total_bytes, (total_bytes as f64 / 3.5) as u64);
         eprintln!("  Security    : ✔ Secrets & obvious binaries filtered");
+        eprintln!("  Excluded    : {}", self.config.pack_dir.join(exclusions::EXCLUDED_FILE_NAME).display());
+        if !redactions.is_empty() {
+            eprintln!("  Redacted    : {} secret/PII value(s) in content", redactions.len());
+        }
 
         match stage2_result {
-            Ok(_) => eprintln!("  Stage-2 XML : {}", self.config.pack_dir.join("PACK_STAGE2_COMPRESSED.xml").display()),
+            Ok(_) => {
+                eprintln!("  Stage-2 XML : {}", self.config.pack_dir.join("PACK_STAGE2_COMPRESSED.xml").display());
+                eprintln!("  Symbols     : {}", self.config.pack_dir.join(symbols::SYMBOLS_FILE_NAME).display());
+            }
             Err(e) => eprintln!("  Stage-2 XML : FAILED ({})", e),
         }
         eprintln!("────────────────────────────────\n");
@@ -162,7 +436,7 @@ impl SaccadePack {
         eprintln!("  - Output directory: {}", self.config.pack_dir.display());
         eprintln!("  - Found {} Rust crate(s)", rust_crates.len());
         eprintln!("  - Found {} frontend dir(s)", frontend_dirs.len());
-        eprintln!("  - Would produce: ai-pack/{} (single file) + PACK_STAGE2_COMPRESSED.xml", PACK_FILE_NAME);
+        eprintln!("  - Would produce: ai-pack/{} (single file) + {} + {} + PACK_STAGE2_COMPRESSED.xml", PACK_FILE_NAME, report::PACK_JSON_FILE_NAME, exclusions::EXCLUDED_FILE_NAME);
         Ok(())
     }
 }
@@ -0,0 +1,485 @@
+// saccade/core/src/licenses.rs
+//
+// LICENSES: what licenses the project and its dependencies ship under, read
+// only from what is on disk. Project packages come from the kept manifests.
+// Dependencies come from the root lockfiles, looked up in locally available
+// sources:
+//   Rust    vendor/ (cargo vendor), $CARGO_HOME/registry/src/*/<name>-<version>
+//   Node    node_modules/<name>/package.json
+//   Python  .venv/ or venv/ site-packages *.dist-info/METADATA
+//   Go      vendor/<module>/, $GOMODCACHE/<module>@<version>/ (LICENSE text)
+// Source files are checked for `SPDX-License-Identifier:` headers.
+
+use crate::lockfiles::{normalize_python_name, LockFormat, LockedPackage, Lockfile};
+use crate::manifest_text::{inline_table_value, quoted_strings, toml_entries, toml_value, unquote, TomlEntry};
+use rayon::prelude::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+const SPDX_HEADER_LINES: usize = 30;
+const MAX_NAMES_PER_LICENSE: usize = 8;
+const MAX_LISTED_MISSING: usize = 20;
+const LICENSE_FILE_NAMES: &[&str] =
+    &["LICENSE", "LICENSE.md", "LICENSE.txt", "LICENSE-MIT", "LICENSE-APACHE", "COPYING", "LICENCE"];
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "js", "jsx", "mjs", "cjs", "ts", "tsx", "c", "h", "cc", "cpp", "hpp", "java", "kt", "rb", "php",
+    "cs", "swift", "scala", "lua", "sh", "m", "mm",
+];
+
+/// Where dependency sources may already be on disk.
+#[derive(Debug, Clone, Default)]
+pub struct LocalSources {
+    pub root: PathBuf,
+    /// `registry/src/<index>` directories holding `<name>-<version>` crates.
+    pub cargo_registries: Vec<PathBuf>,
+    pub go_mod_cache: Option<PathBuf>,
+}
+
+impl LocalSources {
+    /// `root` plus the Cargo registry and Go module cache from the environment.
+    pub fn detect(root: &Path) -> Self {
+        let home = std::env::var_os("HOME").map(PathBuf::from);
+        let cargo_home =
+            std::env::var_os("CARGO_HOME").map(PathBuf::from).or_else(|| home.as_ref().map(|h| h.join(".cargo")));
+        let cargo_registries = cargo_home
+            .and_then(|h| fs::read_dir(h.join("registry").join("src")).ok())
+            .map(|dirs| dirs.flatten().map(|e| e.path()).filter(|p| p.is_dir()).collect())
+            .unwrap_or_default();
+        let go_mod_cache = std::env::var_os("GOMODCACHE")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("GOPATH").map(|p| PathBuf::from(p).join("pkg").join("mod")))
+            .or_else(|| home.map(|h| h.join("go").join("pkg").join("mod")))
+            .filter(|p| p.is_dir());
+        Self { root: root.to_path_buf(), cargo_registries, go_mod_cache }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageLicense {
+    pub ecosystem: &'static str,
+    pub name: String,
+    pub version: String,
+    /// SPDX expression or free text; `None` when metadata was found without one.
+    pub license: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct LicenseInventory {
+    /// Project packages: (manifest, name, license).
+    pub project: Vec<(String, String, Option<String>)>,
+    pub dependencies: Vec<PackageLicense>,
+    /// Locked packages whose sources are not on disk.
+    pub unavailable: BTreeMap<&'static str, usize>,
+    /// SPDX identifier → source files carrying it.
+    pub spdx: BTreeMap<String, usize>,
+    pub source_files: usize,
+    pub files_without_spdx: Vec<String>,
+}
+
+impl LicenseInventory {
+    pub fn collect(files: &[PathBuf], sources: &LocalSources) -> Self {
+        let mut inventory = LicenseInventory { project: project_licenses(files, &sources.root), ..Default::default() };
+
+        let locked: Vec<Lockfile> =
+            LockFormat::ALL.iter().filter_map(|&format| Lockfile::read(&sources.root, format)).collect();
+        let lookups: Vec<(&'static str, Option<PackageLicense>)> = locked
+            .iter()
+            .flat_map(|lockfile| lockfile.packages.iter().map(move |p| (lockfile.format.ecosystem(), p)))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(ecosystem, package)| (ecosystem, dependency_license(ecosystem, package, sources)))
+            .collect();
+        for (ecosystem, found) in lookups {
+            match found {
+                Some(license) => inventory.dependencies.push(license),
+                None => *inventory.unavailable.entry(ecosystem).or_default() += 1,
+            }
+        }
+        inventory.dependencies.sort_by(|a, b| (a.ecosystem, &a.name, &a.version).cmp(&(b.ecosystem, &b.name, &b.version)));
+        inventory.dependencies.dedup();
+
+        let headers: Vec<(String, Option<String>)> = files
+            .par_iter()
+            .filter(|p| is_source_file(p))
+            .map(|p| (p.to_string_lossy().replace('\\', "/"), spdx_identifier(p)))
+            .collect();
+        inventory.source_files = headers.len();
+        for (file, spdx) in headers {
+            match spdx {
+                Some(id) => *inventory.spdx.entry(id).or_default() += 1,
+                None => inventory.files_without_spdx.push(file),
+            }
+        }
+        inventory.files_without_spdx.sort();
+        inventory
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.project.is_empty() && self.dependencies.is_empty() && self.unavailable.is_empty() && self.spdx.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("========================================\n");
+        out.push_str("LICENSES (local metadata only)\n");
+        out.push_str("========================================\n\n");
+
+        if !self.project.is_empty() {
+            out.push_str("Project packages:\n");
+            for (manifest, name, license) in &self.project {
+                out.push_str(&format!("  {}  {}  ({})\n", name, license.as_deref().unwrap_or("NO LICENSE"), manifest));
+            }
+            out.push('\n');
+        }
+
+        let total = self.dependencies.len() + self.unavailable.values().sum::<usize>();
+        if total > 0 {
+            out.push_str(&format!(
+                "Dependencies: {} of {} locked packages inspected locally\n",
+                self.dependencies.len(),
+                total
+            ));
+            let mut by_license: BTreeMap<&str, Vec<&PackageLicense>> = BTreeMap::new();
+            for dep in self.dependencies.iter().filter(|d| d.license.is_some()) {
+                by_license.entry(dep.license.as_deref().unwrap_or_default()).or_default().push(dep);
+            }
+            let mut by_license: Vec<_> = by_license.into_iter().collect();
+            by_license.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+            for (license, deps) in by_license {
+                let names: Vec<&str> = deps.iter().take(MAX_NAMES_PER_LICENSE).map(|d| d.name.as_str()).collect();
+                let more = deps.len().saturating_sub(MAX_NAMES_PER_LICENSE);
+                out.push_str(&format!("  {:<28} {:>4}  {}", license, deps.len(), names.join(", ")));
+                if more > 0 {
+                    out.push_str(&format!(", … {} more", more));
+                }
+                out.push('\n');
+            }
+            let unlicensed: Vec<String> = self
+                .dependencies
+                .iter()
+                .filter(|d| d.license.is_none())
+                .map(|d| format!("{} {} [{}]", d.name, d.version, d.ecosystem))
+                .collect();
+            if !unlicensed.is_empty() {
+                out.push_str(&format!("  Lacking a license ({}): {}\n", unlicensed.len(), unlicensed.join(", ")));
+            }
+            for (ecosystem, count) in &self.unavailable {
+                out.push_str(&format!("  Not on disk: {} {} package(s) ({})\n", count, ecosystem, fetch_hint(ecosystem)));
+            }
+            out.push('\n');
+        }
+
+        out.push_str(&format!(
+            "Source files with SPDX-License-Identifier: {} of {}\n",
+            self.source_files - self.files_without_spdx.len(),
+            self.source_files
+        ));
+        for (id, count) in &self.spdx {
+            out.push_str(&format!("  {:<28} {:>4}\n", id, count));
+        }
+        if !self.spdx.is_empty() && !self.files_without_spdx.is_empty() {
+            let missing = &self.files_without_spdx;
+            let listed: Vec<&str> = missing.iter().take(MAX_LISTED_MISSING).map(String::as_str).collect();
+            out.push_str(&format!("  Lacking a header ({}): {}", missing.len(), listed.join(", ")));
+            if missing.len() > MAX_LISTED_MISSING {
+                out.push_str(&format!(", … {} more", missing.len() - MAX_LISTED_MISSING));
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn fetch_hint(ecosystem: &str) -> &'static str {
+    match ecosystem {
+        "rust" => "run `cargo fetch` or `cargo vendor` to inspect",
+        "node" => "run `npm install` to inspect",
+        "python" => "install into .venv to inspect",
+        _ => "run `go mod download` to inspect",
+    }
+}
+
+fn is_source_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| SOURCE_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// `SPDX-License-Identifier: X` within the first lines of `path`.
+fn spdx_identifier(path: &Path) -> Option<String> {
+    let file = fs::File::open(path).ok()?;
+    BufReader::new(file).lines().take(SPDX_HEADER_LINES).map_while(|l| l.ok()).find_map(|line| {
+        let (_, rest) = line.split_once("SPDX-License-Identifier:")?;
+        let id = rest.trim().trim_end_matches("*/").trim_end_matches("-->").trim();
+        (!id.is_empty()).then(|| id.to_string())
+    })
+}
+
+fn project_licenses(files: &[PathBuf], root: &Path) -> Vec<(String, String, Option<String>)> {
+    let workspace_license = fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|c| cargo_license(&toml_entries(&c), "workspace.package", None));
+    let mut out = Vec::new();
+    for path in files {
+        let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+        if !matches!(file_name, "Cargo.toml" | "package.json" | "pyproject.toml") {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(path) else { continue };
+        let manifest = path.to_string_lossy().replace('\\', "/");
+        let found = match file_name {
+            "Cargo.toml" => {
+                let entries = toml_entries(&content);
+                toml_value(&entries, "package", "name")
+                    .map(|name| (unquote(name).to_string(), cargo_license(&entries, "package", workspace_license.as_deref())))
+            }
+            "package.json" => serde_json::from_str::<Value>(&content).ok().and_then(|json| {
+                let name = json.get("name")?.as_str()?.to_string();
+                Some((name, node_license(&json)))
+            }),
+            "pyproject.toml" => {
+                let entries = toml_entries(&content);
+                ["project", "tool.poetry"].iter().find_map(|table| {
+                    let name = unquote(toml_value(&entries, table, "name")?).to_string();
+                    Some((name, pyproject_license(&entries, table)))
+                })
+            }
+            _ => None,
+        };
+        if let Some((name, license)) = found {
+            out.push((manifest, name, license));
+        }
+    }
+    out.sort();
+    out
+}
+
+/// `license = "…"`, `license.workspace = true`, or `license-file = "…"` in `table`.
+fn cargo_license(entries: &[TomlEntry], table: &str, workspace: Option<&str>) -> Option<String> {
+    if let Some(license) = toml_value(entries, table, "license") {
+        if license.starts_with('{') {
+            return workspace.map(str::to_string);
+        }
+        return Some(unquote(license).to_string());
+    }
+    if toml_value(entries, table, "license.workspace") == Some("true") {
+        return workspace.map(str::to_string);
+    }
+    toml_value(entries, table, "license-file").map(|f| format!("see {}", unquote(f)))
+}
+
+/// `"license": "MIT"`, `{ "type": "MIT" }`, or the legacy `"licenses": [{ "type": … }]`.
+fn node_license(json: &Value) -> Option<String> {
+    let type_of = |v: &Value| v.as_str().or_else(|| v.get("type")?.as_str()).map(str::to_string);
+    if let Some(license) = json.get("license").and_then(type_of) {
+        return Some(license);
+    }
+    let legacy: Vec<String> = json.get("licenses")?.as_array()?.iter().filter_map(type_of).collect();
+    (!legacy.is_empty()).then(|| legacy.join(" OR "))
+}
+
+/// PEP 639 `license = "MIT"`, `license = { text = "…" }`, or a license classifier.
+fn pyproject_license(entries: &[TomlEntry], table: &str) -> Option<String> {
+    if let Some(license) = toml_value(entries, table, "license") {
+        return if license.starts_with('{') {
+            inline_table_value(license, "text").map(str::to_string)
+        } else {
+            Some(unquote(license).to_string())
+        };
+    }
+    let classifiers = toml_value(entries, table, "classifiers")?;
+    quoted_strings(classifiers).into_iter().find_map(license_classifier)
+}
+
+/// `License :: OSI Approved :: MIT License` → `MIT License`.
+fn license_classifier(classifier: &str) -> Option<String> {
+    let last = classifier.strip_prefix("License ::")?.rsplit("::").next()?.trim();
+    (last != "OSI Approved").then(|| last.to_string())
+}
+
+fn dependency_license(ecosystem: &'static str, package: &LockedPackage, sources: &LocalSources) -> Option<PackageLicense> {
+    let license = match ecosystem {
+        "rust" => rust_license(package, sources),
+        "node" => node_package_license(package, &sources.root),
+        "python" => python_license(package, &sources.root),
+        _ => go_license(package, sources),
+    }?;
+    Some(PackageLicense { ecosystem, name: package.name.clone(), version: package.version.clone(), license })
+}
+
+/// `Some(license)` when the crate sources are on disk.
+fn rust_license(package: &LockedPackage, sources: &LocalSources) -> Option<Option<String>> {
+    let versioned = format!("{}-{}", package.name, package.version);
+    let vendor = sources.root.join("vendor");
+    let dir = [vendor.join(&versioned), vendor.join(&package.name)]
+        .into_iter()
+        .chain(sources.cargo_registries.iter().map(|r| r.join(&versioned)))
+        .find(|d| d.join("Cargo.toml").is_file())?;
+    let entries = toml_entries(&fs::read_to_string(dir.join("Cargo.toml")).ok()?);
+    let license = match cargo_license(&entries, "package", None) {
+        Some(file) if file.starts_with("see ") => license_text_in(&dir).or(Some(file)),
+        other => other,
+    };
+    Some(license)
+}
+
+fn node_package_license(package: &LockedPackage, root: &Path) -> Option<Option<String>> {
+    let manifest = root.join("node_modules").join(&package.name).join("package.json");
+    let json: Value = serde_json::from_str(&fs::read_to_string(manifest).ok()?).ok()?;
+    Some(node_license(&json))
+}
+
+/// `<name>-<version>.dist-info/METADATA` in a project-local virtualenv.
+fn python_license(package: &LockedPackage, root: &Path) -> Option<Option<String>> {
+    let wanted = normalize_python_name(&package.name);
+    let site_packages = [".venv", "venv"]
+        .iter()
+        .filter_map(|venv| fs::read_dir(root.join(venv).join("lib")).ok())
+        .flat_map(|dirs| dirs.flatten().map(|e| e.path().join("site-packages")))
+        .chain([".venv", "venv"].iter().map(|venv| root.join(venv).join("Lib").join("site-packages")));
+    for dir in site_packages {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(stem) = file_name.strip_suffix(".dist-info") else { continue };
+            let name = stem.rsplit_once('-').map_or(stem, |(name, _)| name);
+            if normalize_python_name(name) == wanted {
+                let metadata = fs::read_to_string(entry.path().join("METADATA")).ok()?;
+                return Some(python_metadata_license(&metadata));
+            }
+        }
+    }
+    None
+}
+
+/// `License-Expression:`, a short `License:` field, or a license classifier.
+fn python_metadata_license(metadata: &str) -> Option<String> {
+    let headers = metadata.split("\n\n").next().unwrap_or(metadata);
+    let field = |name: &str| {
+        headers.lines().find_map(|l| l.strip_prefix(name)).map(str::trim).filter(|v| !v.is_empty() && *v != "UNKNOWN")
+    };
+    field("License-Expression:")
+        .or_else(|| field("License:").filter(|v| v.len() <= 60))
+        .map(str::to_string)
+        .or_else(|| headers.lines().filter_map(|l| l.strip_prefix("Classifier:")).find_map(|c| license_classifier(c.trim())))
+}
+
+fn go_license(package: &LockedPackage, sources: &LocalSources) -> Option<Option<String>> {
+    let vendored = sources.root.join("vendor").join(&package.name);
+    let cached = sources
+        .go_mod_cache
+        .as_ref()
+        .map(|cache| cache.join(format!("{}@{}", go_case_encode(&package.name), package.version)));
+    let dir = std::iter::once(vendored).chain(cached).find(|d| d.is_dir())?;
+    Some(license_text_in(&dir))
+}
+
+/// The module cache escapes capitals: `github.com/BurntSushi` → `github.com/!burnt!sushi`.
+fn go_case_encode(module: &str) -> String {
+    module.chars().fold(String::new(), |mut out, c| {
+        if c.is_ascii_uppercase() {
+            out.push('!');
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(c);
+        }
+        out
+    })
+}
+
+/// Classify the LICENSE/COPYING files in `dir` by their well-known wording.
+fn license_text_in(dir: &Path) -> Option<String> {
+    let mut found: Vec<&str> = LICENSE_FILE_NAMES
+        .iter()
+        .filter_map(|name| fs::read_to_string(dir.join(name)).ok())
+        .filter_map(|text| classify_license_text(&text))
+        .collect();
+    found.sort();
+    found.dedup();
+    (!found.is_empty()).then(|| found.join(" OR "))
+}
+
+fn classify_license_text(text: &str) -> Option<&'static str> {
+    let has = |needle: &str| text.contains(needle);
+    let id = if has("Apache License") && has("Version 2.0") {
+        "Apache-2.0"
+    } else if has("Permission is hereby granted, free of charge") {
+        "MIT"
+    } else if has("Mozilla Public License") && has("2.0") {
+        "MPL-2.0"
+    } else if has("GNU LESSER GENERAL PUBLIC LICENSE") {
+        if has("Version 3") { "LGPL-3.0" } else { "LGPL-2.1" }
+    } else if has("GNU AFFERO GENERAL PUBLIC LICENSE") {
+        "AGPL-3.0"
+    } else if has("GNU GENERAL PUBLIC LICENSE") {
+        if has("Version 3") { "GPL-3.0" } else { "GPL-2.0" }
+    } else if has("Redistribution and use in source and binary forms") {
+        if has("Neither the name") || has("names of its contributors") { "BSD-3-Clause" } else { "BSD-2-Clause" }
+    } else if has("Permission to use, copy, modify, and/or distribute this software") {
+        "ISC"
+    } else if has("This is free and unencumbered software released into the public domain") {
+        "Unlicense"
+    } else if has("zlib License") || has("This software is provided 'as-is'") {
+        "Zlib"
+    } else {
+        return None;
+    };
+    Some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_project_dependency_and_spdx_licenses() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let files = vec![
+            write("Cargo.toml", "[workspace]\nmembers = [\"app\"]\n\n[workspace.package]\nlicense = \"MIT OR Apache-2.0\"\n"),
+            write("app/Cargo.toml", "[package]\nname = \"app\"\nlicense.workspace = true\n\n[dependencies]\nitoa = \"1\"\nodd = \"0.1\"\n"),
+            write("app/src/main.rs", "// SPDX-License-Identifier: MIT OR Apache-2.0\nfn main() {}\n"),
+            write("app/src/util.rs", "pub fn f() {}\n"),
+        ];
+        write(
+            "Cargo.lock",
+            "version = 3\n\n[[package]]\nname = \"app\"\nversion = \"0.1.0\"\ndependencies = [\"itoa\", \"odd\", \"gone\"]\n\n\
+             [[package]]\nname = \"itoa\"\nversion = \"1.0.11\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n\
+             [[package]]\nname = \"odd\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n\
+             [[package]]\nname = \"gone\"\nversion = \"2.0.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n",
+        );
+        write("vendor/itoa/Cargo.toml", "[package]\nname = \"itoa\"\nlicense = \"MIT OR Apache-2.0\"\n");
+        write("vendor/odd-0.1.0/Cargo.toml", "[package]\nname = \"odd\"\nlicense-file = \"COPYING\"\n");
+        write("vendor/odd-0.1.0/COPYING", "Permission to use, copy, modify, and/or distribute this software for any purpose\n");
+
+        let sources = LocalSources { root: root.to_path_buf(), ..Default::default() };
+        let inventory = LicenseInventory::collect(&files, &sources);
+        assert_eq!(inventory.project.len(), 1);
+        assert_eq!((inventory.project[0].1.as_str(), inventory.project[0].2.as_deref()), ("app", Some("MIT OR Apache-2.0")));
+        let deps: Vec<(&str, Option<&str>)> =
+            inventory.dependencies.iter().map(|d| (d.name.as_str(), d.license.as_deref())).collect();
+        assert_eq!(deps, [("itoa", Some("MIT OR Apache-2.0")), ("odd", Some("ISC"))]);
+        assert_eq!(inventory.unavailable.get("rust"), Some(&1));
+        assert_eq!((inventory.source_files, inventory.spdx.get("MIT OR Apache-2.0")), (2, Some(&1)));
+        assert!(inventory.files_without_spdx[0].ends_with("app/src/util.rs"));
+        assert!(inventory.render().contains("Not on disk: 1 rust package(s)"));
+    }
+
+    #[test]
+    fn reads_python_metadata_and_node_license_shapes() {
+        let metadata = "Metadata-Version: 2.1\nName: requests\nLicense: Apache 2.0\nClassifier: License :: OSI Approved :: Apache Software License\n\nbody";
+        assert_eq!(python_metadata_license(metadata).as_deref(), Some("Apache 2.0"));
+        let classified = "Name: six\nLicense: UNKNOWN\nClassifier: License :: OSI Approved :: MIT License\n";
+        assert_eq!(python_metadata_license(classified).as_deref(), Some("MIT License"));
+        let legacy: Value = serde_json::from_str(r#"{"licenses": [{"type": "MIT"}, {"type": "GPL-2.0"}]}"#).unwrap();
+        assert_eq!(node_license(&legacy).as_deref(), Some("MIT OR GPL-2.0"));
+        assert_eq!(go_case_encode("github.com/BurntSushi/toml"), "github.com/!burnt!sushi/toml");
+    }
+}
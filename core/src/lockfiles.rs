@@ -0,0 +1,753 @@
+// saccade/core/src/lockfiles.rs
+//
+// Offline dependency extraction: lockfiles are parsed in-process, so the DEPS
+// section works on machines without cargo/npm/pipdeptree/go and never touches
+// the network.
+//
+// Every format is reduced to the same shape: one `LockedPackage` per resolved
+// (name, version), flagged direct when the project's own manifest asks for it.
+// The TOML formats and go.mod go through the shared `manifest_text` reader,
+// package-lock.json and package.json through serde_json; the rest are read line
+// by line.
+
+use crate::manifest_text::{go_directive, inline_table_value, quoted_strings, split_inline_array, toml_tables, unquote};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Transitive packages are listed `name version` per entry, wrapped at this width.
+const TRANSITIVE_LINE_WIDTH: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockFormat {
+    Cargo,
+    Npm,
+    Pnpm,
+    Yarn,
+    Poetry,
+    Uv,
+    /// `go.mod` plus `go.sum` when present.
+    GoModules,
+}
+
+impl LockFormat {
+    pub const ALL: [LockFormat; 7] = [
+        LockFormat::Cargo,
+        LockFormat::Npm,
+        LockFormat::Pnpm,
+        LockFormat::Yarn,
+        LockFormat::Poetry,
+        LockFormat::Uv,
+        LockFormat::GoModules,
+    ];
+
+    pub fn file_name(self) -> &'static str {
+        match self {
+            LockFormat::Cargo => "Cargo.lock",
+            LockFormat::Npm => "package-lock.json",
+            LockFormat::Pnpm => "pnpm-lock.yaml",
+            LockFormat::Yarn => "yarn.lock",
+            LockFormat::Poetry => "poetry.lock",
+            LockFormat::Uv => "uv.lock",
+            LockFormat::GoModules => "go.mod",
+        }
+    }
+
+    pub fn ecosystem(self) -> &'static str {
+        match self {
+            LockFormat::Cargo => "rust",
+            LockFormat::Npm | LockFormat::Pnpm | LockFormat::Yarn => "node",
+            LockFormat::Poetry | LockFormat::Uv => "python",
+            LockFormat::GoModules => "go",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    /// Requested by the project itself rather than pulled in by another package.
+    pub direct: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Lockfile {
+    pub path: PathBuf,
+    pub format: LockFormat,
+    /// Sorted by name, then version; workspace members are not included.
+    pub packages: Vec<LockedPackage>,
+}
+
+impl Lockfile {
+    /// Read `format`'s lockfile (and the manifest that marks direct deps) from `dir`.
+    pub fn read(dir: &Path, format: LockFormat) -> Option<Self> {
+        let path = dir.join(format.file_name());
+        let content = fs::read_to_string(&path).ok()?;
+        let sibling = |name: &str| fs::read_to_string(dir.join(name)).ok();
+        let packages = match format {
+            LockFormat::Cargo => parse_cargo_lock(&content),
+            LockFormat::Npm => parse_package_lock(&content, sibling("package.json").as_deref()),
+            LockFormat::Pnpm => parse_pnpm_lock(&content),
+            LockFormat::Yarn => parse_yarn_lock(&content, sibling("package.json").as_deref()),
+            LockFormat::Poetry => parse_poetry_lock(&content, sibling("pyproject.toml").as_deref()),
+            LockFormat::Uv => parse_uv_lock(&content),
+            LockFormat::GoModules => parse_go_modules(&content, sibling("go.sum").as_deref()),
+        };
+        Some(Self::new(path, format, packages))
+    }
+
+    pub fn new(path: PathBuf, format: LockFormat, mut packages: Vec<LockedPackage>) -> Self {
+        packages.sort();
+        // The same (name, version) reached twice: keep one, direct if either was.
+        packages.dedup_by(|next, kept| {
+            let same = next.name == kept.name && next.version == kept.version;
+            if same {
+                kept.direct |= next.direct;
+            }
+            same
+        });
+        Self { path, format, packages }
+    }
+
+    pub fn direct(&self) -> impl Iterator<Item = &LockedPackage> {
+        self.packages.iter().filter(|p| p.direct)
+    }
+
+    /// Names resolved at more than one version, with those versions.
+    pub fn duplicates(&self) -> Vec<(&str, Vec<&str>)> {
+        let mut by_name: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for p in &self.packages {
+            by_name.entry(&p.name).or_default().push(&p.version);
+        }
+        by_name.into_iter().filter(|(_, versions)| versions.len() > 1).collect()
+    }
+
+    pub fn render(&self) -> String {
+        let direct: Vec<&LockedPackage> = self.direct().collect();
+        let mut out = format!(
+            "{}: {} packages ({} direct, {} transitive)\n",
+            self.path.to_string_lossy().replace('\\', "/").trim_start_matches("./"),
+            self.packages.len(),
+            direct.len(),
+            self.packages.len() - direct.len()
+        );
+        if !direct.is_empty() {
+            out.push_str("Direct:\n");
+            for p in &direct {
+                out.push_str(&format!("  {} {}\n", p.name, p.version));
+            }
+        }
+        let duplicates = self.duplicates();
+        if !duplicates.is_empty() {
+            out.push_str(&format!("Duplicate versions ({}):\n", duplicates.len()));
+            for (name, versions) in duplicates {
+                out.push_str(&format!("  {}: {}\n", name, versions.join(", ")));
+            }
+        }
+        let transitive: Vec<String> =
+            self.packages.iter().filter(|p| !p.direct).map(|p| format!("{} {}", p.name, p.version)).collect();
+        if !transitive.is_empty() {
+            out.push_str("Transitive:\n");
+            let mut line = String::new();
+            for entry in transitive {
+                if !line.is_empty() && line.len() + entry.len() + 2 > TRANSITIVE_LINE_WIDTH {
+                    out.push_str(&format!("  {}\n", line));
+                    line.clear();
+                }
+                if !line.is_empty() {
+                    line.push_str(", ");
+                }
+                line.push_str(&entry);
+            }
+            out.push_str(&format!("  {}\n", line));
+        }
+        out
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TOML lockfiles (Cargo.lock, poetry.lock, uv.lock)
+// ---------------------------------------------------------------------------
+
+/// One `[[package]]` table: the fields the three formats share.
+#[derive(Debug, Default)]
+struct TomlPackage {
+    name: String,
+    version: String,
+    /// Raw value of `source = …`; `None` for Cargo workspace members.
+    source: Option<String>,
+    /// `(name, version)` from `dependencies = [...]` and, for uv, `[package.*dependencies]` arrays.
+    dependencies: Vec<(String, Option<String>)>,
+}
+
+fn toml_packages(content: &str) -> Vec<TomlPackage> {
+    let mut packages: Vec<TomlPackage> = Vec::new();
+    // `[package.dependencies]` & co. belong to the `[[package]]` above them (only arrays are collected there).
+    let mut in_package = false;
+    for (table, entries) in toml_tables(content) {
+        let in_dep_table = in_package && table.starts_with("package.") && table.ends_with("dependencies");
+        if table == "package" {
+            packages.push(TomlPackage::default());
+            in_package = true;
+        } else if !table.starts_with("package.") {
+            in_package = false;
+        }
+        let Some(pkg) = packages.last_mut().filter(|_| in_package) else { continue };
+        for (key, value) in entries {
+            if let Some(inline) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                if key == "dependencies" || in_dep_table {
+                    pkg.dependencies.extend(split_inline_array(inline).into_iter().filter_map(toml_dependency));
+                }
+                continue;
+            }
+            if in_dep_table {
+                continue;
+            }
+            match key.as_str() {
+                "name" => pkg.name = unquote(&value).to_string(),
+                "version" => pkg.version = unquote(&value).to_string(),
+                "source" => pkg.source = Some(value),
+                _ => {}
+            }
+        }
+    }
+    packages
+}
+
+/// `"serde 1.0.1"`, `"serde 1.0.1 (registry+…)"` (Cargo) or `{ name = "anyio", … }` (uv).
+fn toml_dependency(entry: &str) -> Option<(String, Option<String>)> {
+    let entry = entry.trim().trim_end_matches(',').trim();
+    if entry.starts_with('{') {
+        let name = inline_table_value(entry, "name")?;
+        return Some((name.to_string(), inline_table_value(entry, "version").map(str::to_string)));
+    }
+    let mut words = unquote(entry).split_whitespace();
+    let name = words.next()?.to_string();
+    Some((name, words.next().map(str::to_string)))
+}
+
+/// Cargo.lock: packages without `source` are workspace members; their dependencies are direct.
+pub fn parse_cargo_lock(content: &str) -> Vec<LockedPackage> {
+    let packages = toml_packages(content);
+    let (members, resolved): (Vec<_>, Vec<_>) = packages.into_iter().partition(|p| p.source.is_none());
+    let requested: Vec<&(String, Option<String>)> = members.iter().flat_map(|m| &m.dependencies).collect();
+    let member_names: HashSet<&str> = members.iter().map(|m| m.name.as_str()).collect();
+    resolved
+        .into_iter()
+        .filter(|p| !member_names.contains(p.name.as_str()))
+        .map(|p| {
+            let direct = requested.iter().any(|(name, version)| {
+                *name == p.name && version.as_deref().is_none_or(|v| v == p.version)
+            });
+            LockedPackage { name: p.name, version: p.version, direct }
+        })
+        .collect()
+}
+
+/// uv.lock: the project itself has `source = { editable = "." }` or `{ virtual = "." }`.
+pub fn parse_uv_lock(content: &str) -> Vec<LockedPackage> {
+    let packages = toml_packages(content);
+    let is_member =
+        |p: &TomlPackage| p.source.as_deref().is_some_and(|s| s.contains("editable") || s.contains("virtual"));
+    let requested: HashSet<String> = packages
+        .iter()
+        .filter(|p| is_member(p))
+        .flat_map(|p| p.dependencies.iter().map(|(name, _)| normalize_python_name(name)))
+        .collect();
+    packages
+        .into_iter()
+        .filter(|p| !is_member(p))
+        .map(|p| {
+            let direct = requested.contains(&normalize_python_name(&p.name));
+            LockedPackage { name: p.name, version: p.version, direct }
+        })
+        .collect()
+}
+
+/// poetry.lock has no root entry; direct deps come from pyproject.toml.
+pub fn parse_poetry_lock(content: &str, pyproject: Option<&str>) -> Vec<LockedPackage> {
+    let requested: HashSet<String> =
+        pyproject.map(pyproject_requirements).unwrap_or_default().into_iter().map(|(name, _)| name).collect();
+    toml_packages(content)
+        .into_iter()
+        .map(|p| {
+            let direct = requested.contains(&normalize_python_name(&p.name));
+            LockedPackage { name: p.name, version: p.version, direct }
+        })
+        .collect()
+}
+
+/// `(normalised name, requirement)` from `[tool.poetry.*dependencies]` tables and
+/// PEP 621 `dependencies` / `optional-dependencies` arrays.
+pub(crate) fn pyproject_requirements(pyproject: &str) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    for (table, entries) in toml_tables(pyproject) {
+        let poetry_table = table.starts_with("tool.poetry") && table.ends_with("dependencies");
+        for (key, value) in entries {
+            if poetry_table && key != "python" {
+                let spec = if value.starts_with('{') { inline_table_value(&value, "version").unwrap_or("*") } else { unquote(&value) };
+                out.push((normalize_python_name(&key), spec.to_string()));
+            }
+            let pep621 = (table == "project" && key == "dependencies") || table == "project.optional-dependencies";
+            if pep621 && value.starts_with('[') {
+                out.extend(quoted_strings(&value).into_iter().filter_map(python_requirement));
+            }
+        }
+    }
+    out
+}
+
+/// `requests[socks]>=2.31; python_version>'3.8'` → (`requests`, `>=2.31; python_version>'3.8'`).
+fn python_requirement(spec: &str) -> Option<(String, String)> {
+    let spec = spec.trim();
+    let end = spec.find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))).unwrap_or(spec.len());
+    if end == 0 {
+        return None;
+    }
+    let mut rest = spec[end..].trim_start();
+    if rest.starts_with('[') {
+        rest = rest.split_once(']').map_or("", |(_, r)| r.trim_start());
+    }
+    Some((normalize_python_name(&spec[..end]), if rest.is_empty() { "*".to_string() } else { rest.to_string() }))
+}
+
+/// PEP 503: case-insensitive, runs of `-`, `_`, `.` are equivalent.
+pub(crate) fn normalize_python_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !out.ends_with('-') {
+                out.push('-');
+            }
+        } else {
+            out.push(c.to_ascii_lowercase());
+        }
+    }
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Manifests (no lockfile)
+// ---------------------------------------------------------------------------
+
+/// Declared dependencies from a manifest, for projects without a lockfile when
+/// the ecosystem tools are off or failed. Everything here is direct and versions
+/// are requirements, not resolutions.
+#[derive(Debug, Clone)]
+pub struct ManifestDeps {
+    pub path: PathBuf,
+    /// `(name, requirement)` in file order.
+    pub requirements: Vec<(String, String)>,
+}
+
+impl ManifestDeps {
+    /// `Cargo.toml`, `package.json`, `pyproject.toml` or `requirements*.txt`; `None` for
+    /// other files, unreadable files and manifests that declare nothing.
+    pub fn read(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        let content = fs::read_to_string(path).ok()?;
+        let requirements = match name {
+            "Cargo.toml" => parse_cargo_manifest(&content),
+            "package.json" => serde_json::from_str::<Value>(&content).map(|m| node_requested(&m).into_iter().collect()).ok()?,
+            "pyproject.toml" => pyproject_requirements(&content),
+            _ if name.starts_with("requirements") && name.ends_with(".txt") => parse_requirements_txt(&content),
+            _ => return None,
+        };
+        (!requirements.is_empty()).then(|| Self { path: path.to_path_buf(), requirements })
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "{}: {} declared dependencies (requirements, not resolved versions)\n",
+            self.path.to_string_lossy().replace('\\', "/").trim_start_matches("./"),
+            self.requirements.len()
+        );
+        for (name, requirement) in &self.requirements {
+            out.push_str(&format!("  {} {}\n", name, requirement));
+        }
+        out
+    }
+}
+
+/// Cargo.toml `[dependencies]`, `[dev-dependencies]`, `[build-dependencies]`,
+/// `[workspace.dependencies]`, target-specific tables and `[dependencies.foo]` tables.
+pub fn parse_cargo_manifest(content: &str) -> Vec<(String, String)> {
+    const KINDS: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+    let mut out: Vec<(String, String)> = Vec::new();
+    for (header, entries) in toml_tables(content) {
+        // `[dependencies.foo]`: foo's fields are the table's keys.
+        if let Some(name) = KINDS.iter().find_map(|k| header.strip_prefix(k)?.strip_prefix('.')) {
+            let mut requirement = "*".to_string();
+            for (key, value) in &entries {
+                match key.as_str() {
+                    "version" => requirement = unquote(value).to_string(),
+                    "path" | "git" if requirement == "*" => requirement = format!("{} {}", key, unquote(value)),
+                    "workspace" if requirement == "*" => requirement = "workspace".to_string(),
+                    _ => {}
+                }
+            }
+            out.push((unquote(name).to_string(), requirement));
+            continue;
+        }
+        let kind = header.rsplit('.').next().unwrap_or(&header);
+        if !KINDS.iter().any(|k| header == *k || (kind == *k && header.contains('.'))) {
+            continue;
+        }
+        for (key, value) in &entries {
+            // Dotted keys: `foo.workspace = true`, `foo.version = "1"`.
+            let (name, requirement) = match key.split_once('.') {
+                Some((name, "workspace")) => (name, "workspace".to_string()),
+                Some((name, field @ ("path" | "git"))) => (name, format!("{} {}", field, unquote(value))),
+                Some((name, _)) => (name, cargo_requirement(value)),
+                None => (key.as_str(), cargo_requirement(value)),
+            };
+            out.push((name.to_string(), requirement));
+        }
+    }
+    out
+}
+
+/// `"1.0"` or `{ version = "1", … }` / `{ path = "../x" }` / `{ workspace = true }`.
+fn cargo_requirement(value: &str) -> String {
+    if !value.starts_with('{') {
+        return unquote(value).to_string();
+    }
+    if let Some(version) = inline_table_value(value, "version") {
+        return version.to_string();
+    }
+    for key in ["path", "git"] {
+        if let Some(location) = inline_table_value(value, key) {
+            return format!("{} {}", key, location);
+        }
+    }
+    if inline_table_value(value, "workspace") == Some("true") { "workspace".to_string() } else { "*".to_string() }
+}
+
+/// requirements.txt: one requirement per line; options (`-r`, `-e`, `--hash`) and comments skipped.
+pub fn parse_requirements_txt(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .map(|l| l.split(" #").next().unwrap_or(l).trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with('-'))
+        .filter_map(python_requirement)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Node lockfiles
+// ---------------------------------------------------------------------------
+
+const NODE_DEP_KEYS: [&str; 4] = ["dependencies", "devDependencies", "optionalDependencies", "peerDependencies"];
+
+/// `name → requested range` from a package.json-shaped object.
+pub(crate) fn node_requested(manifest: &Value) -> BTreeMap<String, String> {
+    NODE_DEP_KEYS
+        .iter()
+        .filter_map(|key| manifest.get(key)?.as_object())
+        .flatten()
+        .map(|(name, range)| (name.clone(), range.as_str().unwrap_or_default().to_string()))
+        .collect()
+}
+
+/// package-lock.json v2/v3 (`packages`) or v1 (nested `dependencies`).
+pub fn parse_package_lock(content: &str, package_json: Option<&str>) -> Vec<LockedPackage> {
+    let Ok(lock) = serde_json::from_str::<Value>(content) else { return Vec::new() };
+    let manifest_requested =
+        package_json.and_then(|s| serde_json::from_str::<Value>(s).ok()).map(|m| node_requested(&m));
+
+    if let Some(entries) = lock.get("packages").and_then(Value::as_object) {
+        let requested = entries.get("").map(node_requested).or(manifest_requested).unwrap_or_default();
+        return entries
+            .iter()
+            .filter(|(key, entry)| !key.is_empty() && entry.get("link").and_then(Value::as_bool) != Some(true))
+            .filter_map(|(key, entry)| {
+                let (_, name) = key.rsplit_once("node_modules/")?;
+                let version = entry.get("version")?.as_str()?;
+                let top_level = key.strip_prefix("node_modules/") == Some(name);
+                Some(LockedPackage {
+                    name: name.to_string(),
+                    version: version.to_string(),
+                    direct: top_level && requested.contains_key(name),
+                })
+            })
+            .collect();
+    }
+
+    let mut out = Vec::new();
+    if let Some(deps) = lock.get("dependencies").and_then(Value::as_object) {
+        collect_npm_v1(deps, true, manifest_requested.as_ref(), &mut out);
+    }
+    out
+}
+
+fn collect_npm_v1(
+    deps: &serde_json::Map<String, Value>,
+    top_level: bool,
+    requested: Option<&BTreeMap<String, String>>,
+    out: &mut Vec<LockedPackage>,
+) {
+    for (name, entry) in deps {
+        if let Some(version) = entry.get("version").and_then(Value::as_str) {
+            // Without package.json, v1 cannot tell hoisted transitive deps from direct ones.
+            let direct = top_level && requested.is_none_or(|r| r.contains_key(name));
+            out.push(LockedPackage { name: name.clone(), version: version.to_string(), direct });
+        }
+        if let Some(nested) = entry.get("dependencies").and_then(Value::as_object) {
+            collect_npm_v1(nested, false, requested, out);
+        }
+    }
+}
+
+/// pnpm-lock.yaml v5–v9: `packages:` keys for versions, `importers: .:` (or the
+/// top-level v5 `dependencies:` tables) for direct names.
+pub fn parse_pnpm_lock(content: &str) -> Vec<LockedPackage> {
+    let mut requested: HashSet<String> = HashSet::new();
+    let mut resolved: Vec<(String, String)> = Vec::new();
+    let (mut section, mut importer, mut dep_table) = (String::new(), String::new(), String::new());
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        let key = match line.trim().strip_suffix(':').or_else(|| line.trim().split_once(": ").map(|(k, _)| k)) {
+            Some(key) => unquote(key).to_string(),
+            None => continue,
+        };
+        match indent {
+            0 => section = key,
+            2 if section == "packages" => resolved.extend(pnpm_package_key(&key)),
+            2 if NODE_DEP_KEYS.contains(&section.as_str()) => {
+                requested.insert(key);
+            }
+            2 if section == "importers" => importer = key,
+            4 if section == "importers" => dep_table = key,
+            6 if section == "importers" && importer == "." && NODE_DEP_KEYS.contains(&dep_table.as_str()) => {
+                requested.insert(key);
+            }
+            _ => {}
+        }
+    }
+    resolved
+        .into_iter()
+        .map(|(name, version)| {
+            let direct = requested.contains(&name);
+            LockedPackage { name, version, direct }
+        })
+        .collect()
+}
+
+/// `/@scope/name@1.2.3(peer@1)` (v6+), `name@1.2.3` (v9) or `/name/1.2.3_peer@1` (v5).
+fn pnpm_package_key(key: &str) -> Option<(String, String)> {
+    let key = key.trim_start_matches('/');
+    let key = key.split('(').next().unwrap_or(key);
+    let (name, version) = match key.rsplit_once('/') {
+        Some((name, version)) if version.starts_with(|c: char| c.is_ascii_digit()) => {
+            (name, version.split('_').next().unwrap_or(version))
+        }
+        _ => {
+            let i = key.get(1..)?.rfind('@')? + 1;
+            (&key[..i], &key[i + 1..])
+        }
+    };
+    (!name.is_empty() && !version.is_empty()).then(|| (name.to_string(), version.to_string()))
+}
+
+/// yarn.lock v1 (`version "1.2.3"`) and Berry (`version: 1.2.3`). Direct entries
+/// are the ones whose specifier matches a package.json range.
+pub fn parse_yarn_lock(content: &str, package_json: Option<&str>) -> Vec<LockedPackage> {
+    let requested = package_json
+        .and_then(|s| serde_json::from_str::<Value>(s).ok())
+        .map(|m| node_requested(&m))
+        .unwrap_or_default();
+    let mut out = Vec::new();
+    let mut entry: Option<(String, bool)> = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(' ') {
+            entry = None;
+            let Some(header) = line.strip_suffix(':') else { continue };
+            let specs: Vec<&str> = header.split(", ").map(unquote).collect();
+            if header.starts_with("__metadata") || specs.iter().any(|s| s.contains("@workspace:")) {
+                continue;
+            }
+            let Some(name) = specs.first().and_then(|s| yarn_spec_name(s)) else { continue };
+            let direct = requested.get(name).is_some_and(|range| {
+                specs.iter().any(|s| *s == format!("{}@{}", name, range) || *s == format!("{}@npm:{}", name, range))
+            });
+            entry = Some((name.to_string(), direct));
+            continue;
+        }
+        let trimmed = line.trim();
+        let Some(version) = trimmed.strip_prefix("version:").or_else(|| trimmed.strip_prefix("version ")) else {
+            continue;
+        };
+        if let Some((name, direct)) = entry.take() {
+            out.push(LockedPackage { name, version: unquote(version).to_string(), direct });
+        }
+    }
+    out
+}
+
+/// `@types/node@^20` → `@types/node`.
+fn yarn_spec_name(spec: &str) -> Option<&str> {
+    let i = spec.get(1..)?.find('@')? + 1;
+    Some(&spec[..i])
+}
+
+// ---------------------------------------------------------------------------
+// Go modules
+// ---------------------------------------------------------------------------
+
+/// go.mod `require` lines (direct unless `// indirect`), plus modules only
+/// go.sum knows about (content hashes, not `/go.mod`-only lines) as transitive.
+pub fn parse_go_modules(go_mod: &str, go_sum: Option<&str>) -> Vec<LockedPackage> {
+    let mut out = Vec::new();
+    for (spec, comment) in go_directive(go_mod, "require") {
+        let mut words = spec.split_whitespace();
+        let (Some(module), Some(version)) = (words.next(), words.next()) else { continue };
+        out.push(LockedPackage {
+            name: module.to_string(),
+            version: version.to_string(),
+            direct: !comment.starts_with("indirect"),
+        });
+    }
+
+    let required: BTreeSet<String> = out.iter().map(|p| p.name.clone()).collect();
+    for line in go_sum.unwrap_or_default().lines() {
+        let mut words = line.split_whitespace();
+        let (Some(module), Some(version)) = (words.next(), words.next()) else { continue };
+        if version.ends_with("/go.mod") || required.contains(module) {
+            continue;
+        }
+        out.push(LockedPackage { name: module.to_string(), version: version.to_string(), direct: false });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(packages: &[LockedPackage]) -> Vec<(&str, &str, bool)> {
+        packages.iter().map(|p| (p.name.as_str(), p.version.as_str(), p.direct)).collect()
+    }
+
+    #[test]
+    fn cargo_lock_marks_member_dependencies_direct_and_reports_duplicates() {
+        let lock = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = [
+ "serde",
+ "syn 2.0.66",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.203"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+dependencies = [
+ "syn 1.0.109",
+]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "syn"
+version = "2.0.66"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let lockfile = Lockfile::new(PathBuf::from("Cargo.lock"), LockFormat::Cargo, parse_cargo_lock(lock));
+        assert_eq!(
+            summary(&lockfile.packages),
+            [("serde", "1.0.203", true), ("syn", "1.0.109", false), ("syn", "2.0.66", true)]
+        );
+        assert_eq!(lockfile.duplicates(), [("syn", vec!["1.0.109", "2.0.66"])]);
+        let rendered = lockfile.render();
+        assert!(rendered.starts_with("Cargo.lock: 3 packages (2 direct, 1 transitive)\n"));
+        assert!(rendered.contains("  syn: 1.0.109, 2.0.66\n"));
+    }
+
+    #[test]
+    fn node_lockfiles_agree_on_direct_dependencies() {
+        let package_json = r#"{"dependencies": {"left-pad": "^1.3.0"}, "devDependencies": {"@types/node": "^20.1.0"}}"#;
+        let npm = r#"{"lockfileVersion": 3, "packages": {
+            "": {"dependencies": {"left-pad": "^1.3.0"}, "devDependencies": {"@types/node": "^20.1.0"}},
+            "node_modules/left-pad": {"version": "1.3.0"},
+            "node_modules/@types/node": {"version": "20.1.2"},
+            "node_modules/undici-types": {"version": "5.26.5"},
+            "node_modules/@types/node/node_modules/left-pad": {"version": "1.2.0"}
+        }}"#;
+        let pnpm = "lockfileVersion: '6.0'\n\nimporters:\n  .:\n    dependencies:\n      left-pad:\n        specifier: ^1.3.0\n        version: 1.3.0\n    devDependencies:\n      '@types/node':\n        specifier: ^20.1.0\n        version: 20.1.2\n\npackages:\n  /@types/node@20.1.2:\n    dependencies:\n      undici-types: 5.26.5\n  /left-pad@1.3.0:\n    dev: false\n  /undici-types@5.26.5:\n    dev: true\n";
+        let yarn = "# yarn lockfile v1\n\n\"@types/node@^20.1.0\":\n  version \"20.1.2\"\n  dependencies:\n    undici-types \"~5.26.4\"\n\nleft-pad@^1.3.0:\n  version \"1.3.0\"\n\nundici-types@~5.26.4:\n  version \"5.26.5\"\n";
+
+        let expected = [("@types/node", "20.1.2", true), ("left-pad", "1.3.0", true), ("undici-types", "5.26.5", false)];
+        let pnpm = Lockfile::new(PathBuf::from("pnpm-lock.yaml"), LockFormat::Pnpm, parse_pnpm_lock(pnpm));
+        let yarn = Lockfile::new(PathBuf::from("yarn.lock"), LockFormat::Yarn, parse_yarn_lock(yarn, Some(package_json)));
+        assert_eq!(summary(&pnpm.packages), expected);
+        assert_eq!(summary(&yarn.packages), expected);
+
+        let npm = Lockfile::new(PathBuf::from("package-lock.json"), LockFormat::Npm, parse_package_lock(npm, None));
+        assert_eq!(summary(&npm.packages)[..2], [("@types/node", "20.1.2", true), ("left-pad", "1.2.0", false)]);
+        assert_eq!(npm.duplicates(), [("left-pad", vec!["1.2.0", "1.3.0"])]);
+    }
+
+    #[test]
+    fn manifests_list_declared_requirements() {
+        let cargo = "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1\", features = [\"derive\", \"rc\"] }\ncore = { path = \"../core\" }\n\n[target.'cfg(unix)'.dependencies]\nlibc = \"0.2\"\nanyhow.workspace = true\n\n[dependencies.tokio]\nversion = \"1.38\"\nfeatures = [\"full\"]\n";
+        assert_eq!(
+            parse_cargo_manifest(cargo),
+            [("serde", "1"), ("core", "path ../core"), ("libc", "0.2"), ("anyhow", "workspace"), ("tokio", "1.38")]
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+        );
+
+        let pyproject = "[project]\nname = \"demo\"\ndependencies = [\n    \"httpx[http2]>=0.27,<1\",\n    \"Rich\",\n]\n";
+        assert_eq!(
+            pyproject_requirements(pyproject),
+            [("httpx", ">=0.27,<1"), ("rich", "*")].map(|(n, v)| (n.to_string(), v.to_string()))
+        );
+        assert_eq!(
+            parse_requirements_txt("-r base.txt\n# pinned\nDjango==5.0.6  # web\n\nPyYAML>=6\n"),
+            [("django", "==5.0.6"), ("pyyaml", ">=6")].map(|(n, v)| (n.to_string(), v.to_string()))
+        );
+    }
+
+    #[test]
+    fn python_and_go_lockfiles() {
+        let uv = "version = 1\n\n[[package]]\nname = \"demo\"\nversion = \"0.1.0\"\nsource = { editable = \".\" }\ndependencies = [\n    { name = \"httpx\" },\n]\n\n[package.dev-dependencies]\ndev = [\n    { name = \"pytest\" },\n]\n\n[[package]]\nname = \"httpx\"\nversion = \"0.27.0\"\nsource = { registry = \"https://pypi.org/simple\" }\ndependencies = [\n    { name = \"anyio\" },\n]\n\n[[package]]\nname = \"anyio\"\nversion = \"4.4.0\"\nsource = { registry = \"https://pypi.org/simple\" }\n\n[[package]]\nname = \"pytest\"\nversion = \"8.2.0\"\nsource = { registry = \"https://pypi.org/simple\" }\n";
+        assert_eq!(
+            summary(&Lockfile::new(PathBuf::from("uv.lock"), LockFormat::Uv, parse_uv_lock(uv)).packages),
+            [("anyio", "4.4.0", false), ("httpx", "0.27.0", true), ("pytest", "8.2.0", true)]
+        );
+
+        let poetry = "[[package]]\nname = \"Requests\"\nversion = \"2.32.3\"\n\n[package.dependencies]\nidna = \">=2.5\"\n\n[[package]]\nname = \"idna\"\nversion = \"3.7\"\n\n[metadata]\nlock-version = \"2.0\"\n";
+        let pyproject = "[tool.poetry.dependencies]\npython = \"^3.11\"\nrequests = \"^2.32\"\n";
+        assert_eq!(
+            summary(&parse_poetry_lock(poetry, Some(pyproject))),
+            [("Requests", "2.32.3", true), ("idna", "3.7", false)]
+        );
+
+        let go_mod = "module example.com/app\n\ngo 1.22\n\nrequire github.com/spf13/cobra v1.8.0\n\nrequire (\n\tgithub.com/spf13/pflag v1.0.5 // indirect\n)\n";
+        let go_sum = "github.com/spf13/cobra v1.8.0 h1:abc=\ngithub.com/spf13/cobra v1.8.0/go.mod h1:def=\ngithub.com/inconshreveable/mousetrap v1.1.0 h1:ghi=\ngithub.com/old/dep v0.1.0/go.mod h1:jkl=\n";
+        assert_eq!(
+            summary(&parse_go_modules(go_mod, Some(go_sum))),
+            [
+                ("github.com/spf13/cobra", "v1.8.0", true),
+                ("github.com/spf13/pflag", "v1.0.5", false),
+                ("github.com/inconshreveable/mousetrap", "v1.1.0", false)
+            ]
+        );
+    }
+}
@@ -1,8 +1,9 @@
 // saccade/core/src/manifest.rs
 
 use crate::config::Config;
-use crate::detection::BuildSystemType;
+use crate::detection::{BuildSystemType, DetectionReport};
 use crate::error::Result;
+use crate::exclusions::ExclusionReport;
 use crate::stage0::Stage0Generator;
 use chrono::{DateTime, Local};
 use std::path::{Path, PathBuf};
@@ -20,6 +21,8 @@ pub struct ProjectInfoContext<'a> {
     pub in_git: bool,
     pub files: &'a [PathBuf],
     pub detected_systems: &'a [BuildSystemType], // <-- ADDED: DCA Signal
+    pub detection: &'a DetectionReport,
+    pub exclusions: &'a ExclusionReport,
 }
 
 impl ManifestGenerator {
@@ -39,6 +42,7 @@ impl ManifestGenerator {
         out.push_str(&format!("Generated: {}\nOutput dir: {}\n\n", now.format("%Y-%m-%d %H:%M:%S %Z"), ctx.pack_dir.display()));
         out.push_str("STATS\n------\n");
         out.push_str(&format!("- files.raw: {}\n- files.kept: {}\n- code_only: {}\n", ctx.raw_count, ctx.filtered_count, self.config.code_only));
+        out.push_str(&ctx.exclusions.stats_lines());
         out.push_str(&format!("- max_depth: {}\n\n", self.config.max_depth));
 
         out.push_str("TOOLS & BUILD SYSTEMS\n----------------------\n");
@@ -46,6 +50,8 @@ impl ManifestGenerator {
         if let Some(commit) = git_commit { out.push_str(&format!("- git.commit: {}\n", commit)); }
         let detected_str: Vec<String> = ctx.detected_systems.iter().map(|s| s.to_string()).collect();
         out.push_str(&format!("- detected_build_systems: [{}]\n\n", detected_str.join(", ")));
+        out.push_str(&ctx.detection.render());
+        out.push('\n');
 
         out.push_str("WHAT'S IN THE PACK\n-------------------\n");
         out.push_str(whats_in_pack);
@@ -69,7 +75,7 @@ impl ManifestGenerator {
 2. PROJECT.txt             - Overview, metadata
 3. STRUCTURE.txt           - Directory tree, file index, token heatmap
 4. APIS.txt                - API surfaces (Rust/TS/Python/Go)
-5. DEPS.txt (optional)     - Dependencies (from `cargo tree`)
+5. DEPS.txt (optional)     - Dependencies (lockfiles, then tools, then manifests)
 6. PACK_STAGE2_COMPRESSED.xml (optional) - Compressed skeleton if repomix present"#
     }
 }
\ No newline at end of file
@@ -0,0 +1,258 @@
+// saccade/core/src/manifest_text.rs
+//
+// The one reader for the line-based manifest formats: TOML (Cargo.toml,
+// pyproject.toml, Cargo.lock, poetry.lock, uv.lock, version catalogs) and
+// go.mod. Every module that needs a table value, a quoted string or a go.mod
+// directive goes through here, so comments, quoting and multi-line values are
+// handled the same way everywhere.
+//
+// This is not a full TOML parser: values stay raw text (`"1.0"`, `{ path = "x" }`,
+// `["a", "b"]`) and callers unquote or split them as needed.
+
+/// One `[table]` / `[[table]]` occurrence and its `key = value` rows, in file order.
+pub(crate) type TomlTable = (String, Vec<(String, String)>);
+
+/// `(table, key, value)` rows, the flattened form of `toml_tables`.
+pub(crate) type TomlEntry = (String, String, String);
+
+/// Every table in file order; keys before the first header are in table `""`.
+/// Each `[[package]]` occurrence is its own table. Multi-line arrays, inline tables
+/// and strings are joined into one value.
+pub(crate) fn toml_tables(content: &str) -> Vec<TomlTable> {
+    let mut tables: Vec<TomlTable> = vec![(String::new(), Vec::new())];
+    // A value still open at the end of the previous line: key, text so far, and
+    // the closing `"""`/`'''` when it is a multi-line string.
+    let mut open: Option<(String, String, Option<&str>)> = None;
+    for raw in content.lines() {
+        if let Some((key, mut value, string_end)) = open.take() {
+            value.push(' ');
+            let closed = match string_end {
+                Some(end) => {
+                    value.push_str(raw.trim());
+                    raw.contains(end)
+                }
+                None => {
+                    let line = strip_comment(raw).trim();
+                    value.push_str(line);
+                    bracket_depth(&value) <= 0
+                }
+            };
+            if closed {
+                push_entry(&mut tables, key, value);
+            } else {
+                open = Some((key, value, string_end));
+            }
+            continue;
+        }
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.starts_with('[') {
+            let name = line.trim_matches(|c| c == '[' || c == ']').trim();
+            tables.push((name.to_string(), Vec::new()));
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let (key, value) = (unquote(key).to_string(), value.trim().to_string());
+        let string_end = ["\"\"\"", "'''"].into_iter().find(|q| value.starts_with(q) && !value[3..].contains(q));
+        if string_end.is_some() || bracket_depth(&value) > 0 {
+            open = Some((key, value, string_end));
+        } else {
+            push_entry(&mut tables, key, value);
+        }
+    }
+    if let Some((key, value, _)) = open {
+        push_entry(&mut tables, key, value);
+    }
+    tables
+}
+
+fn push_entry(tables: &mut [TomlTable], key: String, value: String) {
+    if let Some((_, entries)) = tables.last_mut() {
+        entries.push((key, value));
+    }
+}
+
+pub(crate) fn toml_entries(content: &str) -> Vec<TomlEntry> {
+    toml_tables(content)
+        .into_iter()
+        .flat_map(|(table, entries)| entries.into_iter().map(move |(key, value)| (table.clone(), key, value)))
+        .collect()
+}
+
+pub(crate) fn toml_value<'a>(entries: &'a [TomlEntry], table: &str, key: &str) -> Option<&'a str> {
+    entries.iter().find(|(t, k, _)| t == table && k == key).map(|(_, _, v)| v.as_str())
+}
+
+/// The strings of an array value (`members = ["a", "b"]`); empty if the key is missing.
+pub(crate) fn toml_strings(entries: &[TomlEntry], table: &str, key: &str) -> Vec<String> {
+    toml_value(entries, table, key).map(|v| quoted_strings(v).into_iter().map(str::to_string).collect()).unwrap_or_default()
+}
+
+/// Cuts a `#` comment, ignoring `#` inside strings.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Open `[`/`{` minus closed `]`/`}`, outside strings.
+fn bracket_depth(value: &str) -> i32 {
+    let mut depth = 0;
+    let mut quote: Option<char> = None;
+    for c in value.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// Strips surrounding whitespace and quotes (`"`, `'` or `` ` ``).
+pub(crate) fn unquote(s: &str) -> &str {
+    s.trim().trim_matches(|c| c == '"' || c == '\'' || c == '`')
+}
+
+/// Contents of every `"…"` / `'…'` string on a line.
+pub(crate) fn quoted_strings(line: &str) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = line;
+    while let Some(start) = rest.find(['"', '\'']) {
+        let quote = rest[start..].chars().next().unwrap_or('"');
+        let Some(len) = rest[start + 1..].find(quote) else { break };
+        out.push(&rest[start + 1..start + 1 + len]);
+        rest = &rest[start + len + 2..];
+    }
+    out
+}
+
+/// `{ name = "x", version = "1" }` → value of `key`.
+pub(crate) fn inline_table_value<'a>(table: &'a str, key: &str) -> Option<&'a str> {
+    let inner = table.trim().trim_start_matches('{').trim_end_matches('}');
+    split_inline_array(inner).into_iter().find_map(|pair| {
+        let (k, v) = pair.split_once('=')?;
+        (k.trim() == key).then(|| unquote(v))
+    })
+}
+
+/// Split `"a", { name = "b", marker = "x" }` on commas outside braces and quotes.
+pub(crate) fn split_inline_array(inline: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut quote, mut start) = (0i32, None, 0);
+    for (i, c) in inline.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '{' | '[') => depth += 1,
+            (None, '}' | ']') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                parts.push(&inline[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&inline[start..]);
+    parts.into_iter().map(str::trim).filter(|p| !p.is_empty()).collect()
+}
+
+// ---------------------------------------------------------------------------
+// go.mod
+// ---------------------------------------------------------------------------
+
+/// The `module` path.
+pub(crate) fn go_module_path(go_mod: &str) -> Option<&str> {
+    go_mod.lines().find_map(|l| l.split("//").next()?.trim().strip_prefix("module ")).map(unquote)
+}
+
+/// `(spec, comment)` for every `verb …` line and every line of a `verb ( … )` block;
+/// the comment is whatever followed `//`, trimmed.
+pub(crate) fn go_directive<'a>(go_mod: &'a str, verb: &str) -> Vec<(&'a str, &'a str)> {
+    let mut out = Vec::new();
+    let mut in_block = false;
+    for line in go_mod.lines() {
+        let (spec, comment) = line.split_once("//").unwrap_or((line, ""));
+        let (spec, comment) = (spec.trim(), comment.trim());
+        if in_block {
+            if spec.starts_with(')') {
+                in_block = false;
+            } else if !spec.is_empty() {
+                out.push((spec, comment));
+            }
+            continue;
+        }
+        let Some(rest) = spec.strip_prefix(verb).filter(|r| r.is_empty() || r.starts_with([' ', '\t', '('])) else { continue };
+        let rest = rest.trim();
+        if rest == "(" {
+            in_block = true;
+        } else if !rest.is_empty() {
+            out.push((rest, comment));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tables_join_multiline_values_and_ignore_comments() {
+        let toml = concat!(
+            "name = \"top\"\n",
+            "[package]\n",
+            "name = \"app\" # the crate\n",
+            "description = \"uses # in text\"\n",
+            "keywords = [\n  \"a\", # first\n  \"b\",\n]\n",
+            "notes = \"\"\"\nline [one\n\"\"\"\n",
+            "[[package]]\n",
+            "name = \"x\"\n",
+            "[[package]]\n",
+            "name = \"y\"\n",
+            "deps = [{ name = \"z\", extra = [\"a,b\"] }]\n",
+        );
+        let tables = toml_tables(toml);
+        let names: Vec<&str> = tables.iter().map(|(t, _)| t.as_str()).collect();
+        assert_eq!(names, ["", "package", "package", "package"]);
+
+        let entries = toml_entries(toml);
+        assert_eq!(toml_value(&entries, "", "name"), Some("\"top\""));
+        assert_eq!(toml_value(&entries, "package", "name"), Some("\"app\""));
+        assert_eq!(toml_value(&entries, "package", "description").map(unquote), Some("uses # in text"));
+        assert_eq!(toml_strings(&entries, "package", "keywords"), ["a", "b"]);
+        assert_eq!(tables[3].1[1].1, "[{ name = \"z\", extra = [\"a,b\"] }]");
+        assert_eq!(split_inline_array("{ name = \"z\", extra = [\"a,b\"] }, \"w\""), ["{ name = \"z\", extra = [\"a,b\"] }", "\"w\""]);
+        assert_eq!(inline_table_value("{ path = \"../x, y\", version = \"1\" }", "version"), Some("1"));
+    }
+
+    #[test]
+    fn go_mod_directives_and_module_path() {
+        let go_mod = "module example.com/app // main\n\nrequire golang.org/x/text v0.14.0\nrequire (\n\tgithub.com/a/b v1.0.0 // indirect\n)\nreplace example.com/lib => ../lib\nrequirements x\n";
+        assert_eq!(go_module_path(go_mod), Some("example.com/app"));
+        assert_eq!(go_directive(go_mod, "require"), [("golang.org/x/text v0.14.0", ""), ("github.com/a/b v1.0.0", "indirect")]);
+        assert_eq!(go_directive(go_mod, "replace"), [("example.com/lib => ../lib", "")]);
+    }
+}
@@ -0,0 +1,359 @@
+// saccade/core/src/module_graph.rs
+//
+// Collapses the file-level import graph to modules (directories or packages),
+// then reports fan-in/out, import cycles and layering violations. Rendered as
+// the GRAPH section of PACK.txt and exported as GRAPH.dot / GRAPH.json.
+
+use crate::error::{Result, SaccadeError};
+use crate::graph::ImportGraph;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const GRAPH_DOT_FILE_NAME: &str = "GRAPH.dot";
+pub const GRAPH_JSON_FILE_NAME: &str = "GRAPH.json";
+
+/// Manifests that make their directory a package root.
+const PACKAGE_MANIFESTS: &[&str] = &[
+    "Cargo.toml", "package.json", "go.mod", "pyproject.toml", "setup.py", "CMakeLists.txt",
+];
+
+const MAX_LISTED_EDGES: usize = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Granularity {
+    /// Each file's parent directory is a module.
+    #[default]
+    Directory,
+    /// The nearest ancestor with a manifest (crate, npm package, Go module, …).
+    Package,
+}
+
+impl FromStr for Granularity {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dir" | "directory" => Ok(Granularity::Directory),
+            "package" | "crate" => Ok(Granularity::Package),
+            other => Err(format!("unknown graph granularity '{}' (expected dir or package)", other)),
+        }
+    }
+}
+
+impl fmt::Display for Granularity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Granularity::Directory => "directory",
+            Granularity::Package => "package",
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleNode {
+    pub name: String,
+    pub files: usize,
+    /// Distinct modules importing this one.
+    pub fan_in: usize,
+    /// Distinct modules this one imports.
+    pub fan_out: usize,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+    /// File-level imports behind this edge.
+    pub imports: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ModuleGraph {
+    pub granularity: String,
+    pub modules: Vec<ModuleNode>,
+    pub edges: Vec<ModuleEdge>,
+    /// Strongly connected groups of two or more modules, each sorted by name.
+    pub cycles: Vec<Vec<String>>,
+    /// Layer order checked, top (may import anything below) first.
+    pub layers: Vec<String>,
+    /// Edges from a lower layer up into a higher one.
+    pub layer_violations: Vec<ModuleEdge>,
+}
+
+impl ModuleGraph {
+    pub fn build(graph: &ImportGraph, granularity: Granularity, layers: &[String]) -> Self {
+        let files = graph.files();
+        let module_of: Vec<String> = match granularity {
+            Granularity::Directory => files.iter().map(|f| display(f.parent().unwrap_or(Path::new("")))).collect(),
+            Granularity::Package => {
+                let roots: BTreeSet<PathBuf> = files
+                    .iter()
+                    .filter(|f| f.file_name().and_then(|n| n.to_str()).is_some_and(|n| PACKAGE_MANIFESTS.contains(&n)))
+                    .filter_map(|f| f.parent().map(Path::to_path_buf))
+                    .collect();
+                files
+                    .iter()
+                    .map(|f| {
+                        let parent = f.parent().unwrap_or(Path::new(""));
+                        let root = parent.ancestors().find(|a| roots.contains(*a)).unwrap_or(parent);
+                        display(root)
+                    })
+                    .collect()
+            }
+        };
+
+        let mut file_counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for m in &module_of {
+            *file_counts.entry(m).or_default() += 1;
+        }
+        let mut weights: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+        for (from, to) in graph.edges() {
+            let (a, b) = (module_of[from].as_str(), module_of[to].as_str());
+            if a != b {
+                *weights.entry((a, b)).or_default() += 1;
+            }
+        }
+
+        let names: Vec<&str> = file_counts.keys().copied().collect();
+        let index: BTreeMap<&str, usize> = names.iter().enumerate().map(|(i, n)| (*n, i)).collect();
+        let mut adjacency = vec![Vec::new(); names.len()];
+        let (mut fan_in, mut fan_out) = (vec![0; names.len()], vec![0; names.len()]);
+        for &(a, b) in weights.keys() {
+            adjacency[index[a]].push(index[b]);
+            fan_out[index[a]] += 1;
+            fan_in[index[b]] += 1;
+        }
+
+        let edges: Vec<ModuleEdge> = weights
+            .iter()
+            .map(|(&(a, b), &n)| ModuleEdge { from: a.to_string(), to: b.to_string(), imports: n })
+            .collect();
+        let cycles = strongly_connected(&adjacency)
+            .into_iter()
+            .filter(|c| c.len() > 1)
+            .map(|c| {
+                let mut names: Vec<String> = c.into_iter().map(|i| names[i].to_string()).collect();
+                names.sort();
+                names
+            })
+            .collect();
+        let layer_violations = edges
+            .iter()
+            .filter(|e| match (layer_of(&e.from, layers), layer_of(&e.to, layers)) {
+                (Some(from), Some(to)) => from > to,
+                _ => false,
+            })
+            .cloned()
+            .collect();
+        let modules = names
+            .iter()
+            .enumerate()
+            .map(|(i, n)| ModuleNode { name: n.to_string(), files: file_counts[n], fan_in: fan_in[i], fan_out: fan_out[i] })
+            .collect();
+
+        Self { granularity: granularity.to_string(), modules, edges, cycles, layers: layers.to_vec(), layer_violations }
+    }
+
+    /// Body of the GRAPH section.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Module graph (by {}): {} modules, {} edges\n\n",
+            self.granularity,
+            self.modules.len(),
+            self.edges.len()
+        );
+
+        out.push_str("Fan-in / fan-out (most depended-on first):\n\n");
+        let mut modules: Vec<&ModuleNode> = self.modules.iter().filter(|m| m.fan_in + m.fan_out > 0).collect();
+        modules.sort_by(|a, b| b.fan_in.cmp(&a.fan_in).then_with(|| a.name.cmp(&b.name)));
+        if modules.is_empty() {
+            out.push_str("  (no imports between modules)\n");
+        }
+        for m in modules {
+            out.push_str(&format!("  in {:>3}  out {:>3}  {}\n", m.fan_in, m.fan_out, m.name));
+        }
+
+        out.push_str(&format!("\nEdges (importer -> imported, file-level imports). Top {}:\n\n", MAX_LISTED_EDGES));
+        let mut edges: Vec<&ModuleEdge> = self.edges.iter().collect();
+        edges.sort_by(|a, b| b.imports.cmp(&a.imports).then_with(|| (&a.from, &a.to).cmp(&(&b.from, &b.to))));
+        for e in edges.iter().take(MAX_LISTED_EDGES) {
+            out.push_str(&format!("  {} -> {}  ({})\n", e.from, e.to, e.imports));
+        }
+
+        out.push_str("\nImport cycles:\n\n");
+        if self.cycles.is_empty() {
+            out.push_str("  none\n");
+        }
+        for cycle in &self.cycles {
+            out.push_str(&format!("  [{}]\n", cycle.join(", ")));
+        }
+
+        if !self.layers.is_empty() {
+            out.push_str(&format!("\nLayer violations (order: {}):\n\n", self.layers.join(" > ")));
+            if self.layer_violations.is_empty() {
+                out.push_str("  none\n");
+            }
+            for e in &self.layer_violations {
+                out.push_str(&format!("  {} -> {}  ({})\n", e.from, e.to, e.imports));
+            }
+        }
+        out
+    }
+
+    /// Graphviz digraph; cycle members and violating edges are drawn in red.
+    pub fn to_dot(&self) -> String {
+        let in_cycle: BTreeSet<&str> = self.cycles.iter().flatten().map(String::as_str).collect();
+        let mut out = String::from("digraph modules {\n  rankdir=LR;\n  node [shape=box, fontname=\"monospace\"];\n");
+        for m in &self.modules {
+            let color = if in_cycle.contains(m.name.as_str()) { ", color=red" } else { "" };
+            out.push_str(&format!("  {} [label=\"{}\\n{} files\"{}];\n", dot_id(&m.name), dot_escape(&m.name), m.files, color));
+        }
+        for e in &self.edges {
+            let color = if self.layer_violations.contains(e) { ", color=red" } else { "" };
+            out.push_str(&format!("  {} -> {} [label=\"{}\"{}];\n", dot_id(&e.from), dot_id(&e.to), e.imports, color));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    pub fn write(&self, pack_dir: &Path) -> Result<()> {
+        let dot_path = pack_dir.join(GRAPH_DOT_FILE_NAME);
+        fs::write(&dot_path, self.to_dot()).map_err(|e| SaccadeError::Io { source: e, path: dot_path })?;
+        let json_path = pack_dir.join(GRAPH_JSON_FILE_NAME);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&json_path, json).map_err(|e| SaccadeError::Io { source: e, path: json_path })
+    }
+}
+
+/// Index of the first layer `module` belongs to (the layer itself or anything under it).
+fn layer_of(module: &str, layers: &[String]) -> Option<usize> {
+    layers.iter().position(|l| {
+        let l = l.trim_end_matches('/');
+        module == l || module.strip_prefix(l).is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Tarjan's algorithm, iterative so deep graphs cannot overflow the stack.
+fn strongly_connected(adjacency: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let n = adjacency.len();
+    let mut index = vec![usize::MAX; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for start in 0..n {
+        if index[start] != usize::MAX {
+            continue;
+        }
+        // (node, next neighbour to visit)
+        let mut work = vec![(start, 0usize)];
+        while let Some(&mut (v, ref mut next)) = work.last_mut() {
+            if *next == 0 {
+                index[v] = next_index;
+                low[v] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = adjacency[v].get(*next) {
+                *next += 1;
+                if index[w] == usize::MAX {
+                    work.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            work.pop();
+            if let Some(&(parent, _)) = work.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == v {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+    components
+}
+
+fn display(path: &Path) -> String {
+    let s = path.to_string_lossy().replace('\\', "/");
+    if s.is_empty() { ".".to_string() } else { s }
+}
+
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", dot_escape(name))
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::imports::extract_imports;
+    use std::collections::HashMap;
+
+    fn graph(files: &[(&str, &str)]) -> ImportGraph {
+        let paths: Vec<PathBuf> = files.iter().map(|(p, _)| PathBuf::from(p)).collect();
+        let imports: Vec<_> = files.iter().map(|(p, c)| extract_imports(Path::new(p), c)).collect();
+        let contents: HashMap<PathBuf, String> = files.iter().map(|(p, c)| (PathBuf::from(p), c.to_string())).collect();
+        ImportGraph::from_imports(&paths, &imports, |p| contents.get(p).cloned())
+    }
+
+    #[test]
+    fn finds_cycles_and_layer_violations() {
+        let g = graph(&[
+            ("app/__init__.py", ""),
+            ("app/main.py", "import domain.model\n"),
+            ("domain/__init__.py", ""),
+            ("domain/model.py", "import infra.db\n"),
+            ("infra/__init__.py", ""),
+            ("infra/db.py", "import domain.model\nimport app.main\n"),
+        ]);
+        let layers = vec!["app".to_string(), "domain".to_string(), "infra".to_string()];
+        let mg = ModuleGraph::build(&g, Granularity::Directory, &layers);
+
+        assert_eq!(mg.cycles, vec![vec!["app".to_string(), "domain".to_string(), "infra".to_string()]]);
+        let violations: Vec<(&str, &str)> = mg.layer_violations.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+        assert_eq!(violations, [("infra", "app"), ("infra", "domain")]);
+        let domain = mg.modules.iter().find(|m| m.name == "domain").unwrap();
+        assert_eq!((domain.files, domain.fan_in, domain.fan_out), (2, 2, 1));
+
+        let text = mg.render();
+        assert!(text.contains("[app, domain, infra]"));
+        assert!(text.contains("Layer violations (order: app > domain > infra)"));
+        assert!(mg.to_dot().contains("\"infra\" -> \"app\" [label=\"1\", color=red];"));
+    }
+
+    #[test]
+    fn package_granularity_groups_by_manifest() {
+        let g = graph(&[
+            ("core/Cargo.toml", "[package]\nname = \"core\"\n"),
+            ("core/src/lib.rs", "mod net;\n"),
+            ("core/src/net.rs", ""),
+            ("cli/Cargo.toml", "[package]\nname = \"cli\"\n"),
+            ("cli/src/main.rs", "use core::net;\n"),
+        ]);
+        let mg = ModuleGraph::build(&g, Granularity::Package, &[]);
+        let names: Vec<&str> = mg.modules.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["cli", "core"]);
+        assert_eq!(mg.edges, [ModuleEdge { from: "cli".into(), to: "core".into(), imports: 1 }]);
+        assert!(mg.cycles.is_empty());
+    }
+}
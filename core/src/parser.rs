@@ -1,7 +1,10 @@
 // core/src/parser.rs
 
+use crate::registry::{LanguageRegistry, LanguageSpec};
+use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
-use std::str;
+use std::fmt;
+use std::str::{self, FromStr};
 use tree_sitter::{Node, Parser, Query, QueryCursor};
 
 const CHUNK_SEPARATOR: &str = "\n---⋯\n";
@@ -9,12 +12,22 @@ const CHUNK_SEPARATOR: &str = "\n---⋯\n";
 /// ─────────────────────────────────────────────────────────────────────
 /// LANGUAGE-SPECIFIC QUERIES (separate per language to avoid drift)
 /// ─────────────────────────────────────────────────────────────────────
+///
+/// Capture names decide what each `SkeletonLevel` keeps:
+///   @def + @body  → signature (text before the body)
+///   @def alone    → whole node treated as a signature (e.g. TS overloads)
+///   @type         → type/struct definitions (head only below `Types`)
+///   @const        → constants and exported variables
+///   @import       → imports / use statements (`Full` only)
+///   @comment      → comments; promoted to docs when they look like docs
+///   @doc          → doc strings
+///   @capture      → anything else worth keeping (`Full` only)
 
 // JavaScript / JSX / MJS / CJS
-const JAVASCRIPT_QUERY: &str = r#"
-(import_statement) @capture
+pub(crate) const JAVASCRIPT_QUERY: &str = r#"
+(import_statement) @import
 (export_statement) @capture
-(comment) @capture
+(comment) @comment
 
 (function_declaration
   body: (statement_block) @body) @def
@@ -41,13 +54,13 @@ const JAVASCRIPT_QUERY: &str = r#"
 "#;
 
 // TypeScript / TSX
-const TYPESCRIPT_QUERY: &str = r#"
-(import_statement) @capture
+pub(crate) const TYPESCRIPT_QUERY: &str = r#"
+(import_statement) @import
 (export_statement) @capture
-(comment) @capture
-(interface_declaration) @capture
-(type_alias_declaration) @capture
-(enum_declaration) @capture
+(comment) @comment
+(interface_declaration) @type
+(type_alias_declaration) @type
+(enum_declaration) @type
 
 (function_declaration
   body: (statement_block) @body) @def
@@ -71,15 +84,17 @@ const TYPESCRIPT_QUERY: &str = r#"
 "#;
 
 // Rust — use only field names for body to avoid node-type drift
-const RUST_QUERY: &str = r#"
-(line_comment) @capture
-(block_comment) @capture
-(use_declaration) @capture
-(extern_crate_declaration) @capture
-(struct_item) @capture
-(enum_item) @capture
-(union_item) @capture
-(type_item) @capture
+pub(crate) const RUST_QUERY: &str = r#"
+(line_comment) @comment
+(block_comment) @comment
+(use_declaration) @import
+(extern_crate_declaration) @import
+(struct_item) @type
+(enum_item) @type
+(union_item) @type
+(type_item) @type
+(const_item) @const
+(static_item) @const
 (mod_item) @capture
 (macro_definition) @capture
 
@@ -94,60 +109,231 @@ const RUST_QUERY: &str = r#"
 "#;
 
 // Python
-const PYTHON_QUERY: &str = r#"
-(comment) @capture
-(import_statement) @capture
-(import_from_statement) @capture
+pub(crate) const PYTHON_QUERY: &str = r#"
+(comment) @comment
+(import_statement) @import
+(import_from_statement) @import
 
 (function_definition
   body: (block) @body) @def
 
 (class_definition
   body: (block) @body) @def
+
+(module . (expression_statement (string) @doc))
+(function_definition
+  body: (block . (expression_statement (string) @doc)))
+(class_definition
+  body: (block . (expression_statement (string) @doc)))
+
+; Module-level UPPER_CASE assignments are constants by convention.
+(module
+  (expression_statement
+    (assignment
+      left: (identifier) @const_name)) @const
+  (#match? @const_name "^[A-Z][A-Z0-9_]*$"))
+"#;
+
+// Ruby — modules/classes/defs plus receiver-less DSL calls (associations, routes, requires)
+pub(crate) const RUBY_QUERY: &str = r#"
+(comment) @comment
+
+(module
+  body: (_) @body) @def
+(module !body) @def
+
+(class
+  body: (_) @body) @def
+(class !body) @def
+
+(singleton_class
+  body: (_) @body) @def
+
+(method
+  body: (_) @body) @def
+(method !body) @def
+
+(singleton_method
+  body: (_) @body) @def
+(singleton_method !body) @def
+
+(call
+  !receiver
+  method: (identifier) @dsl
+  block: (_) @body
+  (#match? @dsl "^(get|post|put|patch|delete|resources|resource|namespace|scope|concern|included|class_methods)$")) @def
+
+(call
+  !receiver
+  method: (identifier) @dsl
+  (#match? @dsl "^(require|require_relative)$")) @import
+
+(call
+  !receiver
+  method: (identifier) @dsl
+  !block
+  (#match? @dsl "^(include|extend|prepend|attr_reader|attr_writer|attr_accessor|has_many|has_one|belongs_to|has_and_belongs_to_many|validates|validate|scope|before_action|after_action|around_action|get|post|put|patch|delete|resources|resource|root|mount)$")) @def
+
+(assignment
+  left: (constant)) @const
+"#;
+
+// PHP — namespaces, use statements, type declarations and function/method signatures
+pub(crate) const PHP_QUERY: &str = r#"
+(comment) @comment
+(namespace_use_declaration) @import
+(const_declaration) @const
+
+(namespace_definition
+  body: (_) @body) @def
+(namespace_definition !body) @def
+
+(class_declaration
+  body: (_) @body) @def
+
+(interface_declaration
+  body: (_) @body) @def
+
+(trait_declaration
+  body: (_) @body) @def
+
+(enum_declaration
+  body: (_) @body) @def
+
+(function_definition
+  body: (_) @body) @def
+
+(method_declaration
+  body: (_) @body) @def
+(method_declaration !body) @def
+"#;
+
+// Bash — function definitions, top-level exports and sourced files
+pub(crate) const BASH_QUERY: &str = r#"
+(comment) @comment
+
+(function_definition
+  body: (_) @body) @def
+
+(program
+  (declaration_command "export") @const)
+
+(command
+  name: (command_name) @cmd
+  (#match? @cmd "^(source|\\.)$")) @import
 "#;
 
-/// Skeletonizes a single file's content using Tree-sitter.
+static BUILTIN_REGISTRY: Lazy<LanguageRegistry> = Lazy::new(LanguageRegistry::builtin);
+
+/// How much of each file Stage-2 keeps, from smallest to largest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum SkeletonLevel {
+    /// Names only: `fn get_user`, `struct User`, indented by nesting.
+    Outline,
+    /// Signatures and doc comments; types reduced to their header line.
+    Signatures,
+    /// Signatures, doc comments, full type/struct definitions and constants.
+    Types,
+    /// Everything the queries capture, including imports and all comments.
+    #[default]
+    Full,
+}
+
+impl FromStr for SkeletonLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "outline" | "names" => Ok(Self::Outline),
+            "signatures" | "sigs" => Ok(Self::Signatures),
+            "types" => Ok(Self::Types),
+            "full" => Ok(Self::Full),
+            other => Err(format!("unknown skeleton level '{}' (expected outline|signatures|types|full)", other)),
+        }
+    }
+}
+
+impl fmt::Display for SkeletonLevel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Outline => "outline",
+            Self::Signatures => "signatures",
+            Self::Types => "types",
+            Self::Full => "full",
+        };
+        f.write_str(name)
+    }
+}
+
+/// What a captured node is, derived from its capture name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemRole {
+    Def,
+    Type,
+    Const,
+    Import,
+    Comment,
+    Doc,
+    Other,
+}
+
+/// Capture names checked (in order) when a match has no @def/@body pair.
+const ROLE_CAPTURES: &[(&str, ItemRole)] = &[
+    ("type", ItemRole::Type),
+    ("const", ItemRole::Const),
+    ("import", ItemRole::Import),
+    ("doc", ItemRole::Doc),
+    ("comment", ItemRole::Comment),
+    ("capture", ItemRole::Other),
+];
+
+/// One entry of a file's skeleton, with the line span of the node it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkeletonItem {
+    pub role: ItemRole,
+    /// Short label: `fn`, `struct`, `class`, `import`, `comment`, `doc`, …
+    pub kind: String,
+    pub name: Option<String>,
+    /// `pub`, `pub(crate)`, `private`, `public`, `export`, … when the language says.
+    pub visibility: Option<String>,
+    /// Name of the innermost enclosing def/type (impl, class, module, …).
+    pub parent: Option<String>,
+    /// Signature, definition or comment text, shaped by the `SkeletonLevel`.
+    pub text: String,
+    /// 1-based, inclusive; spans the whole node (bodies included).
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// One captured node, before level filtering.
+struct Item<'t> {
+    role: ItemRole,
+    node: Node<'t>,
+    /// Signature for defs (text before the body), full text otherwise.
+    text: String,
+}
+
+/// Skeletonizes a single file's content using the built-in grammars.
 /// Returns a token-light "skeleton": defs with bodies stripped + salient captures.
 pub fn skeletonize_file(content: &str, file_extension: &str) -> Option<String> {
-    enum Lang<'a> {
-        Js(&'a str),
-        Ts(&'a str, bool), // Add bool flag for TSX vs TS
-        Rs(&'a str),
-        Py(&'a str),
-    }
+    skeletonize(BUILTIN_REGISTRY.get(file_extension)?, content, SkeletonLevel::Full)
+}
 
-    let lang = match file_extension {
-        // JavaScript-family (explicitly include mjs/cjs)
-        "js" | "jsx" | "mjs" | "cjs" => {
-            Lang::Js(JAVASCRIPT_QUERY)
-        }
-        // TypeScript-family: use correct grammar per extension
-        "ts" => Lang::Ts(TYPESCRIPT_QUERY, false),  // Use TS grammar
-        "tsx" => Lang::Ts(TYPESCRIPT_QUERY, true),  // Use TSX grammar
-        "rs" => Lang::Rs(RUST_QUERY),
-        "py" => Lang::Py(PYTHON_QUERY),
-        _ => return None,
-    };
+/// Skeletonizes `content` with an explicit grammar/query pair from a `LanguageRegistry`.
+pub fn skeletonize(spec: &LanguageSpec, content: &str, level: SkeletonLevel) -> Option<String> {
+    let items = skeleton_items(spec, content, level)?;
+    let texts: Vec<&str> = items.iter().map(|i| i.text.as_str()).collect();
+    Some(texts.join(CHUNK_SEPARATOR))
+}
 
+/// Like `skeletonize`, but keeps each entry separate with its kind, name and line span.
+pub fn skeleton_items(spec: &LanguageSpec, content: &str, level: SkeletonLevel) -> Option<Vec<SkeletonItem>> {
     let mut parser = Parser::new();
+    let language = &spec.language;
+    let query_str = spec.skeleton_query.as_str();
 
-    // Select language + query string
-    let (language, query_str) = match lang {
-        Lang::Js(q) => (tree_sitter_javascript::language(), q),
-        Lang::Ts(q, is_tsx) => {
-            let language = if is_tsx {
-                tree_sitter_typescript::language_tsx()
-            } else {
-                tree_sitter_typescript::language_typescript()
-            };
-            (language, q)
-        },
-        Lang::Rs(q) => (tree_sitter_rust::language(), q),
-        Lang::Py(q) => (tree_sitter_python::language(), q),
-    };
-
-    if let Err(e) = parser.set_language(&language) {
-        eprintln!("WARN: set_language failed for .{}: {}", file_extension, e);
+    if let Err(e) = parser.set_language(language) {
+        eprintln!("WARN: set_language failed for {}: {}", spec.name, e);
         return None;
     }
 
@@ -156,22 +342,35 @@ pub fn skeletonize_file(content: &str, file_extension: &str) -> Option<String> {
         None => return None,
     };
 
-    let query = match Query::new(&language, query_str) {
+    let query = match Query::new(language, query_str) {
         Ok(q) => q,
         Err(e) => {
             // Avoid noisy panics; print a compact one-liner and skip.
             eprintln!(
-                "WARN: query compile failed for .{} at row {} col {}: {}",
-                file_extension, e.row, e.column, e.message
+                "WARN: query compile failed for {} at row {} col {}: {}",
+                spec.name, e.row, e.column, e.message
             );
             return None;
         }
     };
 
+    let mut items = collect_items(&query, tree.root_node(), content);
+    promote_doc_comments(&mut items);
+    let results = render(&items, level, content);
+
+    if results.is_empty() {
+        None
+    } else {
+        Some(results)
+    }
+}
+
+/// Run the query and turn each match into at most one `Item` (first capture of a node wins).
+fn collect_items<'t>(query: &Query, root: Node<'t>, content: &str) -> Vec<Item<'t>> {
     let mut cursor = QueryCursor::new();
-    let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+    let matches = cursor.matches(query, root, content.as_bytes());
 
-    let mut results = Vec::new();
+    let mut items = Vec::new();
     let mut seen_ids: HashSet<usize> = HashSet::new();
 
     for m in matches {
@@ -183,61 +382,232 @@ pub fn skeletonize_file(content: &str, file_extension: &str) -> Option<String> {
         }
 
         // Prefer def/body pairs → slice signature text only
-        if let (Some(def), Some(body)) = (caps.get("def"), caps.get("body")) {
-            let def_id = def.id();
-            if !seen_ids.contains(&def_id) {
-                let start = def.start_byte();
-                let end = body.start_byte();
-                if end >= start {
-                    if let Some(sig) = safe_slice(content, start, end) {
-                        let sig_trim = sig.trim();
-                        if !sig_trim.is_empty() {
-                            results.push(sig_trim.to_string());
-                        }
-                    }
-                }
-                seen_ids.insert(def_id);
-            }
+        let (role, node, text) = if let (Some(def), Some(body)) = (caps.get("def"), caps.get("body")) {
+            let sig = safe_slice(content, def.start_byte(), body.start_byte()).unwrap_or("");
+            (ItemRole::Def, *def, sig)
+        } else if let Some((node, role)) = ROLE_CAPTURES.iter().find_map(|(n, r)| caps.get(n).map(|node| (*node, *r))) {
+            // Otherwise, simple capture (imports/comments/etc.)
+            (role, node, node.utf8_text(content.as_bytes()).unwrap_or(""))
+        } else if let Some(def) = caps.get("def") {
+            // Def with no body (e.g., TS overloads)
+            (ItemRole::Def, *def, def.utf8_text(content.as_bytes()).unwrap_or(""))
+        } else {
+            continue;
+        };
+
+        if !seen_ids.insert(node.id()) {
             continue;
         }
+        let text = text.trim();
+        if !text.is_empty() {
+            items.push(Item { role, node, text: text.to_string() });
+        }
+    }
 
-        // Otherwise, simple capture (imports/comments/etc.)
-        if let Some(cap) = caps.get("capture") {
-            let id = cap.id();
-            if !seen_ids.contains(&id) {
-                if let Ok(text) = cap.utf8_text(content.as_bytes()) {
-                    let t = text.trim();
-                    if !t.is_empty() {
-                        results.push(t.to_string());
-                    }
-                }
-                seen_ids.insert(id);
-            }
+    items
+}
+
+/// Mark comments that document the next item as docs. `///`, `//!`, `/**` and `/*!`
+/// always count; `#`-style comments count when they sit directly above a declaration
+/// (or above another doc line, so multi-line blocks carry over).
+fn promote_doc_comments(items: &mut [Item]) {
+    items.sort_by_key(|i| i.node.start_byte());
+    for idx in (0..items.len()).rev() {
+        if items[idx].role != ItemRole::Comment {
             continue;
         }
+        let text = items[idx].text.as_str();
+        let marked = ["///", "//!", "/**", "/*!"].iter().any(|p| text.starts_with(p));
+        let adjacent = text.starts_with('#')
+            && !text.starts_with("#!")
+            && items.get(idx + 1).is_some_and(|next| {
+                next.node.start_position().row == items[idx].node.end_position().row + 1
+                    && matches!(next.role, ItemRole::Def | ItemRole::Type | ItemRole::Const | ItemRole::Doc)
+            });
+        if marked || adjacent {
+            items[idx].role = ItemRole::Doc;
+        }
+    }
+}
 
-        // Def with no body (e.g., TS overloads)
-        if let Some(def) = caps.get("def") {
-            let id = def.id();
-            if !seen_ids.contains(&id) {
-                if let Ok(text) = def.utf8_text(content.as_bytes()) {
-                    let t = text.trim();
-                    if !t.is_empty() {
-                        results.push(t.to_string());
-                    }
-                }
-                seen_ids.insert(id);
+/// Keep and shape items according to `level`.
+fn render(items: &[Item], level: SkeletonLevel, content: &str) -> Vec<SkeletonItem> {
+    let mut out = Vec::new();
+    for (idx, item) in items.iter().enumerate() {
+        let text = match (level, item.role) {
+            (SkeletonLevel::Outline, ItemRole::Def | ItemRole::Type | ItemRole::Const) => {
+                let Some(entry) = outline_entry(item, content) else { continue };
+                let depth = items[..idx]
+                    .iter()
+                    .filter(|o| matches!(o.role, ItemRole::Def | ItemRole::Type) && o.node.end_byte() >= item.node.end_byte())
+                    .count();
+                format!("{}{}", "  ".repeat(depth), entry)
             }
+            (SkeletonLevel::Outline, _) => continue,
+            (_, ItemRole::Def | ItemRole::Doc) => item.text.clone(),
+            (SkeletonLevel::Signatures, ItemRole::Type) => type_header(&item.text).to_string(),
+            (SkeletonLevel::Signatures, _) => continue,
+            (_, ItemRole::Type | ItemRole::Const) => item.text.clone(),
+            (SkeletonLevel::Types, _) => continue,
+            (SkeletonLevel::Full, _) => item.text.clone(),
+        };
+        let (start_line, end_line) = line_span(item.node);
+        let name = item_name(item.node, content);
+        out.push(SkeletonItem {
+            role: item.role,
+            kind: item_kind(item).to_string(),
+            visibility: visibility(item.node, name.as_deref(), content),
+            parent: parent_item(items, idx).and_then(|p| item_name(p.node, content)),
+            name,
+            text,
+            start_line,
+            end_line,
+        });
+    }
+    out
+}
+
+fn item_kind<'a>(item: &'a Item) -> &'a str {
+    match item.role {
+        ItemRole::Import => "import",
+        ItemRole::Comment => "comment",
+        ItemRole::Doc => "doc",
+        _ => kind_label(item.node.kind()),
+    }
+}
+
+/// Innermost earlier def/type whose node encloses `items[idx]` (items are sorted by start).
+fn parent_item<'a, 't>(items: &'a [Item<'t>], idx: usize) -> Option<&'a Item<'t>> {
+    let node = items[idx].node;
+    items[..idx].iter().rev().find(|o| {
+        matches!(o.role, ItemRole::Def | ItemRole::Type)
+            && o.node.start_byte() <= node.start_byte()
+            && o.node.end_byte() >= node.end_byte()
+            && o.node.id() != node.id()
+    })
+}
+
+/// Visibility from modifier nodes (Rust/PHP/TS), `export` wrappers (JS/TS), Rust's
+/// private-by-default items, or Python's leading-underscore convention.
+fn visibility(node: Node, name: Option<&str>, content: &str) -> Option<String> {
+    if let Some(owner) = rust_trait_owner(node, content) {
+        return owner.and_then(|trait_node| visibility(trait_node, None, content));
+    }
+    let mut walker = node.walk();
+    let modifier = node
+        .children(&mut walker)
+        .find(|c| matches!(c.kind(), "visibility_modifier" | "accessibility_modifier"));
+    if let Some(m) = modifier {
+        return m.utf8_text(content.as_bytes()).ok().map(|t| t.trim().to_string());
+    }
+    if node.parent().is_some_and(|p| p.kind() == "export_statement") {
+        return Some("export".to_string());
+    }
+    match node.kind() {
+        "function_item" | "struct_item" | "enum_item" | "union_item" | "trait_item" | "type_item" | "const_item"
+        | "static_item" | "mod_item" => Some("private".to_string()),
+        "function_definition" | "class_definition" => {
+            let name = name?;
+            let private = name.starts_with('_') && !name.ends_with("__");
+            Some(if private { "private" } else { "public" }.to_string())
         }
+        _ => None,
     }
+}
 
-    if results.is_empty() {
-        None
-    } else {
-        Some(results.join(CHUNK_SEPARATOR))
+/// Rust trait members and trait-impl members take the trait's visibility: `Some(trait)` when
+/// the trait is declared in this file, `Some(None)` when it is not, `None` outside a trait.
+fn rust_trait_owner<'t>(node: Node<'t>, content: &str) -> Option<Option<Node<'t>>> {
+    let block = node.parent().filter(|p| p.kind() == "declaration_list")?;
+    let owner = block.parent()?;
+    match owner.kind() {
+        "trait_item" => Some(Some(owner)),
+        "impl_item" => {
+            let trait_ref = owner.child_by_field_name("trait")?;
+            let text = trait_ref.utf8_text(content.as_bytes()).ok()?;
+            // `fmt::Display<T>` → `Display`
+            let name = text.split('<').next().unwrap_or(text).rsplit("::").next().unwrap_or(text).trim();
+            let mut root = owner;
+            while let Some(parent) = root.parent() {
+                root = parent;
+            }
+            let mut walker = root.walk();
+            let declared = root.children(&mut walker).find(|c| {
+                c.kind() == "trait_item"
+                    && c.child_by_field_name("name").and_then(|n| n.utf8_text(content.as_bytes()).ok()) == Some(name)
+            });
+            Some(declared)
+        }
+        _ => None,
     }
 }
 
+/// 1-based inclusive lines; a node ending at column 0 (trailing newline) ends on the previous line.
+fn line_span(node: Node) -> (usize, usize) {
+    let start = node.start_position();
+    let end = node.end_position();
+    let end_row = if end.column == 0 && end.row > start.row { end.row - 1 } else { end.row };
+    (start.row + 1, end_row + 1)
+}
+
+/// `fn get_user`, `struct User`, `class UsersController` … Anonymous closures are dropped.
+fn outline_entry(item: &Item, content: &str) -> Option<String> {
+    let label = kind_label(item.node.kind());
+    match item_name(item.node, content) {
+        Some(name) => Some(format!("{} {}", label, name)),
+        None if item.node.kind() == "arrow_function" => None,
+        None => Some(item.text.lines().next().unwrap_or("").trim().to_string()),
+    }
+}
+
+/// Short, language-neutral label for a declaration node kind.
+fn kind_label(kind: &str) -> &str {
+    match kind {
+        "function_item" | "function_declaration" | "function_definition" | "method_definition"
+        | "method_declaration" | "method" | "singleton_method" | "function_signature_item" => "fn",
+        "lexical_declaration" | "arrow_function" => "fn",
+        "struct_item" => "struct",
+        "enum_item" | "enum_declaration" => "enum",
+        "union_item" => "union",
+        "trait_item" | "trait_declaration" => "trait",
+        "impl_item" => "impl",
+        "type_item" | "type_alias_declaration" => "type",
+        "interface_declaration" => "interface",
+        "class_declaration" | "class_definition" | "class" | "singleton_class" => "class",
+        "module" | "mod_item" | "namespace_definition" => "mod",
+        "const_item" | "const_declaration" | "expression_statement" | "assignment" | "declaration_command" => "const",
+        "static_item" => "static",
+        _ => kind,
+    }
+}
+
+/// Best-effort declared name: the `name` field, `impl` targets, or a direct child's name.
+fn item_name(node: Node, content: &str) -> Option<String> {
+    let text = |n: Node| n.utf8_text(content.as_bytes()).ok().map(|t| t.trim().to_string());
+    if let Some(name) = node.child_by_field_name("name").or_else(|| node.child_by_field_name("left")) {
+        return text(name);
+    }
+    if node.kind() == "impl_item" {
+        let ty = text(node.child_by_field_name("type")?)?;
+        return match node.child_by_field_name("trait").and_then(text) {
+            Some(tr) => Some(format!("{} for {}", tr, ty)),
+            None => Some(ty),
+        };
+    }
+    let mut walker = node.walk();
+    let found = node
+        .named_children(&mut walker)
+        .find_map(|child| child.child_by_field_name("name").or_else(|| child.child_by_field_name("left")))
+        .and_then(text);
+    found
+}
+
+/// `pub struct User { … }` → `pub struct User`; otherwise the first line.
+fn type_header(text: &str) -> &str {
+    let first = text.lines().next().unwrap_or("");
+    first.split('{').next().unwrap_or(first).trim_end()
+}
+
 /// Return a &str slice by byte offsets, guarding UTF-8 boundaries.
 fn safe_slice<'a>(s: &'a str, start: usize, end: usize) -> Option<&'a str> {
     if start > end || end > s.len() {
@@ -253,4 +623,123 @@ fn safe_slice<'a>(s: &'a str, start: usize, end: usize) -> Option<&'a str> {
         b += 1;
     }
     s.get(a..b)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ruby_keeps_classes_defs_and_dsl_calls() {
+        let src = "class User < ApplicationRecord\n  has_many :posts\n\n  def full_name(sep)\n    first + sep + last\n  end\nend\n";
+        let skel = skeletonize_file(src, "rb").unwrap();
+        assert!(skel.contains("class User < ApplicationRecord"));
+        assert!(skel.contains("has_many :posts"));
+        assert!(skel.contains("def full_name(sep)"));
+        assert!(!skel.contains("first + sep + last"));
+    }
+
+    #[test]
+    fn php_keeps_namespace_uses_and_signatures() {
+        let src = "<?php\nnamespace App\\Http;\n\nuse Illuminate\\Http\\Request;\n\nclass UserController extends Controller\n{\n    public function show(Request $r): User\n    {\n        return User::find($r->id);\n    }\n}\n";
+        let skel = skeletonize_file(src, "php").unwrap();
+        assert!(skel.contains("namespace App\\Http;"));
+        assert!(skel.contains("use Illuminate\\Http\\Request;"));
+        assert!(skel.contains("class UserController extends Controller"));
+        assert!(skel.contains("public function show(Request $r): User"));
+        assert!(!skel.contains("User::find"));
+    }
+
+    #[test]
+    fn bash_keeps_functions_exports_and_sources() {
+        let src = "#!/usr/bin/env bash\nsource ./lib/common.sh\nexport DEPLOY_ENV=prod\n\ndeploy() {\n  rsync -a dist/ \"$HOST\"\n}\n";
+        let skel = skeletonize_file(src, "sh").unwrap();
+        assert!(skel.contains("source ./lib/common.sh"));
+        assert!(skel.contains("export DEPLOY_ENV=prod"));
+        assert!(skel.contains("deploy()"));
+        assert!(!skel.contains("rsync"));
+    }
+
+    #[test]
+    fn items_carry_kind_name_and_line_span() {
+        let spec = BUILTIN_REGISTRY.get("rs").unwrap();
+        let items = skeleton_items(spec, RUST_SAMPLE, SkeletonLevel::Signatures).unwrap();
+        let get_user = items.iter().find(|i| i.name.as_deref() == Some("get_user")).unwrap();
+        assert_eq!(get_user.kind, "fn");
+        assert_eq!((get_user.start_line, get_user.end_line), (12, 14));
+        assert_eq!(get_user.visibility.as_deref(), Some("pub"));
+        assert_eq!(get_user.parent.as_deref(), Some("User"));
+        let doc = items.iter().find(|i| i.kind == "doc").unwrap();
+        assert_eq!((doc.start_line, doc.end_line), (11, 11));
+        assert_eq!(doc.role, ItemRole::Doc);
+    }
+
+    #[test]
+    fn trait_members_inherit_the_trait_visibility() {
+        let src = "pub trait Store {\n    fn load(&self) -> u8 { 0 }\n    fn warm(&self) {}\n}\n\n\
+                   trait Hidden {\n    fn peek(&self) {}\n}\n\n\
+                   struct Db;\n\nimpl Store for Db {\n    fn load(&self) -> u8 { 0 }\n}\n\n\
+                   impl std::fmt::Debug for Db {\n    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }\n}\n\n\
+                   impl Db {\n    fn helper(&self) {}\n}\n";
+        let spec = BUILTIN_REGISTRY.get("rs").unwrap();
+        let items = skeleton_items(spec, src, SkeletonLevel::Signatures).unwrap();
+        let vis = |name: &str, line: usize| {
+            let item = items.iter().find(|i| i.name.as_deref() == Some(name) && i.start_line == line).unwrap();
+            item.visibility.clone()
+        };
+        assert_eq!(vis("load", 2).as_deref(), Some("pub"));
+        assert_eq!(vis("warm", 3).as_deref(), Some("pub"));
+        assert_eq!(vis("peek", 7).as_deref(), Some("private"));
+        assert_eq!(vis("load", 13).as_deref(), Some("pub"));
+        assert_eq!(vis("fmt", 17), None);
+        assert_eq!(vis("helper", 21).as_deref(), Some("private"));
+    }
+
+    const RUST_SAMPLE: &str = "// Copyright 2024 Example Corp. All rights reserved.\nuse std::fmt;\n\npub const MAX_USERS: usize = 10;\n\npub struct User {\n    pub id: u64,\n}\n\nimpl User {\n    /// Looks a user up by id.\n    pub fn get_user(id: u64) -> Option<User> {\n        None\n    }\n}\n";
+
+    fn rust_at(level: SkeletonLevel) -> String {
+        let spec = BUILTIN_REGISTRY.get("rs").unwrap();
+        skeletonize(spec, RUST_SAMPLE, level).unwrap()
+    }
+
+    #[test]
+    fn outline_lists_names_with_nesting() {
+        let skel = rust_at(SkeletonLevel::Outline);
+        assert!(skel.contains("const MAX_USERS"));
+        assert!(skel.contains("struct User"));
+        assert!(skel.contains("impl User"));
+        assert!(skel.contains("  fn get_user"));
+        assert!(!skel.contains("Copyright"));
+        assert!(!skel.contains("use std::fmt"));
+    }
+
+    #[test]
+    fn signatures_keep_docs_and_drop_plain_comments_imports_and_bodies() {
+        let skel = rust_at(SkeletonLevel::Signatures);
+        assert!(skel.contains("/// Looks a user up by id."));
+        assert!(skel.contains("pub fn get_user(id: u64) -> Option<User>"));
+        assert!(skel.contains("pub struct User"));
+        assert!(!skel.contains("pub id: u64"));
+        assert!(!skel.contains("MAX_USERS"));
+        assert!(!skel.contains("Copyright"));
+        assert!(!skel.contains("use std::fmt"));
+    }
+
+    #[test]
+    fn types_level_adds_struct_bodies_and_constants() {
+        let skel = rust_at(SkeletonLevel::Types);
+        assert!(skel.contains("pub id: u64"));
+        assert!(skel.contains("pub const MAX_USERS: usize = 10;"));
+        assert!(!skel.contains("Copyright"));
+        assert!(rust_at(SkeletonLevel::Full).contains("Copyright"));
+    }
+
+    #[test]
+    fn hash_comments_above_defs_count_as_docs() {
+        let spec = BUILTIN_REGISTRY.get("py").unwrap();
+        let src = "# SPDX-License-Identifier: MIT\n\n# Fetch a user.\ndef get_user(uid):\n    return None\n";
+        let skel = skeletonize(spec, src, SkeletonLevel::Signatures).unwrap();
+        assert!(skel.contains("# Fetch a user."));
+        assert!(!skel.contains("SPDX"));
+    }
+}
@@ -0,0 +1,262 @@
+// saccade/core/src/pii.rs
+//
+// PII redaction for everything the pack emits: emails, phone numbers, IP addresses, internal
+// hostnames and home-directory user names, plus custom `NAME=REGEX` rules from the config.
+// Pseudonymisation maps each value to a stable placeholder (`<email:3f9a1c>`), so the same
+// address reads the same everywhere in the pack and across runs.
+
+use crate::error::{Result, SaccadeError};
+use once_cell::sync::Lazy;
+use regex::{Match, Regex};
+use std::fmt;
+use std::str::FromStr;
+
+/// Gets the whole text and the candidate, so a rule can look at what surrounds a hit.
+type Check = fn(&str, Match) -> bool;
+
+/// When a rule has capture groups, only the first that matched is replaced (e.g. the user in `/home/alice/`).
+#[derive(Debug, Clone)]
+pub struct PiiRule {
+    pub name: String,
+    pub re: Regex,
+    check: Option<Check>,
+}
+
+static BUILTIN_RULES: Lazy<Vec<PiiRule>> = Lazy::new(|| {
+    let rule = |name: &str, pattern: &str, check: Option<Check>| PiiRule { name: name.to_string(), re: Regex::new(pattern).unwrap(), check };
+    vec![
+        rule("email", r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}\b", Some(is_personal_email)),
+        rule("home-path", r"(?:/home|/Users)/([A-Za-z0-9._-]+)", Some(is_real_user)),
+        rule("home-path", r#"\b[A-Za-z]:\\{1,2}Users\\{1,2}([^\\\s/'"]+)"#, Some(is_real_user)),
+        rule(
+            "hostname",
+            r"(?i)(?:://|\b)((?:[a-z0-9-]+\.)+(?:internal|corp|intranet|lan|localdomain|home\.arpa))\b|\b((?:[a-z0-9-]+\.){2,}local)\b",
+            Some(is_host),
+        ),
+        rule("ip", r"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b", Some(is_routable_ip)),
+        rule("ip", r"\b(?:[0-9A-Fa-f]{1,4}:){7}[0-9A-Fa-f]{1,4}\b", None),
+        rule("phone", r"\+\d{1,3}[\s.-]?\(?\d{1,4}\)?(?:[\s.-]?\d{2,4}){2,4}\b", None),
+        // Bare US numbers need `(415) 555-0100`, `415-555-0100` or `415.555.0100`; `800 600 1024` is data.
+        rule("phone", r"\(\d{3}\)\s?\d{3}[\s.-]\d{4}\b|\b\d{3}-\d{3}-\d{4}\b|\b\d{3}\.\d{3}\.\d{4}\b", None),
+    ]
+});
+
+/// What happens to matched PII.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PiiMode {
+    /// Leave PII in place.
+    Off,
+    /// Replace with `<redacted:NAME>`.
+    Redact,
+    /// Replace with `<NAME:hash>`; equal values get equal placeholders.
+    #[default]
+    Pseudonymize,
+}
+
+impl FromStr for PiiMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" | "none" => Ok(Self::Off),
+            "redact" => Ok(Self::Redact),
+            "pseudonymize" | "pseudonymise" => Ok(Self::Pseudonymize),
+            other => Err(format!("unknown PII mode '{}' (expected off|redact|pseudonymize)", other)),
+        }
+    }
+}
+
+impl fmt::Display for PiiMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Redact => "redact",
+            Self::Pseudonymize => "pseudonymize",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Built-in rules plus custom ones, applied with one mode.
+#[derive(Debug, Clone)]
+pub struct PiiRedactor {
+    mode: PiiMode,
+    rules: Vec<PiiRule>,
+}
+
+impl PiiRedactor {
+    pub fn new(mode: PiiMode) -> Self {
+        Self { mode, rules: BUILTIN_RULES.clone() }
+    }
+
+    /// Custom rules run after the built-ins; see `parse_rule`.
+    pub fn with_rules(mut self, rules: &[PiiRule]) -> Self {
+        self.rules.extend(rules.iter().cloned());
+        self
+    }
+
+    /// Replaces PII in `text`; returns the hits as (rule name, 1-based line).
+    pub fn redact(&self, text: &str) -> (String, Vec<(String, usize)>) {
+        if self.mode == PiiMode::Off {
+            return (text.to_string(), Vec::new());
+        }
+        let mut spans: Vec<(usize, usize, &str)> = Vec::new();
+        for rule in &self.rules {
+            for caps in rule.re.captures_iter(text) {
+                // The first participating group, else the whole match.
+                let Some(m) = (1..caps.len()).find_map(|i| caps.get(i)).or_else(|| caps.get(0)) else { continue };
+                if m.as_str().is_empty() || rule.check.is_some_and(|check| !check(text, m)) {
+                    continue;
+                }
+                if spans.iter().any(|&(start, end, _)| m.start() < end && start < m.end()) {
+                    continue;
+                }
+                spans.push((m.start(), m.end(), rule.name.as_str()));
+            }
+        }
+        if spans.is_empty() {
+            return (text.to_string(), Vec::new());
+        }
+        spans.sort();
+
+        let mut out = String::with_capacity(text.len());
+        let mut hits = Vec::with_capacity(spans.len());
+        let mut last = 0;
+        for (start, end, name) in spans {
+            out.push_str(&text[last..start]);
+            out.push_str(&self.placeholder(name, &text[start..end]));
+            hits.push((name.to_string(), text[..start].matches('\n').count() + 1));
+            last = end;
+        }
+        out.push_str(&text[last..]);
+        (out, hits)
+    }
+
+    fn placeholder(&self, name: &str, value: &str) -> String {
+        match self.mode {
+            PiiMode::Pseudonymize => format!("<{}:{:06x}>", name, fnv1a(&value.to_lowercase()) & 0xff_ffff),
+            _ => format!("<redacted:{}>", name),
+        }
+    }
+}
+
+impl Default for PiiRedactor {
+    fn default() -> Self {
+        Self::new(PiiMode::default())
+    }
+}
+
+/// Parses a `NAME=REGEX` custom rule.
+pub fn parse_rule(spec: &str) -> Result<PiiRule> {
+    let invalid = |reason: &str| SaccadeError::InvalidConfig {
+        field: "pii_rules".to_string(),
+        value: spec.to_string(),
+        reason: reason.to_string(),
+    };
+    let (name, pattern) = spec.split_once('=').ok_or_else(|| invalid("expected NAME=REGEX"))?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(invalid("NAME must be letters, digits, '-' or '_'"));
+    }
+    Ok(PiiRule { name: name.to_string(), re: Regex::new(pattern)?, check: None })
+}
+
+/// FNV-1a: stable across runs and Rust versions, unlike `DefaultHasher`.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// The identifier just before `at` on the same line, skipping spaces, quotes, `=`, `:` and `(`.
+fn preceding_word(text: &str, at: usize) -> &str {
+    let before = text[..at].trim_end_matches([' ', '\t', '"', '\'', '`', '=', ':', '(']);
+    let start = before.rfind(|c: char| !(c.is_alphanumeric() || c == '_')).map_or(0, |i| i + 1);
+    &before[start..]
+}
+
+/// Git remotes (`git@github.com`) and bot/no-reply senders are not people.
+fn is_personal_email(_: &str, m: Match) -> bool {
+    let lower = m.as_str().to_lowercase();
+    let (local, domain) = lower.split_once('@').unwrap_or_default();
+    !(local == "git" || local.contains("noreply") || local.contains("no-reply") || domain.starts_with("users.noreply."))
+}
+
+/// `/home/$USER`, `/home/user` and CI runners name no one.
+fn is_real_user(_: &str, m: Match) -> bool {
+    let s = m.as_str();
+    !matches!(s, "user" | "username" | "runner" | "root" | "ubuntu" | "$USER" | "USER" | "%USERNAME%" | "Public" | "Default" | "runneradmin")
+        && !s.starts_with('<')
+        && !s.starts_with('$')
+        && !s.starts_with('{')
+}
+
+/// Loopback, unspecified, broadcast, all-single-digit quads (`4.0.0.0`) and anything after a
+/// `v`/`version` token (`version 1.2.30.4`, `__version__ = "1.10.2.3"`) are versions, not hosts.
+fn is_routable_ip(text: &str, m: Match) -> bool {
+    let s = m.as_str();
+    let octets: Vec<&str> = s.split('.').collect();
+    let word = preceding_word(text, m.start()).trim_matches('_').to_lowercase();
+    !(s.starts_with("127.")
+        || s == "0.0.0.0"
+        || s == "255.255.255.255"
+        || octets.iter().all(|o| o.len() == 1)
+        || matches!(word.as_str(), "v" | "ver")
+        || word.ends_with("version"))
+}
+
+/// A host after `://` or `@`; otherwise not a dotted module path (`io.netty.util.internal.X`)
+/// or the target of `from`/`import`/`package`/`use`.
+fn is_host(text: &str, m: Match) -> bool {
+    let before = &text[..m.start()];
+    if before.ends_with("://") || before.ends_with('@') {
+        return true;
+    }
+    let mut after = text[m.end()..].chars();
+    if after.next() == Some('.') && after.next().is_some_and(|c| c.is_alphabetic() || c == '_') {
+        return false;
+    }
+    !matches!(preceding_word(text, m.start()), "from" | "import" | "package" | "use" | "using" | "namespace")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pseudonymizes_consistently_and_honours_custom_rules() {
+        let text = "// Contact Jane <jane.doe@acme.com>, cc JANE.DOE@acme.com\n\
+                    // log at /home/jdoe/app.log, db on 10.20.30.40 or db01.prod.internal\n\
+                    // call +1 415-555-0100; bind 0.0.0.0; remote git@github.com:org/repo; v4.0.0.0\n\
+                    // ticket EMP-12345\n";
+        let redactor = PiiRedactor::new(PiiMode::Pseudonymize).with_rules(&[parse_rule("employee-id=EMP-\\d+").unwrap()]);
+        let (out, hits) = redactor.redact(text);
+        let names: Vec<&str> = hits.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["email", "email", "home-path", "ip", "hostname", "phone", "employee-id"]);
+
+        let email = format!("<email:{:06x}>", fnv1a("jane.doe@acme.com") & 0xff_ffff);
+        assert_eq!(out.matches(&email).count(), 2);
+        assert!(out.contains("/home/<home-path:") && out.contains("0.0.0.0") && out.contains("git@github.com") && out.contains("v4.0.0.0"));
+
+        let (redacted, _) = PiiRedactor::new(PiiMode::Redact).redact("mail ops@acme.com");
+        assert_eq!(redacted, "mail <redacted:email>");
+        assert!(PiiRedactor::new(PiiMode::Off).redact(text).1.is_empty());
+        assert!(parse_rule("no equals sign").is_err());
+    }
+
+    #[test]
+    fn module_paths_versions_and_plain_numbers_are_not_pii() {
+        let code = concat!(
+            "from app.internal import tokens\n",
+            "import io.netty.util.internal.PlatformDependent;\n",
+            "\"\"\"Client library, version 1.2.30.4.\"\"\"\n",
+            "__version__ = \"1.10.2.3\"\n",
+            "size 800 600 1024\n",
+        );
+        let (out, hits) = PiiRedactor::default().redact(code);
+        assert!(hits.is_empty(), "{:?}", hits);
+        assert_eq!(out, code);
+
+        let hosts = "url = \"https://api.prod.internal/v1\"; proxy build.corp; call (415) 555-0100 or 415.555.0100\n";
+        let names: Vec<String> = PiiRedactor::default().redact(hosts).1.into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["hostname", "hostname", "phone", "phone"]);
+    }
+}
@@ -0,0 +1,218 @@
+// saccade/core/src/refs.rs
+//
+// "Who uses this symbol": every identifier node named like the symbol, found
+// through Tree-sitter parses. Used by `saccade refs <symbol>` and by
+// REQUEST_FILE `range: refs: <symbol>`.
+//
+// Hits are scoped by language family: when the symbol is defined somewhere,
+// only files in the defining families are reported, so a Rust `validate` does
+// not pull in every Python `validate`, while a TypeScript export still finds
+// its callers in `.tsx` and `.js` files.
+
+use crate::registry::LanguageRegistry;
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Node, Parser};
+
+const MAX_FILE_SIZE_FOR_REFS: u64 = 2 * 1024 * 1024; // 2 MB
+const MAX_SNIPPET_CHARS: usize = 160;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Repo-relative, forward slashes.
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    /// The trimmed source line, in full; `render` shortens it.
+    pub snippet: String,
+    pub language: String,
+    /// The identifier is the `name` of its enclosing declaration.
+    pub is_definition: bool,
+}
+
+impl Reference {
+    /// The snippet cut to `MAX_SNIPPET_CHARS`, for one-line listings.
+    pub fn short_snippet(&self) -> String {
+        self.snippet.chars().take(MAX_SNIPPET_CHARS).collect()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct RefsReport {
+    pub symbol: String,
+    pub definitions: Vec<Reference>,
+    pub references: Vec<Reference>,
+}
+
+impl RefsReport {
+    pub fn render(&self) -> String {
+        let files: BTreeSet<&str> = self.references.iter().map(|r| r.path.as_str()).collect();
+        let mut out = format!(
+            "References to '{}': {} in {} files\n",
+            self.symbol,
+            self.references.len(),
+            files.len()
+        );
+        if self.definitions.is_empty() {
+            out.push_str("Defined: (not found in repo)\n");
+        }
+        for d in &self.definitions {
+            out.push_str(&format!("Defined: {}:{}  {}\n", d.path, d.line, d.short_snippet()));
+        }
+        out.push('\n');
+        for r in &self.references {
+            out.push_str(&format!("{}:{}:{}  {}\n", r.path, r.line, r.column, r.short_snippet()));
+        }
+        out
+    }
+}
+
+/// Search `files` (relative to `base_dir`) for `symbol`. `Type::method` and
+/// `Type.method` search for `method`.
+pub fn find_references(files: &[PathBuf], base_dir: &Path, symbol: &str, registry: &LanguageRegistry) -> RefsReport {
+    let name = symbol.rsplit([':', '.']).next().unwrap_or(symbol).trim();
+    if name.is_empty() {
+        return RefsReport { symbol: symbol.to_string(), ..Default::default() };
+    }
+
+    let mut hits: Vec<Reference> = files
+        .par_iter()
+        .flat_map_iter(|relative| {
+            let absolute = base_dir.join(relative);
+            let Some(content) = read_candidate(&absolute, name) else { return Vec::new() };
+            references_in_source(relative, &content, name, registry)
+        })
+        .collect();
+    hits.sort_by(|a, b| (&a.path, a.line, a.column).cmp(&(&b.path, b.line, b.column)));
+
+    let defining: BTreeSet<String> =
+        hits.iter().filter(|h| h.is_definition).map(|h| language_family(&h.language).to_string()).collect();
+    if !defining.is_empty() {
+        hits.retain(|h| defining.contains(language_family(&h.language)));
+    }
+    let (definitions, references) = hits.into_iter().partition(|h| h.is_definition);
+    RefsReport { symbol: symbol.to_string(), definitions, references }
+}
+
+/// Languages that import each other's definitions share a family.
+fn language_family(language: &str) -> &str {
+    match language {
+        "typescript" | "tsx" | "javascript" => "javascript",
+        other => other,
+    }
+}
+
+/// Identifier hits for `name` in one file; empty if the language is unknown.
+pub fn references_in_source(path: &Path, content: &str, name: &str, registry: &LanguageRegistry) -> Vec<Reference> {
+    let Some(spec) = path.extension().and_then(|e| e.to_str()).and_then(|ext| registry.get(ext)) else {
+        return Vec::new();
+    };
+    let mut parser = Parser::new();
+    if parser.set_language(&spec.language).is_err() {
+        return Vec::new();
+    }
+    let Some(tree) = parser.parse(content, None) else { return Vec::new() };
+
+    let lines: Vec<&str> = content.lines().collect();
+    let display_path = path.to_string_lossy().replace('\\', "/");
+    let mut out = Vec::new();
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.child_count() == 0 {
+            if is_identifier(node) && node.utf8_text(content.as_bytes()) == Ok(name) {
+                let pos = node.start_position();
+                let line = lines.get(pos.row).map(|l| l.trim()).unwrap_or("");
+                out.push(Reference {
+                    path: display_path.clone(),
+                    line: pos.row + 1,
+                    column: pos.column + 1,
+                    snippet: line.to_string(),
+                    language: spec.name.clone(),
+                    is_definition: is_declaration_name(node),
+                });
+            }
+            continue;
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    out
+}
+
+/// Cheap substring check before paying for a parse.
+fn read_candidate(path: &Path, name: &str) -> Option<String> {
+    if fs::metadata(path).ok()?.len() > MAX_FILE_SIZE_FOR_REFS {
+        return None;
+    }
+    let content = fs::read_to_string(path).ok()?;
+    content.contains(name).then_some(content)
+}
+
+/// `identifier`, `field_identifier`, `type_identifier`, `property_identifier`, …,
+/// plus Ruby `constant` and PHP `name`.
+fn is_identifier(node: Node) -> bool {
+    let kind = node.kind();
+    node.is_named() && (kind.ends_with("identifier") || kind == "constant" || kind == "name")
+}
+
+/// The `name` of a declaration node (`function_item`, `class_definition`, `variable_declarator`, …);
+/// `scoped_identifier` and friends also have a `name` field but are uses.
+fn is_declaration_name(node: Node) -> bool {
+    let Some(parent) = node.parent() else { return false };
+    let kind = parent.kind();
+    let declares = ["_item", "_declaration", "_definition", "declarator", "_spec"].iter().any(|s| kind.ends_with(s))
+        || matches!(kind, "method" | "class" | "module" | "singleton_method");
+    declares && parent.child_by_field_name("name").is_some_and(|n| n.id() == node.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn finds_calls_and_separates_definitions_by_language() {
+        let tmp = TempDir::new().unwrap();
+        let files = [
+            ("src/auth.rs", "pub fn validate_token(t: &str) -> bool {\n    !t.is_empty()\n}\n"),
+            ("src/api.rs", "fn handle(tok: &str) {\n    // validate_token in a comment is not a reference\n    if !crate::auth::validate_token(tok) {}\n}\n"),
+            ("tools/check.py", "def run():\n    validate_token('x')\n"),
+        ];
+        for (p, c) in files {
+            fs::create_dir_all(tmp.path().join(p).parent().unwrap()).unwrap();
+            fs::write(tmp.path().join(p), c).unwrap();
+        }
+        let paths: Vec<PathBuf> = files.iter().map(|(p, _)| PathBuf::from(p)).collect();
+
+        let report = find_references(&paths, tmp.path(), "auth::validate_token", &LanguageRegistry::builtin());
+        assert_eq!(report.definitions.len(), 1);
+        assert_eq!((report.definitions[0].path.as_str(), report.definitions[0].line), ("src/auth.rs", 1));
+        assert_eq!(report.references.len(), 1);
+        let call = &report.references[0];
+        assert_eq!((call.path.as_str(), call.line, call.column), ("src/api.rs", 3, 22));
+        assert_eq!(call.snippet, "if !crate::auth::validate_token(tok) {}");
+        assert!(report.render().contains("src/api.rs:3:22  if !crate::auth::validate_token(tok) {}"));
+    }
+
+    #[test]
+    fn typescript_definitions_keep_callers_in_tsx_and_js() {
+        let tmp = TempDir::new().unwrap();
+        let files = [
+            ("src/format.ts", "export function formatDate(d: Date): string {\n  return d.toISOString();\n}\n"),
+            ("src/View.tsx", "const View = () => <span>{formatDate(new Date())}</span>;\n"),
+            ("legacy/old.js", "console.log(formatDate(new Date()));\n"),
+            ("tools/fmt.py", "formatDate('x')\n"),
+        ];
+        for (p, c) in files {
+            fs::create_dir_all(tmp.path().join(p).parent().unwrap()).unwrap();
+            fs::write(tmp.path().join(p), c).unwrap();
+        }
+        let paths: Vec<PathBuf> = files.iter().map(|(p, _)| PathBuf::from(p)).collect();
+
+        let report = find_references(&paths, tmp.path(), "formatDate", &LanguageRegistry::builtin());
+        let callers: Vec<&str> = report.references.iter().map(|r| r.path.as_str()).collect();
+        assert_eq!(callers, ["legacy/old.js", "src/View.tsx"]);
+    }
+}
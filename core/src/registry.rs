@@ -0,0 +1,285 @@
+// saccade/core/src/registry.rs
+//
+// Maps file extensions to Tree-sitter grammars and skeleton queries.
+//
+// Built-in grammars are compiled in. Extra grammars and query overrides are
+// discovered at runtime from "language dirs" (see `default_language_dirs`):
+//
+//   <dir>/languages.json                 - optional list of extra/remapped languages
+//   <dir>/queries/<name>/skeleton.scm    - skeleton query for language <name>
+//
+// Native grammars are only loaded from trusted dirs (the user config dir and `--lang-dir`):
+// dlopen runs the library's constructors. A repo-local `./.saccade` belongs to the checkout
+// being packed, so only its `.scm` query overrides are used.
+//
+// `languages.json` entries look like:
+//   { "name": "mydsl", "library": "grammars/libmydsl.so", "extensions": ["dsl"] }
+// `library` is a shared object built with `tree-sitter build`; it must export
+// `tree_sitter_<name>` (override with "symbol"). An entry without `library`
+// just maps extra extensions onto an already-registered language.
+
+use crate::parser::{
+    BASH_QUERY, JAVASCRIPT_QUERY, PHP_QUERY, PYTHON_QUERY, RUBY_QUERY, RUST_QUERY, TYPESCRIPT_QUERY,
+};
+use libloading::{Library, Symbol};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tree_sitter::{Language, Query};
+
+const LANGUAGES_FILE: &str = "languages.json";
+const SKELETON_QUERY_FILE: &str = "skeleton.scm";
+
+/// Where grammars and queries may come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageDir {
+    pub path: PathBuf,
+    /// May load `languages.json` (and so native libraries); otherwise `.scm` overrides only.
+    pub trusted: bool,
+}
+
+impl LanguageDir {
+    pub fn trusted(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), trusted: true }
+    }
+
+    pub fn queries_only(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), trusted: false }
+    }
+}
+
+/// A grammar plus the skeleton query run against it.
+pub struct LanguageSpec {
+    pub name: String,
+    pub language: Language,
+    pub skeleton_query: String,
+}
+
+/// Extension → language lookup used by Stage-2.
+pub struct LanguageRegistry {
+    by_ext: HashMap<String, Arc<LanguageSpec>>,
+    // Keeps dynamically loaded grammars mapped for as long as their `Language`s live.
+    // Declared last so it is dropped after `by_ext`.
+    _libraries: Vec<Library>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LanguageEntry {
+    name: String,
+    #[serde(default)]
+    library: Option<PathBuf>,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default)]
+    extensions: Vec<String>,
+}
+
+impl LanguageRegistry {
+    /// Only the grammars compiled into saccade, with their built-in queries.
+    pub fn builtin() -> Self {
+        let mut registry = Self { by_ext: HashMap::new(), _libraries: Vec::new() };
+        registry.register("javascript", tree_sitter_javascript::language(), JAVASCRIPT_QUERY, &["js", "jsx", "mjs", "cjs"]);
+        // TS and TSX share a query but need different grammars.
+        registry.register("typescript", tree_sitter_typescript::language_typescript(), TYPESCRIPT_QUERY, &["ts"]);
+        registry.register("tsx", tree_sitter_typescript::language_tsx(), TYPESCRIPT_QUERY, &["tsx"]);
+        registry.register("rust", tree_sitter_rust::language(), RUST_QUERY, &["rs"]);
+        registry.register("python", tree_sitter_python::language(), PYTHON_QUERY, &["py"]);
+        registry.register("ruby", tree_sitter_ruby::language(), RUBY_QUERY, &["rb"]);
+        registry.register("php", tree_sitter_php::language_php(), PHP_QUERY, &["php"]);
+        registry.register("bash", tree_sitter_bash::language(), BASH_QUERY, &["sh", "bash"]);
+        registry
+    }
+
+    /// Built-ins, then each dir in order. Later dirs win, so a repo-local dir
+    /// should come after the user config dir.
+    pub fn load(dirs: &[LanguageDir]) -> Self {
+        let mut registry = Self::builtin();
+        for dir in dirs.iter().filter(|d| d.path.is_dir()) {
+            if dir.trusted {
+                registry.load_dir(&dir.path);
+            } else {
+                registry.apply_query_overrides(&dir.path);
+            }
+        }
+        registry
+    }
+
+    pub fn get(&self, extension: &str) -> Option<&LanguageSpec> {
+        self.by_ext.get(&extension.to_ascii_lowercase()).map(|s| s.as_ref())
+    }
+
+    /// Names of all registered languages, sorted and deduplicated.
+    pub fn language_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.by_ext.values().map(|s| s.name.as_str()).collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+
+    fn register(&mut self, name: &str, language: Language, query: &str, extensions: &[&str]) {
+        let spec = Arc::new(LanguageSpec { name: name.to_string(), language, skeleton_query: query.to_string() });
+        for ext in extensions {
+            self.by_ext.insert(ext.to_ascii_lowercase(), Arc::clone(&spec));
+        }
+    }
+
+    fn load_dir(&mut self, dir: &Path) {
+        let entries_path = dir.join(LANGUAGES_FILE);
+        if let Ok(raw) = fs::read_to_string(&entries_path) {
+            match serde_json::from_str::<Vec<LanguageEntry>>(&raw) {
+                Ok(entries) => {
+                    for entry in entries {
+                        self.load_entry(dir, &entry);
+                    }
+                }
+                Err(e) => eprintln!("WARN: ignoring {}: {}", entries_path.display(), e),
+            }
+        }
+        self.apply_query_overrides(dir);
+    }
+
+    fn load_entry(&mut self, dir: &Path, entry: &LanguageEntry) {
+        let Some(library) = &entry.library else {
+            // Extension remap onto a language we already know.
+            let Some(spec) = self.find_by_name(&entry.name) else {
+                eprintln!("WARN: {} maps extensions to unknown language '{}'", LANGUAGES_FILE, entry.name);
+                return;
+            };
+            for ext in &entry.extensions {
+                self.by_ext.insert(ext.to_ascii_lowercase(), Arc::clone(&spec));
+            }
+            return;
+        };
+
+        let query_path = dir.join("queries").join(&entry.name).join(SKELETON_QUERY_FILE);
+        let query = match fs::read_to_string(&query_path) {
+            Ok(q) => q,
+            Err(_) => {
+                eprintln!("WARN: grammar '{}' has no {}; skipping", entry.name, query_path.display());
+                return;
+            }
+        };
+        let symbol = entry.symbol.clone().unwrap_or_else(|| format!("tree_sitter_{}", entry.name.replace('-', "_")));
+        match load_grammar(&dir.join(library), &symbol) {
+            Ok((lib, language)) => {
+                if !query_compiles(&language, &query, &query_path) {
+                    return;
+                }
+                self._libraries.push(lib);
+                let exts: Vec<&str> = entry.extensions.iter().map(String::as_str).collect();
+                self.register(&entry.name, language, &query, &exts);
+            }
+            Err(e) => eprintln!("WARN: could not load grammar '{}': {}", entry.name, e),
+        }
+    }
+
+    /// Replace the query of every registered language that has a `skeleton.scm` in `dir`.
+    /// TSX shares the TypeScript query, so a `typescript` override covers it unless `tsx` has its own.
+    fn apply_query_overrides(&mut self, dir: &Path) {
+        let names: Vec<String> = self.language_names().into_iter().map(str::to_string).collect();
+        for name in names {
+            let mut query_path = dir.join("queries").join(&name).join(SKELETON_QUERY_FILE);
+            if name == "tsx" && !query_path.is_file() {
+                query_path = dir.join("queries").join("typescript").join(SKELETON_QUERY_FILE);
+            }
+            let Ok(query) = fs::read_to_string(&query_path) else { continue };
+            let Some(old) = self.find_by_name(&name) else { continue };
+            if !query_compiles(&old.language, &query, &query_path) {
+                continue;
+            }
+            let spec = Arc::new(LanguageSpec { name: old.name.clone(), language: old.language.clone(), skeleton_query: query });
+            for slot in self.by_ext.values_mut().filter(|s| Arc::ptr_eq(s, &old)) {
+                *slot = Arc::clone(&spec);
+            }
+        }
+    }
+
+    fn find_by_name(&self, name: &str) -> Option<Arc<LanguageSpec>> {
+        self.by_ext.values().find(|s| s.name == name).cloned()
+    }
+}
+
+impl Default for LanguageRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/saccade` (or `~/.config/saccade`, `%APPDATA%\saccade`), then `./.saccade`
+/// for query overrides only.
+pub fn default_language_dirs() -> Vec<LanguageDir> {
+    let mut dirs = Vec::new();
+    let user_config = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")));
+    if let Some(base) = user_config {
+        dirs.push(LanguageDir::trusted(base.join("saccade")));
+    }
+    dirs.push(LanguageDir::queries_only(".saccade"));
+    dirs
+}
+
+/// Compile once up front so a broken `.scm` warns once instead of once per file.
+fn query_compiles(language: &Language, query: &str, path: &Path) -> bool {
+    match Query::new(language, query) {
+        Ok(_) => true,
+        Err(e) => {
+            eprintln!("WARN: {} does not compile (row {} col {}): {}; ignoring", path.display(), e.row, e.column, e.message);
+            false
+        }
+    }
+}
+
+/// Open a tree-sitter grammar shared library and fetch its language function.
+fn load_grammar(path: &Path, symbol: &str) -> std::result::Result<(Library, Language), String> {
+    // SAFETY: dlopen runs the library's constructors, which is why only trusted dirs get
+    // here. The symbol is the standard `const TSLanguage *tree_sitter_<name>(void)` entry point.
+    unsafe {
+        let lib = Library::new(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let language = {
+            let func: Symbol<unsafe extern "C" fn() -> *const tree_sitter::ffi::TSLanguage> =
+                lib.get(symbol.as_bytes()).map_err(|e| format!("{}: {}", symbol, e))?;
+            Language::from_raw(func())
+        };
+        Ok((lib, language))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn builtin_maps_known_extensions() {
+        let registry = LanguageRegistry::builtin();
+        assert_eq!(registry.get("rs").unwrap().name, "rust");
+        assert_eq!(registry.get("TSX").unwrap().name, "tsx");
+        assert!(registry.get("unknown").is_none());
+    }
+
+    #[test]
+    fn query_files_override_builtins_and_entries_remap_extensions() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("queries/ruby")).unwrap();
+        fs::write(tmp.path().join("queries/ruby/skeleton.scm"), "(comment) @capture").unwrap();
+        fs::write(tmp.path().join(LANGUAGES_FILE), r#"[{ "name": "ruby", "extensions": ["rake"] }]"#).unwrap();
+
+        let registry = LanguageRegistry::load(&[LanguageDir::trusted(tmp.path())]);
+        assert_eq!(registry.get("rake").unwrap().name, "ruby");
+        assert_eq!(registry.get("rb").unwrap().skeleton_query, "(comment) @capture");
+        assert_eq!(registry.get("rake").unwrap().skeleton_query, "(comment) @capture");
+
+        // A repo-local dir contributes queries but never languages.json.
+        fs::create_dir_all(tmp.path().join("queries/typescript")).unwrap();
+        fs::write(tmp.path().join("queries/typescript/skeleton.scm"), "(comment) @capture").unwrap();
+        let registry = LanguageRegistry::load(&[LanguageDir::queries_only(tmp.path())]);
+        assert!(registry.get("rake").is_none());
+        assert_eq!(registry.get("rb").unwrap().skeleton_query, "(comment) @capture");
+        assert_eq!(registry.get("tsx").unwrap().skeleton_query, "(comment) @capture");
+    }
+}
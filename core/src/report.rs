@@ -0,0 +1,168 @@
+// saccade/core/src/report.rs
+//
+// Machine-readable companion to PACK.txt, for tools that should not have to
+// scrape the text sections.
+
+use crate::error::{Result, SaccadeError};
+use crate::detection::DetectionReport;
+use crate::exclusions::{ExclusionReason, ExclusionReport};
+use crate::graph::FileRanks;
+use crate::heuristics::HeuristicCandidate;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PACK_JSON_FILE_NAME: &str = "PACK.json";
+
+/// Bump on any breaking change to the PACK.json layout.
+pub const PACK_JSON_VERSION: &str = "1";
+
+#[derive(Debug, Serialize)]
+pub struct PackReport {
+    pub version: &'static str,
+    /// Kept files, most important first.
+    pub files: Vec<FileEntry>,
+    /// Non-standard manifests found by the heuristic funnel, highest confidence first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub manifest_candidates: Vec<ManifestCandidateEntry>,
+    /// Detected build systems with the files that confirmed them.
+    pub detection: Vec<DetectionEntry>,
+    /// Files named like a build system's manifest that failed validation.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub detection_rejected: Vec<RejectionEntry>,
+    /// Dropped paths grouped by reason (also written to EXCLUDED.txt).
+    pub excluded: Vec<ExclusionGroupEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExclusionGroupEntry {
+    pub reason: ExclusionReason,
+    pub description: &'static str,
+    pub paths: Vec<ExcludedPathEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExcludedPathEntry {
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DetectionEntry {
+    pub system: String,
+    pub confidence: f32,
+    pub evidence: Vec<EvidenceEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EvidenceEntry {
+    pub path: String,
+    pub signal: String,
+    pub line: Option<usize>,
+    pub confidence: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RejectionEntry {
+    pub path: String,
+    pub system: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ManifestCandidateEntry {
+    pub path: String,
+    pub confidence: f32,
+    pub mds_score: f32,
+    pub context_score: f32,
+    pub structure: Option<&'static str>,
+    pub keywords: Vec<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub bytes: u64,
+    /// Import-graph PageRank; sums to 1 across `files`.
+    pub rank: f64,
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+impl PackReport {
+    pub fn new(files: &[PathBuf], ranks: &FileRanks) -> Self {
+        let mut entries: Vec<FileEntry> = files
+            .iter()
+            .map(|path| {
+                let rank = ranks.get(path);
+                FileEntry {
+                    path: path.to_string_lossy().replace('\\', "/"),
+                    bytes: fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+                    rank: rank.map_or(0.0, |r| r.score),
+                    fan_in: rank.map_or(0, |r| r.fan_in),
+                    fan_out: rank.map_or(0, |r| r.fan_out),
+                }
+            })
+            .collect();
+        entries.sort_by(|a, b| b.rank.total_cmp(&a.rank).then_with(|| a.path.cmp(&b.path)));
+        Self { version: PACK_JSON_VERSION, files: entries, manifest_candidates: Vec::new(), detection: Vec::new(), detection_rejected: Vec::new(), excluded: Vec::new() }
+    }
+
+    pub fn with_manifest_candidates(mut self, candidates: &[HeuristicCandidate]) -> Self {
+        self.manifest_candidates = candidates
+            .iter()
+            .map(|c| ManifestCandidateEntry {
+                path: c.path.to_string_lossy().replace('\\', "/"),
+                confidence: c.confidence,
+                mds_score: c.mds_score,
+                context_score: c.context_score,
+                structure: c.structure,
+                keywords: c.keywords.clone(),
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_detection(mut self, report: &DetectionReport) -> Self {
+        let path = |p: &Path| p.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string();
+        self.detection = report
+            .systems
+            .iter()
+            .map(|s| DetectionEntry {
+                system: s.system.to_string(),
+                confidence: s.confidence,
+                evidence: s
+                    .evidence
+                    .iter()
+                    .map(|e| EvidenceEntry { path: path(&e.path), signal: e.signal.clone(), line: e.line, confidence: e.confidence })
+                    .collect(),
+            })
+            .collect();
+        self.detection_rejected = report
+            .rejected
+            .iter()
+            .map(|r| RejectionEntry { path: path(&r.path), system: r.system.to_string(), reason: r.reason.clone() })
+            .collect();
+        self
+    }
+
+    pub fn with_exclusions(mut self, report: &ExclusionReport) -> Self {
+        self.excluded = report
+            .by_reason()
+            .into_iter()
+            .map(|(reason, group)| ExclusionGroupEntry {
+                reason,
+                description: reason.describe(),
+                paths: group.iter().map(|e| ExcludedPathEntry { path: e.display_path(), detail: e.detail.clone() }).collect(),
+            })
+            .collect();
+        self
+    }
+
+    pub fn write(&self, pack_dir: &Path) -> Result<()> {
+        let path = pack_dir.join(PACK_JSON_FILE_NAME);
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(&path, json).map_err(|e| SaccadeError::Io { source: e, path })
+    }
+}
@@ -0,0 +1,721 @@
+// saccade/core/src/runbook.rs
+//
+// RUNBOOK: how to build, run and test this repo, compiled from its own files.
+//   Cargo     workspace, bins, examples, tests, benches, features (Cargo.toml)
+//   Node      package.json scripts, run with the package manager its lockfile implies
+//   Make      Makefile targets (first one is the default)
+//   just      justfile recipes
+//   CMake     configure/build, executable and library targets, ctest
+//   Python    [project.scripts] / Poetry scripts, pytest, tox envs, nox sessions
+//   CI        GitHub Actions `run:` steps, GitLab CI `script:` lines
+// Manifests come from the detector's evidence, so only confirmed build systems contribute.
+
+use crate::cmake::CMakeProject;
+use crate::detection::{BuildSystemType, DetectionReport};
+use crate::manifest_text::{split_inline_array, toml_entries, toml_strings, toml_value, unquote, TomlEntry};
+use crate::workspace::expand_members;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_COMMANDS_PER_GROUP: usize = 25;
+const MAX_COMMAND_WIDTH: usize = 120;
+
+static MAKE_TARGET_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^([A-Za-z0-9][\w.-]*)\s*:(?:[^=]|$)(.*)$").unwrap());
+static JUST_RECIPE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^@?([A-Za-z_][\w-]*)([^:]*):(?:[^=]|$)").unwrap());
+static NOX_SESSION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"@nox\.session(?:\([^)]*\))?\s*\n\s*def\s+(\w+)").unwrap());
+static CMAKE_TEST_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?mi)^\s*(enable_testing|add_test|gtest_discover_tests)\s*\(").unwrap());
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCommand {
+    /// Exact command line, run from the repo root.
+    pub command: String,
+    pub purpose: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct RunbookGroup {
+    pub title: String,
+    /// File the commands were read from.
+    pub source: String,
+    pub commands: Vec<RunCommand>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Runbook {
+    pub groups: Vec<RunbookGroup>,
+}
+
+impl Runbook {
+    /// Commands for the build systems in `detection`, plus justfiles and CI workflows in `files`.
+    /// Paths are shown relative to `root`.
+    pub fn compile(root: &Path, files: &[PathBuf], detection: &DetectionReport) -> Self {
+        let manifests = |system: BuildSystemType| -> Vec<PathBuf> {
+            let mut paths: Vec<PathBuf> = detection
+                .systems
+                .iter()
+                .filter(|s| s.system == system)
+                .flat_map(|s| s.evidence.iter().map(|e| e.path.clone()))
+                .collect();
+            paths.sort();
+            paths.dedup();
+            paths
+        };
+        let named = |names: &[&str]| -> Vec<&PathBuf> {
+            files.iter().filter(|f| f.file_name().and_then(|n| n.to_str()).is_some_and(|n| names.contains(&n))).collect()
+        };
+
+        let mut groups = Vec::new();
+        groups.extend(cargo_groups(root, &manifests(BuildSystemType::Rust)));
+        for path in manifests(BuildSystemType::Node) {
+            groups.extend(node_group(root, &path));
+        }
+        let mut python_dirs: BTreeSet<PathBuf> = BTreeSet::new();
+        for path in manifests(BuildSystemType::Python) {
+            python_dirs.insert(path.parent().map(Path::to_path_buf).unwrap_or_default());
+        }
+        for dir in &python_dirs {
+            groups.extend(python_group(root, dir));
+        }
+        for path in manifests(BuildSystemType::Make).iter().filter(|p| !p.to_string_lossy().ends_with(".mk")) {
+            groups.extend(make_group(root, path));
+        }
+        for path in named(&["justfile", "Justfile", ".justfile"]) {
+            groups.extend(just_group(root, path));
+        }
+        if detection.systems.iter().any(|s| s.system == BuildSystemType::CMake) {
+            groups.extend(cmake_group(root, files));
+        }
+        for path in files {
+            let rel = display(root, path);
+            if rel.starts_with(".github/workflows/") && (rel.ends_with(".yml") || rel.ends_with(".yaml")) {
+                groups.extend(github_workflow_group(root, path));
+            } else if rel == ".gitlab-ci.yml" {
+                groups.extend(gitlab_ci_group(root, path));
+            }
+        }
+        Self { groups }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("RUNBOOK (build, run and test commands, from the repo's own manifests; run from the repo root)\n");
+        for group in &self.groups {
+            out.push_str(&format!("\n{} — {}\n", group.title, group.source));
+            let width = group.commands.iter().map(|c| c.command.chars().count()).max().unwrap_or(0).min(48);
+            for c in group.commands.iter().take(MAX_COMMANDS_PER_GROUP) {
+                if c.purpose.is_empty() {
+                    out.push_str(&format!("  {}\n", c.command));
+                } else {
+                    out.push_str(&format!("  {:<width$}  # {}\n", c.command, c.purpose, width = width));
+                }
+            }
+            if group.commands.len() > MAX_COMMANDS_PER_GROUP {
+                out.push_str(&format!("  … {} more\n", group.commands.len() - MAX_COMMANDS_PER_GROUP));
+            }
+        }
+        out
+    }
+}
+
+fn command(cmd: impl Into<String>, purpose: impl Into<String>) -> RunCommand {
+    let mut command: String = cmd.into();
+    if command.chars().count() > MAX_COMMAND_WIDTH {
+        command = command.chars().take(MAX_COMMAND_WIDTH).collect::<String>() + " …";
+    }
+    RunCommand { command, purpose: purpose.into() }
+}
+
+/// `cmd` run in `dir` (relative to the root); the root itself needs no `cd`.
+fn in_dir(dir: &str, cmd: &str) -> String {
+    if dir.is_empty() || dir == "." {
+        cmd.to_string()
+    } else {
+        format!("cd {} && {}", dir, cmd)
+    }
+}
+
+fn display(root: &Path, path: &Path) -> String {
+    let rel = path.strip_prefix(root).unwrap_or(path);
+    rel.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string()
+}
+
+fn parent_dir(root: &Path, path: &Path) -> String {
+    path.parent().map(|p| display(root, p)).unwrap_or_default()
+}
+
+fn group(title: &str, source: String, commands: Vec<RunCommand>) -> Option<RunbookGroup> {
+    (!commands.is_empty()).then(|| RunbookGroup { title: title.to_string(), source, commands })
+}
+
+/// `*.rs` file stems (and `dir/main.rs` crates) directly under `dir`.
+fn rust_targets_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            if path.is_dir() {
+                return path.join("main.rs").is_file().then(|| e.file_name().to_string_lossy().to_string());
+            }
+            let stem = path.file_stem()?.to_string_lossy().to_string();
+            path.extension().is_some_and(|x| x == "rs").then_some(stem)
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+// --- Cargo -----------------------------------------------------------------
+
+fn cargo_groups(root: &Path, manifests: &[PathBuf]) -> Vec<RunbookGroup> {
+    let parsed: Vec<(PathBuf, Vec<TomlEntry>)> = manifests
+        .iter()
+        .filter_map(|p| Some((p.clone(), toml_entries(&fs::read_to_string(p).ok()?))))
+        .collect();
+    // Members are driven from their workspace root with `-p`; other crates from their own dir.
+    let workspaces: Vec<(PathBuf, Vec<PathBuf>)> = parsed
+        .iter()
+        .filter(|(_, entries)| entries.iter().any(|(t, _, _)| t == "workspace" || t.starts_with("workspace.")))
+        .map(|(p, entries)| {
+            let dir = p.parent().map(Path::to_path_buf).unwrap_or_default();
+            let members = cargo_member_dirs(&dir, entries);
+            (dir, members)
+        })
+        .collect();
+
+    let mut groups = Vec::new();
+    for (path, entries) in &parsed {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let workspace = workspaces.iter().find(|(w, members)| *w == dir || members.contains(&dir)).map(|(w, _)| w);
+        let is_workspace_root = workspaces.iter().any(|(w, _)| *w == dir);
+        let mut commands = Vec::new();
+        if is_workspace_root {
+            let at = display(root, &dir);
+            commands.push(command(in_dir(&at, "cargo build --workspace"), "build every member"));
+            commands.push(command(in_dir(&at, "cargo test --workspace"), "run every member's tests"));
+        }
+        if let Some(name) = toml_value(entries, "package", "name").map(unquote) {
+            let (at, package) = match workspace {
+                Some(w) => (display(root, w), format!(" -p {}", name)),
+                None => (display(root, &dir), String::new()),
+            };
+            let cargo = |sub: &str, rest: &str| in_dir(&at, &format!("cargo {}{}{}", sub, package, rest));
+            if !is_workspace_root {
+                commands.push(command(cargo("build", ""), format!("build {}", name)));
+                commands.push(command(cargo("test", ""), format!("test {}", name)));
+            }
+            let declared = |table: &str| -> Vec<String> {
+                entries.iter().filter(|(t, k, _)| t == table && k == "name").map(|(_, _, v)| unquote(v).to_string()).collect()
+            };
+            let mut bins = declared("bin");
+            let declared_main = entries.iter().any(|(t, k, v)| t == "bin" && k == "path" && unquote(v) == "src/main.rs");
+            if dir.join("src/main.rs").is_file() && !declared_main {
+                bins.push(name.to_string());
+            }
+            bins.extend(rust_targets_in(&dir.join("src/bin")));
+            let sets = [
+                ("run", "--bin", bins, "binary"),
+                ("run", "--example", [declared("example"), rust_targets_in(&dir.join("examples"))].concat(), "example"),
+                ("test", "--test", [declared("test"), rust_targets_in(&dir.join("tests"))].concat(), "integration test"),
+                ("bench", "--bench", [declared("bench"), rust_targets_in(&dir.join("benches"))].concat(), "benchmark"),
+            ];
+            for (sub, flag, mut names, purpose) in sets {
+                names.sort();
+                names.dedup();
+                for target in names {
+                    commands.push(command(cargo(sub, &format!(" {} {}", flag, target)), purpose));
+                }
+            }
+            let features: Vec<&str> =
+                entries.iter().filter(|(t, k, _)| t == "features" && k != "default").map(|(_, k, _)| k.as_str()).collect();
+            if let Some(first) = features.first() {
+                commands.push(command(cargo("build", &format!(" --features {}", first)), format!("features: {}", features.join(", "))));
+            }
+        }
+        groups.extend(group("Cargo", display(root, path), commands));
+    }
+    groups
+}
+
+/// `[workspace] members` globs, minus `exclude`, as directories next to the workspace manifest.
+fn cargo_member_dirs(dir: &Path, entries: &[TomlEntry]) -> Vec<PathBuf> {
+    let members = toml_strings(entries, "workspace", "members");
+    let exclude = toml_strings(entries, "workspace", "exclude");
+    expand_members(dir, &members, &exclude).into_iter().map(|m| dir.join(m)).collect()
+}
+
+// --- Node ------------------------------------------------------------------
+
+fn node_group(root: &Path, path: &Path) -> Option<RunbookGroup> {
+    let json: Value = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+    let scripts = json.get("scripts")?.as_object()?;
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let manager = package_manager(&json, dir, root);
+    let at = parent_dir(root, path);
+    let commands = scripts
+        .iter()
+        .map(|(name, body)| {
+            let run = match (manager, name.as_str()) {
+                ("npm", "test" | "start") => format!("npm {}", name),
+                ("npm", _) | ("pnpm", _) | ("bun", _) => format!("{} run {}", manager, name),
+                _ => format!("{} {}", manager, name),
+            };
+            command(in_dir(&at, &run), body.as_str().unwrap_or_default().chars().take(80).collect::<String>())
+        })
+        .collect();
+    group(&format!("Node ({})", manager), display(root, path), commands)
+}
+
+/// `packageManager`, else the lockfile in the package dir or an ancestor up to `root`.
+fn package_manager(json: &Value, dir: &Path, root: &Path) -> &'static str {
+    let declared = json.get("packageManager").and_then(Value::as_str).unwrap_or_default();
+    for manager in ["pnpm", "yarn", "bun", "npm"] {
+        if declared.starts_with(manager) {
+            return manager;
+        }
+    }
+    let mut current = Some(dir);
+    while let Some(d) = current {
+        for (lockfile, manager) in [("pnpm-lock.yaml", "pnpm"), ("yarn.lock", "yarn"), ("bun.lockb", "bun"), ("bun.lock", "bun")] {
+            if d.join(lockfile).is_file() {
+                return manager;
+            }
+        }
+        if d == root || d.as_os_str().is_empty() {
+            break;
+        }
+        current = d.parent();
+    }
+    "npm"
+}
+
+// --- Make / just -----------------------------------------------------------
+
+fn make_group(root: &Path, path: &Path) -> Option<RunbookGroup> {
+    let content = fs::read_to_string(path).ok()?;
+    let at = parent_dir(root, path);
+    let mut seen = BTreeSet::new();
+    let mut commands = Vec::new();
+    let mut previous_comment = String::new();
+    for line in content.lines() {
+        if let Some(comment) = line.strip_prefix('#') {
+            previous_comment = comment.trim_start_matches('#').trim().to_string();
+            continue;
+        }
+        if let Some(c) = MAKE_TARGET_RE.captures(line) {
+            let target = &c[1];
+            // `target: deps ## help text` is the common self-documenting convention.
+            let help = c[2].split_once("##").map(|(_, h)| h.trim().to_string());
+            if seen.insert(target.to_string()) {
+                let mut purpose = help.unwrap_or_else(|| previous_comment.clone());
+                if commands.is_empty() {
+                    purpose = if purpose.is_empty() { "default target".to_string() } else { format!("default target; {}", purpose) };
+                }
+                commands.push(command(in_dir(&at, &format!("make {}", target)), purpose));
+            }
+        }
+        previous_comment.clear();
+    }
+    group("Make", display(root, path), commands)
+}
+
+fn just_group(root: &Path, path: &Path) -> Option<RunbookGroup> {
+    let content = fs::read_to_string(path).ok()?;
+    let at = parent_dir(root, path);
+    let mut commands = Vec::new();
+    let mut previous_comment = String::new();
+    for line in content.lines() {
+        if let Some(comment) = line.strip_prefix('#') {
+            previous_comment = comment.trim().to_string();
+            continue;
+        }
+        let keyword = line.split_whitespace().next().unwrap_or_default();
+        if !line.starts_with(char::is_whitespace) && !matches!(keyword, "set" | "alias" | "export" | "import" | "mod") {
+            if let Some(c) = JUST_RECIPE_RE.captures(line) {
+                let params: Vec<String> = c[2]
+                    .split_whitespace()
+                    .filter(|p| !p.contains('='))
+                    .map(|p| format!("<{}>", p.trim_start_matches(['*', '+', '$'])))
+                    .collect();
+                let recipe = if params.is_empty() { c[1].to_string() } else { format!("{} {}", &c[1], params.join(" ")) };
+                commands.push(command(in_dir(&at, &format!("just {}", recipe)), previous_comment.clone()));
+            }
+        }
+        previous_comment.clear();
+    }
+    group("just", display(root, path), commands)
+}
+
+// --- CMake -----------------------------------------------------------------
+
+fn cmake_group(root: &Path, files: &[PathBuf]) -> Option<RunbookGroup> {
+    let cmake_files: Vec<(PathBuf, String)> = files
+        .iter()
+        .filter(|f| f.file_name().is_some_and(|n| n == "CMakeLists.txt") || f.to_string_lossy().ends_with(".cmake"))
+        .filter_map(|f| Some((f.clone(), fs::read_to_string(f).ok()?)))
+        .collect();
+    let top = cmake_files
+        .iter()
+        .filter(|(p, _)| p.file_name().is_some_and(|n| n == "CMakeLists.txt"))
+        .min_by_key(|(p, _)| p.components().count())?;
+    let at = parent_dir(root, &top.0);
+    let project = CMakeProject::parse(&cmake_files);
+
+    let mut commands = vec![
+        command(in_dir(&at, "cmake -S . -B build"), "configure"),
+        command(in_dir(&at, "cmake --build build"), "build all targets"),
+    ];
+    for target in project.targets.iter().filter(|t| {
+        !(t.kind.starts_with("alias") || t.kind.starts_with("imported") || t.kind.contains("INTERFACE"))
+    }) {
+        commands.push(command(in_dir(&at, &format!("cmake --build build --target {}", target.name)), target.kind.clone()));
+    }
+    if cmake_files.iter().any(|(_, content)| CMAKE_TEST_RE.is_match(content)) {
+        commands.push(command(in_dir(&at, "ctest --test-dir build --output-on-failure"), "run tests"));
+    }
+    group("CMake", display(root, &top.0), commands)
+}
+
+// --- Python ----------------------------------------------------------------
+
+fn python_group(root: &Path, dir: &Path) -> Option<RunbookGroup> {
+    let at = display(root, dir);
+    let pyproject = fs::read_to_string(dir.join("pyproject.toml")).ok();
+    let entries = pyproject.as_deref().map(toml_entries).unwrap_or_default();
+    let poetry = entries.iter().any(|(t, _, _)| t.starts_with("tool.poetry"));
+    let runner = if dir.join("uv.lock").is_file() {
+        "uv run "
+    } else if poetry || dir.join("poetry.lock").is_file() {
+        "poetry run "
+    } else {
+        ""
+    };
+
+    let mut commands = Vec::new();
+    let scripts: Vec<(&str, &str)> = entries
+        .iter()
+        .filter(|(t, _, _)| t == "project.scripts" || t == "tool.poetry.scripts")
+        .map(|(_, k, v)| (k.as_str(), unquote(v)))
+        .collect();
+    if !scripts.is_empty() {
+        if runner.is_empty() {
+            commands.push(command(in_dir(&at, "pip install -e ."), "install, with entry points"));
+        }
+        for (name, target) in scripts {
+            commands.push(command(in_dir(&at, &format!("{}{}", runner, name)), format!("entry point → {}", target)));
+        }
+    }
+    let has_pytest = entries.iter().any(|(t, _, _)| t == "tool.pytest.ini_options")
+        || ["pytest.ini", "conftest.py", "tests/conftest.py"].iter().any(|f| dir.join(f).is_file())
+        || fs::read_to_string(dir.join("setup.cfg")).is_ok_and(|c| c.contains("[tool:pytest]"));
+    if has_pytest {
+        commands.push(command(in_dir(&at, &format!("{}pytest", runner)), "run tests"));
+    }
+    if let Ok(tox) = fs::read_to_string(dir.join("tox.ini")) {
+        let mut envs: Vec<String> = Vec::new();
+        let mut section = "";
+        for line in tox.lines().map(str::trim) {
+            if line.starts_with('[') {
+                section = line.trim_matches(|c| c == '[' || c == ']');
+                if let Some(env) = section.strip_prefix("testenv:") {
+                    envs.push(env.to_string());
+                }
+            } else if section == "tox" {
+                if let Some(list) = line.strip_prefix("envlist").and_then(|l| l.trim_start().strip_prefix('=')) {
+                    envs.extend(split_inline_array(list).into_iter().filter(|e| !e.contains('{')).map(str::to_string));
+                }
+            }
+        }
+        commands.push(command(in_dir(&at, "tox"), "every env in envlist"));
+        let mut seen = BTreeSet::new();
+        for env in envs.into_iter().filter(|e| seen.insert(e.clone())) {
+            commands.push(command(in_dir(&at, &format!("tox -e {}", env)), "tox env"));
+        }
+    }
+    if let Ok(nox) = fs::read_to_string(dir.join("noxfile.py")) {
+        for c in NOX_SESSION_RE.captures_iter(&nox) {
+            commands.push(command(in_dir(&at, &format!("nox -s {}", &c[1])), "nox session"));
+        }
+    }
+    let source = if pyproject.is_some() { display(root, &dir.join("pyproject.toml")) } else { format!("{}/", at) };
+    group("Python", source, commands)
+}
+
+// --- CI --------------------------------------------------------------------
+
+/// `run:` steps of every job, labelled `job: step name`.
+fn github_workflow_group(root: &Path, path: &Path) -> Option<RunbookGroup> {
+    let content = fs::read_to_string(path).ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut commands = Vec::new();
+    let (mut in_jobs, mut job, mut step_name) = (false, String::new(), String::new());
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        i += 1;
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if indent == 0 {
+            in_jobs = trimmed == "jobs:";
+            continue;
+        }
+        if !in_jobs {
+            continue;
+        }
+        if indent == 2 && trimmed.ends_with(':') {
+            job = trimmed.trim_end_matches(':').to_string();
+            continue;
+        }
+        let item = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+        if trimmed.starts_with("- ") {
+            step_name.clear();
+        }
+        if let Some(name) = item.strip_prefix("name:") {
+            step_name = unquote(name.trim()).to_string();
+        } else if let Some(run) = item.strip_prefix("run:") {
+            let run = run.trim();
+            let script = if run.starts_with('|') || run.starts_with('>') {
+                // Block scalar: the lines indented deeper than `run:`.
+                let mut body = Vec::new();
+                while i < lines.len() {
+                    let next = lines[i];
+                    let next_indent = next.len() - next.trim_start().len();
+                    if !next.trim().is_empty() && next_indent <= indent {
+                        break;
+                    }
+                    if !next.trim().is_empty() && !next.trim().starts_with('#') {
+                        body.push(next.trim());
+                    }
+                    i += 1;
+                }
+                body.join(" && ")
+            } else {
+                unquote(run).to_string()
+            };
+            let purpose = if step_name.is_empty() { format!("job {}", job) } else { format!("job {}: {}", job, step_name) };
+            commands.push(command(script, purpose));
+        }
+    }
+    group("CI (GitHub Actions)", display(root, path), commands)
+}
+
+/// `script:` lines of every job.
+fn gitlab_ci_group(root: &Path, path: &Path) -> Option<RunbookGroup> {
+    const RESERVED: &[&str] = &["stages", "variables", "default", "include", "image", "services", "before_script", "after_script", "workflow", "cache"];
+    let content = fs::read_to_string(path).ok()?;
+    let mut commands = Vec::new();
+    let (mut job, mut in_script) = (String::new(), false);
+    for line in content.lines() {
+        let indent = line.len() - line.trim_start().len();
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if indent == 0 {
+            let key = trimmed.trim_end_matches(':');
+            job = if key.starts_with('.') || RESERVED.contains(&key) { String::new() } else { key.to_string() };
+            in_script = false;
+        } else if !job.is_empty() && trimmed.starts_with("script:") {
+            in_script = true;
+        } else if in_script && trimmed.starts_with("- ") {
+            commands.push(command(unquote(trimmed.trim_start_matches("- ").trim()), format!("job {}", job)));
+        } else if !trimmed.starts_with("- ") {
+            in_script = false;
+        }
+    }
+    group("CI (GitLab)", display(root, path), commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detection::Detector;
+
+    #[test]
+    fn compiles_cargo_node_make_and_ci_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        };
+        let files = vec![
+            write("Cargo.toml", "[workspace]\nmembers = [\"cli\"]\n"),
+            write("cli/Cargo.toml", "[package]\nname = \"demo\"\n\n[features]\ndefault = []\nfast = []\n"),
+            write("cli/src/main.rs", "fn main() {}\n"),
+            write("cli/examples/hello.rs", "fn main() {}\n"),
+            write("web/package.json", r#"{"scripts": {"build": "vite build", "test": "vitest"}}"#),
+            write("web/pnpm-lock.yaml", "lockfileVersion: '9.0'\n"),
+            write("Makefile", "# Build everything\nall: build\n\nlint: ## Run linters\n\tcargo clippy\n"),
+            write(".github/workflows/ci.yml", "on: push\njobs:\n  test:\n    steps:\n      - uses: actions/checkout@v4\n      - name: Test\n        run: |\n          cargo fmt --check\n          cargo test\n"),
+        ];
+        let detection = Detector::new().detect(&files).unwrap();
+        let runbook = Runbook::compile(root, &files, &detection);
+        let lines: Vec<(String, &str, &str)> = runbook
+            .groups
+            .iter()
+            .flat_map(|g| g.commands.iter().map(move |c| (g.source.clone(), c.command.as_str(), c.purpose.as_str())))
+            .collect();
+        let commands: Vec<&str> = lines.iter().map(|(_, c, _)| *c).collect();
+        assert_eq!(
+            commands,
+            [
+                "cargo build --workspace",
+                "cargo test --workspace",
+                "cargo build -p demo",
+                "cargo test -p demo",
+                "cargo run -p demo --bin demo",
+                "cargo run -p demo --example hello",
+                "cargo build -p demo --features fast",
+                "cd web && pnpm run build",
+                "cd web && pnpm run test",
+                "make all",
+                "make lint",
+                "cargo fmt --check && cargo test",
+            ]
+        );
+        assert_eq!(lines[9].2, "default target; Build everything");
+        assert_eq!(lines[10].2, "Run linters");
+        assert_eq!(lines[11].2, "job test: Test");
+        assert!(runbook.render().contains("\nMake — Makefile\n"));
+    }
+
+    fn commands(group: Option<RunbookGroup>) -> Vec<(String, String)> {
+        group.map(|g| g.commands.into_iter().map(|c| (c.command, c.purpose)).collect()).unwrap_or_default()
+    }
+
+    fn pairs<'a>(expected: &[(&'a str, &'a str)]) -> Vec<(String, String)> {
+        expected.iter().map(|(c, p)| (c.to_string(), p.to_string())).collect()
+    }
+
+    #[test]
+    fn workspace_members_follow_globs_and_exclude() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for member in ["crates/a", "crates/b", "crates/legacy", "tools/gen"] {
+            fs::create_dir_all(root.join(member)).unwrap();
+        }
+        let entries = toml_entries("[workspace]\nmembers = [\n  \"crates/*\", # libraries\n  \"tools/gen\",\n]\nexclude = [\"crates/legacy\"]\n");
+        let members = cargo_member_dirs(root, &entries);
+        assert_eq!(members, [root.join("crates/a"), root.join("crates/b"), root.join("tools/gen")]);
+    }
+
+    #[test]
+    fn just_recipes_with_parameters_and_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let path = root.join("justfile");
+        fs::write(
+            &path,
+            "set shell := [\"bash\", \"-c\"]\nalias b := build\nversion := \"1.0\"\n\n# Build the project\nbuild:\n    cargo build\n\n# Run a target\nrun target *args: build\n    cargo run -- {{target}} {{args}}\n\n@fmt:\n    cargo fmt\n\nrelease tag=\"v1\":\n    git tag {{tag}}\n",
+        )
+        .unwrap();
+        assert_eq!(
+            commands(just_group(root, &path)),
+            pairs(&[("just build", "Build the project"), ("just run <target> <args>", "Run a target"), ("just fmt", ""), ("just release", "")])
+        );
+        fs::write(&path, "set shell := [\"bash\", \"-c\"]\n").unwrap();
+        assert!(just_group(root, &path).is_none());
+    }
+
+    #[test]
+    fn cmake_targets_skip_aliases_and_interfaces_and_add_ctest() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("lib")).unwrap();
+        let top = root.join("CMakeLists.txt");
+        let lib = root.join("lib/CMakeLists.txt");
+        fs::write(&top, "cmake_minimum_required(VERSION 3.20)\nproject(demo CXX)\nadd_subdirectory(lib)\nadd_executable(app main.cpp)\nenable_testing()\nadd_test(NAME app_runs COMMAND app)\n").unwrap();
+        fs::write(&lib, "add_library(core STATIC core.cpp)\nadd_library(demo::core ALIAS core)\nadd_library(headers INTERFACE)\n").unwrap();
+        assert_eq!(
+            commands(cmake_group(root, &[lib.clone(), top.clone()])),
+            pairs(&[
+                ("cmake -S . -B build", "configure"),
+                ("cmake --build build", "build all targets"),
+                ("cmake --build build --target app", "executable"),
+                ("cmake --build build --target core", "library STATIC"),
+                ("ctest --test-dir build --output-on-failure", "run tests"),
+            ])
+        );
+        fs::write(&top, "project(demo CXX)\nadd_subdirectory(lib)\n").unwrap();
+        let group = cmake_group(root, &[top, lib]).unwrap();
+        assert!(group.commands.iter().all(|c| !c.command.starts_with("ctest")));
+        assert_eq!(group.source, "CMakeLists.txt");
+    }
+
+    #[test]
+    fn python_scripts_pytest_tox_and_nox() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        write("uv/pyproject.toml", "[project]\nname = \"tool\"\n\n[project.scripts]\ntool = \"tool.cli:main\"\n\n[tool.pytest.ini_options]\ntestpaths = [\"tests\"]\n");
+        write("uv/uv.lock", "version = 1\n");
+        write("uv/tox.ini", "[tox]\nenvlist = py311, lint, py{38,39}\n\n[testenv]\ncommands = pytest\n\n[testenv:docs]\ncommands = sphinx-build docs out\n");
+        write("uv/noxfile.py", "import nox\n\n@nox.session(python=[\"3.11\"])\ndef tests(session):\n    session.run(\"pytest\")\n\n@nox.session\ndef lint(session):\n    pass\n\ndef helper():\n    pass\n");
+        assert_eq!(
+            commands(python_group(root, &root.join("uv"))),
+            pairs(&[
+                ("cd uv && uv run tool", "entry point → tool.cli:main"),
+                ("cd uv && uv run pytest", "run tests"),
+                ("cd uv && tox", "every env in envlist"),
+                ("cd uv && tox -e py311", "tox env"),
+                ("cd uv && tox -e lint", "tox env"),
+                ("cd uv && tox -e docs", "tox env"),
+                ("cd uv && nox -s tests", "nox session"),
+                ("cd uv && nox -s lint", "nox session"),
+            ])
+        );
+
+        write("poetry/pyproject.toml", "[tool.poetry]\nname = \"app\"\n\n[tool.poetry.scripts]\napp = \"app:main\"\n");
+        write("poetry/tests/conftest.py", "");
+        assert_eq!(
+            commands(python_group(root, &root.join("poetry"))),
+            pairs(&[("cd poetry && poetry run app", "entry point → app:main"), ("cd poetry && poetry run pytest", "run tests")])
+        );
+
+        write("pip/pyproject.toml", "[project]\nname = \"lib\"\n\n[project.scripts]\nlib = \"lib:main\"\n");
+        write("pip/setup.cfg", "[tool:pytest]\naddopts = -q\n");
+        assert_eq!(
+            commands(python_group(root, &root.join("pip"))),
+            pairs(&[("cd pip && pip install -e .", "install, with entry points"), ("cd pip && lib", "entry point → lib:main"), ("cd pip && pytest", "run tests")])
+        );
+
+        write("bare/pyproject.toml", "[project]\nname = \"bare\"\n");
+        assert!(python_group(root, &root.join("bare")).is_none());
+    }
+
+    #[test]
+    fn gitlab_ci_scripts_per_job() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let path = root.join(".gitlab-ci.yml");
+        fs::write(
+            &path,
+            "stages: [build, test]\nvariables:\n  CARGO_HOME: .cargo\nbefore_script:\n  - rustup show\n.template:\n  script:\n    - echo hidden\nbuild:\n  stage: build\n  script:\n    - cargo build --release\n    - \"cargo doc --no-deps\"\n  artifacts:\n    paths:\n      - target/\ntest:\n  extends: .template\n  script:\n    # unit and integration\n    - cargo test\n",
+        )
+        .unwrap();
+        assert_eq!(
+            commands(gitlab_ci_group(root, &path)),
+            pairs(&[("cargo build --release", "job build"), ("cargo doc --no-deps", "job build"), ("cargo test", "job test")])
+        );
+        fs::write(&path, "stages: [build]\ninclude:\n  - local: ci/build.yml\n").unwrap();
+        assert!(gitlab_ci_group(root, &path).is_none());
+    }
+}
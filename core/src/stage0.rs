@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::error::Result;
+use crate::graph::FileRanks;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 
@@ -16,6 +17,7 @@ impl Stage0Generator {
         &self,
         files: &[std::path::PathBuf],
         detected_systems: &[crate::detection::BuildSystemType],
+        ranks: &FileRanks,
     ) -> Result<String> {
         let mut output = String::new();
 
@@ -24,16 +26,7 @@ impl Stage0Generator {
         for path in files {
             let file_name = path.file_name().and_then(|n| n.to_str());
             if let Some(name) = file_name {
-                let system_type = match name {
-                    "Cargo.toml" => Some(crate::detection::BuildSystemType::Rust),
-                    "package.json" => Some(crate::detection::BuildSystemType::Node),
-                    "go.mod" => Some(crate::detection::BuildSystemType::Go),
-                    "requirements.txt" | "pyproject.toml" | "Pipfile" => {
-                        Some(crate::detection::BuildSystemType::Python)
-                    }
-                    "CMakeLists.txt" => Some(crate::detection::BuildSystemType::CMake),
-                    _ => None,
-                };
+                let system_type = crate::detection::project_root_system(name);
 
                 if let (Some(st), Some(parent)) = (system_type, path.parent()) {
                     if detected_systems.contains(&st) {
@@ -43,7 +36,10 @@ impl Stage0Generator {
                         } else {
                             parent_path
                         };
-                        project_roots.insert(key, st);
+                        let systems: &mut Vec<crate::detection::BuildSystemType> = project_roots.entry(key).or_default();
+                        if !systems.contains(&st) {
+                            systems.push(st);
+                        }
                     }
                 }
             }
@@ -78,8 +74,9 @@ impl Stage0Generator {
             self.config.max_depth
         ));
         for dir in &dirs {
-            if let Some(system_type) = project_roots.get(dir.as_str()) {
-                output.push_str(&format!("{}  <-- [{} Project]\n", dir, system_type));
+            if let Some(systems) = project_roots.get(dir.as_str()) {
+                let names: Vec<String> = systems.iter().map(|s| s.to_string()).collect();
+                output.push_str(&format!("{}  <-- [{} Project]\n", dir, names.join(" + ")));
             } else {
                 output.push_str(dir);
                 output.push('\n');
@@ -104,7 +101,33 @@ impl Stage0Generator {
             output.push('\n');
         }
 
-        // Section 3: Token Heatmap
+        // Section 3: Importance Ranking (import-graph PageRank)
+        output.push_str("\n========================================\n");
+        output.push_str("IMPORTANCE RANKING\n");
+        output.push_str("========================================\n\n");
+
+        let linked: Vec<_> = ranks
+            .ranked()
+            .into_iter()
+            .filter(|r| r.fan_in + r.fan_out > 0)
+            .take(50)
+            .collect();
+        if linked.is_empty() {
+            output.push_str("No intra-repo imports resolved.\n");
+        } else {
+            output.push_str("Import-graph centrality (PageRank, 1.00 = average file). Top 50:\n\n");
+            for rank in linked {
+                output.push_str(&format!(
+                    "{:>8.2}  in {:>4}  out {:>4}  {}\n",
+                    ranks.relative(rank),
+                    rank.fan_in,
+                    rank.fan_out,
+                    rank.path.to_string_lossy().replace('\\', "/")
+                ));
+            }
+        }
+
+        // Section 4: Token Heatmap
         output.push_str("\n========================================\n");
         output.push_str("TOKEN HEATMAP\n");
         output.push_str("========================================\n\n");
@@ -142,7 +165,7 @@ impl Stage0Generator {
             let ext = if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
                 if matches!(
                     name,
-                    "Makefile" | "Dockerfile" | "dockerfile" | "CMakeLists.txt" | "BUILD" | "WORKSPACE"
+                    "Makefile" | "Dockerfile" | "dockerfile" | "CMakeLists.txt" | "BUILD" | "WORKSPACE" | "meson.build"
                 ) {
                     name.to_string()
                 } else if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
@@ -1,13 +1,18 @@
 // saccade/core/src/stage1.rs
 
+use crate::build_manifests::BuildManifest;
+use crate::cmake::CMakeProject;
+use crate::config::{PRUNE_DIRS, PRUNE_DIR_PREFIXES};
 use crate::detection::BuildSystemType;
 use crate::error::Result;
+use crate::heuristics::HeuristicCandidate;
+use crate::lockfiles::{LockFormat, Lockfile, ManifestDeps};
+use crate::tools::{ToolExecutor, ToolOutcome, ToolRun};
+use crate::workspace::Workspace;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::fs;
-use std::path::PathBuf;
-use std::process::{Command, Output};
-use tree_sitter::{Parser, Query};
+use std::path::{Path, PathBuf};
 
 /// === Dependency output budgets (visible, enforceable) =====================
 const DEPS_SECTION_MAX_LINES: usize = 300;
@@ -15,27 +20,46 @@ const DEPS_SECTION_MAX_BYTES: usize = 128 * 1024; // 128 KiB
 const DEPS_JOINER: &str = "\n\n----------------------------------------\n";
 const INCLUDE_CARGO_METADATA: bool = false; // OFF by default (too noisy)
 
-static EMAIL_RE: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap());
+/// Manifest-driven ecosystems: detected system → DEPS block title and the manifests it reads.
+const MANIFEST_DEPS: &[(BuildSystemType, &str, &[&str])] = &[
+    (BuildSystemType::Conan, "C++ (Conan)", &["conanfile.py", "conanfile.txt"]),
+    (BuildSystemType::Vcpkg, "C++ (vcpkg)", &["vcpkg.json"]),
+    (BuildSystemType::Bazel, "Bazel", &["MODULE.bazel", "WORKSPACE", "WORKSPACE.bazel"]),
+    (BuildSystemType::Meson, "Meson", &["meson.build", "*.wrap"]),
+    (BuildSystemType::Make, "Make", &["Makefile", "makefile", "GNUmakefile"]),
+    (BuildSystemType::Nix, "Nix (flakes)", &["flake.nix"]),
+    (BuildSystemType::Gradle, "JVM (Gradle)", &["build.gradle", "build.gradle.kts", "libs.versions.toml"]),
+    (BuildSystemType::Maven, "JVM (Maven)", &["pom.xml"]),
+    (BuildSystemType::SwiftPM, "Swift (SwiftPM)", &["Package.swift"]),
+    (BuildSystemType::Dart, "Dart (pub)", &["pubspec.yaml"]),
+    (BuildSystemType::Elixir, "Elixir (Mix)", &["mix.exs"]),
+    (BuildSystemType::Haskell, "Haskell (Cabal/Stack)", &["*.cabal", "stack.yaml"]),
+    (BuildSystemType::Zig, "Zig", &["build.zig.zon"]),
+];
+
 static REGISTRY_RE: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"index\.crates\.io-[^\s/\\]+[\\/]").unwrap());
 
-const CMAKE_DEPS_QUERY: &str = r#"
-(normal_command) @command
-"#;
-
-// Tree-sitter query to find `requires = "..."` assignments in Python.
-const PYTHON_CONAN_DEPS_QUERY: &str = r#"
-(assignment
-  left: (identifier) @name
-  right: (string) @value)
-"#;
-
-pub struct Stage1Generator;
+pub struct Stage1Generator {
+    tools: ToolExecutor,
+    manifest_candidates: Vec<HeuristicCandidate>,
+}
 
 impl Stage1Generator {
     pub fn new() -> Self {
-        Self
+        Self { tools: ToolExecutor::new(), manifest_candidates: Vec::new() }
+    }
+
+    /// Executor for `cargo tree`, `npm ls`, … (timeouts, output caps, `--offline`).
+    pub fn with_tools(mut self, tools: ToolExecutor) -> Self {
+        self.tools = tools;
+        self
+    }
+
+    /// Non-standard manifests from the heuristic funnel, listed at the end of DEPS.
+    pub fn with_manifest_candidates(mut self, candidates: Vec<HeuristicCandidate>) -> Self {
+        self.manifest_candidates = candidates;
+        self
     }
 
     // ---------------------------------------------------------------------
@@ -142,15 +166,21 @@ impl Stage1Generator {
     pub fn generate_all_deps(&self, detected_systems: &[BuildSystemType]) -> Result<String> {
         let mut sections: Vec<String> = Vec::new();
 
+        // Internal packages first: in a monorepo that is the map everything else hangs off.
+        let workspace = Workspace::discover(Path::new("."));
+        if !workspace.is_empty() {
+            sections.push(clamp_and_scrub(workspace.render().trim_end(), "workspace"));
+        }
+
         // --- DCA in action: Only run tools for detected systems ---
         if detected_systems.contains(&BuildSystemType::Rust) {
-            sections.push(self.deps_rust());
+            sections.push(self.deps_rust(&workspace));
         }
         if detected_systems.contains(&BuildSystemType::Node) {
-            sections.push(self.deps_node());
+            sections.push(self.deps_node(&workspace));
         }
         if detected_systems.contains(&BuildSystemType::Python) {
-            sections.push(self.deps_python());
+            sections.push(self.deps_python(&workspace));
         }
         if detected_systems.contains(&BuildSystemType::Go) {
             sections.push(self.deps_go());
@@ -158,8 +188,13 @@ impl Stage1Generator {
         if detected_systems.contains(&BuildSystemType::CMake) {
             sections.push(self.deps_cmake(detected_systems)?);
         }
-        if detected_systems.contains(&BuildSystemType::Conan) {
-            sections.push(self.deps_conan()?);
+        for (system, title, names) in MANIFEST_DEPS {
+            if detected_systems.contains(system) {
+                sections.push(self.manifest_blocks(title, names));
+            }
+        }
+        if !self.manifest_candidates.is_empty() {
+            sections.push(self.heuristic_manifests());
         }
         // --- End DCA section ---
 
@@ -170,262 +205,213 @@ impl Stage1Generator {
         out.push_str("========================================\n");
         out.push_str("DEPENDENCIES (multi-ecosystem, summarized)\n");
         out.push_str("========================================\n\n");
+        if self.tools.is_offline() {
+            out.push_str("Mode: offline (no external tools; lockfiles and manifests only)\n\n");
+        }
         out.push_str(&sections.join(DEPS_JOINER));
         out.push('\n');
+        let diagnostics = self.render_diagnostics();
+        if !diagnostics.is_empty() {
+            out.push_str(DEPS_JOINER);
+            out.push_str(&diagnostics);
+        }
         Ok(out)
     }
 
-    fn deps_rust(&self) -> String {
-        let mut parts: Vec<String> = vec!["RUST (cargo)".to_string(), "Tools: cargo tree".to_string()];
-        if let Some(s) = run_and_capture("cargo", &["tree", "-d"]) {
-            parts.push(format!("cargo tree -d (duplicates)\n{}\n", clamp_and_scrub(&s, "cargo tree -d")));
-        } else {
-            parts.push(warn_tool_missing("cargo tree -d"));
+    fn deps_rust(&self, workspace: &Workspace) -> String {
+        let mut parts: Vec<String> = vec!["RUST (cargo)".to_string()];
+        if let Some(locked) = lockfile_blocks(&[LockFormat::Cargo]) {
+            parts.push(locked);
+            return parts.join("\n");
+        }
+        let duplicates = self.tools.run("cargo", &["tree", "-d"]);
+        if !duplicates.success() {
+            let manifests = member_manifests(Path::new("."), workspace, "rust", &["Cargo.toml"]);
+            parts.push(manifest_blocks(&manifests, &fallback_reason(&duplicates)));
+            return parts.join("\n");
         }
-        if let Some(s) = run_and_capture("cargo", &["tree", "-e", "normal,build", "--depth", "2"]) {
-            parts.push(format!("cargo tree -e normal,build --depth 2\n{}\n", clamp_and_scrub(&s, "cargo tree -e normal,build --depth 2")));
+        parts.push(tool_block(&duplicates));
+        let tree = self.tools.run("cargo", &["tree", "-e", "normal,build", "--depth", "2"]);
+        if tree.success() {
+            parts.push(tool_block(&tree));
         }
         if INCLUDE_CARGO_METADATA {
-            if let Some(s) = run_and_capture("cargo", &["metadata", "--format-version", "1"]) {
-                parts.push(format!("cargo metadata --format-version 1 (truncated)\n{}\n", clamp_and_scrub(&s, "cargo metadata")));
+            let metadata = self.tools.run("cargo", &["metadata", "--format-version", "1"]);
+            if metadata.success() {
+                parts.push(tool_block(&metadata));
             }
         }
         parts.join("\n")
     }
 
-    fn deps_node(&self) -> String {
+    fn deps_node(&self, workspace: &Workspace) -> String {
         let mut parts: Vec<String> = vec!["NODE (npm/pnpm/yarn)".to_string()];
-        if tool_exists("npm") {
-            parts.push("Tool: npm".to_string());
-            if let Some(s) = run_collect_any_status("npm", &["ls", "--depth", "2"]) {
-                parts.push(format!("npm ls --depth 2\n{}\n", clamp_and_scrub(&s, "npm ls --depth 2")));
-            } else {
-                parts.push(warn_tool_failed("npm ls --depth 2"));
-            }
-            return parts.join("\n");
-        }
-        if tool_exists("pnpm") {
-            parts.push("Tool: pnpm".to_string());
-            if let Some(s) = run_collect_any_status("pnpm", &["list", "--depth", "2"]) {
-                parts.push(format!("pnpm list --depth 2\n{}\n", clamp_and_scrub(&s, "pnpm list --depth 2")));
-            } else {
-                parts.push(warn_tool_failed("pnpm list --depth 2"));
-            }
+        if let Some(locked) = lockfile_blocks(&[LockFormat::Npm, LockFormat::Pnpm, LockFormat::Yarn]) {
+            parts.push(locked);
             return parts.join("\n");
         }
-        if tool_exists("yarn") {
-            parts.push("Tool: yarn".to_string());
-            if let Some(s) = run_collect_any_status("yarn", &["list", "--depth=2"]) {
-                parts.push(format!("yarn list --depth=2\n{}\n", clamp_and_scrub(&s, "yarn list --depth=2")));
-            } else {
-                parts.push(warn_tool_failed("yarn list --depth=2"));
+        let candidates: [(&str, &[&str]); 3] =
+            [("npm", &["ls", "--depth", "2"]), ("pnpm", &["list", "--depth", "2"]), ("yarn", &["list", "--depth=2"])];
+        let reason = match candidates.iter().find(|(tool, _)| self.tools.exists(tool)) {
+            Some((tool, args)) => {
+                let run = self.tools.run(tool, args);
+                if run.completed() && !run.stdout.trim().is_empty() {
+                    parts.push(tool_block(&run));
+                    return parts.join("\n");
+                }
+                fallback_reason(&run)
             }
-            return parts.join("\n");
-        }
-        parts.push(warn_tool_missing("npm|pnpm|yarn"));
+            None if self.tools.is_offline() => "--offline".to_string(),
+            None => "npm|pnpm|yarn not found".to_string(),
+        };
+        parts.push(manifest_blocks(&member_manifests(Path::new("."), workspace, "node", &["package.json"]), &reason));
         parts.join("\n")
     }
 
-    fn deps_python(&self) -> String {
-        let mut parts: Vec<String> = vec!["PYTHON (pip/poetry)".to_string()];
-        if tool_exists("pipdeptree") {
-            if let Some(s) = run_collect_any_status("pipdeptree", &["--json-tree", "-w", "silence"]) {
-                parts.push(format!("pipdeptree --json-tree -w silence (truncated)\n{}\n", clamp_and_scrub(&s, "pipdeptree --json-tree")));
+    fn deps_python(&self, workspace: &Workspace) -> String {
+        let mut parts: Vec<String> = vec!["PYTHON (pip/poetry/uv)".to_string()];
+        if let Some(locked) = lockfile_blocks(&[LockFormat::Poetry, LockFormat::Uv]) {
+            parts.push(locked);
+            return parts.join("\n");
+        }
+        let mut reason = if self.tools.is_offline() { "--offline".to_string() } else { "pipdeptree not found".to_string() };
+        if self.tools.exists("pipdeptree") {
+            let run = self.tools.run("pipdeptree", &["--json-tree", "-w", "silence"]);
+            if run.completed() && !run.stdout.trim().is_empty() {
+                parts.push(tool_block(&run));
                 return parts.join("\n");
             }
+            reason = fallback_reason(&run);
         }
-        if let Ok(s) = fs::read_to_string("poetry.lock") {
-            parts.push("(poetry.lock present; head)".to_string());
-            parts.push(clamp_and_scrub(&s, "poetry.lock"));
+        let manifests =
+            member_manifests(Path::new("."), workspace, "python", &["pyproject.toml", "requirements.txt", "requirements-dev.txt"]);
+        if manifests.iter().any(|m| m.is_file()) {
+            parts.push(manifest_blocks(&manifests, &reason));
             return parts.join("\n");
         }
-        let mut appended = false;
-        for name in &["requirements.txt", "requirements-dev.txt"] {
-            if let Ok(s) = fs::read_to_string(name) {
-                parts.push(format!("({} present; head)", name));
-                parts.push(clamp_and_scrub(&s, name));
-                appended = true;
-            }
-        }
-        if appended { return parts.join("\n"); }
-        if tool_exists("pip") {
-            if let Some(s) = run_collect_any_status("pip", &["list"]) {
-                parts.push(format!("pip list\n{}\n", clamp_and_scrub(&s, "pip list")));
+        if self.tools.exists("pip") {
+            let run = self.tools.run("pip", &["list"]);
+            if run.completed() {
+                parts.push(tool_block(&run));
                 return parts.join("\n");
             }
         }
-        parts.push(warn_tool_missing("pipdeptree|poetry.lock|requirements*.txt|pip"));
+        parts.push(warn_tool_missing("poetry.lock|uv.lock|pipdeptree|pyproject.toml|requirements*.txt|pip"));
         parts.join("\n")
     }
 
     fn deps_go(&self) -> String {
         let mut parts: Vec<String> = vec!["GO (modules)".to_string()];
-        if tool_exists("go") {
-            if let Some(s) = run_collect_any_status("go", &["version"]) {
-                parts.push(scrub(s.trim()));
-            }
-            if let Some(s) = run_collect_any_status("go", &["mod", "graph"]) {
-                parts.push(format!("go mod graph\n{}\n", clamp_and_scrub(&s, "go mod graph")));
-            } else {
-                parts.push(warn_tool_failed("go mod graph"));
-            }
-        } else {
+        if let Some(locked) = lockfile_blocks(&[LockFormat::GoModules]) {
+            parts.push(locked);
+            return parts.join("\n");
+        }
+        if self.tools.is_offline() {
+            parts.push("(no go.mod; skipped `go mod graph` in --offline mode)".to_string());
+            return parts.join("\n");
+        }
+        let version = self.tools.run("go", &["version"]);
+        if !version.success() {
             parts.push(warn_tool_missing("go"));
+            return parts.join("\n");
+        }
+        parts.push(scrub(version.stdout.trim()));
+        let graph = self.tools.run("go", &["mod", "graph"]);
+        if graph.completed() && !graph.stdout.trim().is_empty() {
+            parts.push(tool_block(&graph));
+        } else {
+            parts.push(warn_tool_failed("go mod graph"));
         }
         parts.join("\n")
     }
 
-    /// Parse CMakeLists.txt for `find_package` dependencies.
+    /// Failed or noisy tool runs, with the tail of their stderr.
+    fn render_diagnostics(&self) -> String {
+        let diagnostics = self.tools.diagnostics();
+        if diagnostics.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("TOOL DIAGNOSTICS\n");
+        for d in diagnostics {
+            out.push_str(&format!("`{}`: {}\n", d.command, d.outcome));
+            for line in d.stderr.lines().filter(|l| !l.trim().is_empty()) {
+                out.push_str(&format!("  {}\n", scrub(line)));
+            }
+        }
+        out
+    }
+
+    /// Parse CMakeLists.txt / *.cmake into packages, fetched sources, subdirectories and a target graph.
     fn deps_cmake(&self, _detected_systems: &[BuildSystemType]) -> Result<String> {
         let mut parts: Vec<String> = vec!["C++ (CMake)".to_string()];
-        let mut found_any = false;
 
-        let cmake_files: Vec<_> = walkdir::WalkDir::new(".")
+        let cmake_files: Vec<(PathBuf, String)> = walkdir::WalkDir::new(".")
             .into_iter()
+            .filter_entry(|e| e.depth() == 0 || !(e.file_type().is_dir() && is_pruned_dir(e.path())))
             .filter_map(|e| e.ok())
             .filter(|e| {
                 let name = e.file_name().to_string_lossy();
                 name == "CMakeLists.txt" || name.ends_with(".cmake")
             })
+            .filter_map(|e| fs::read_to_string(e.path()).ok().map(|content| (e.path().to_path_buf(), content)))
             .collect();
 
-        for entry in cmake_files {
-            let path = entry.path();
-            if let Ok(content) = fs::read_to_string(path) {
-                if let Some(deps) = self.extract_cmake_deps(&content) {
-                    parts.push(format!(
-                        "Dependencies from: {}\n{}",
-                        path.display(),
-                        deps
-                    ));
-                    found_any = true;
-                }
-            }
-        }
-
-        if !found_any {
-            parts.push("(No `find_package` dependencies found in CMake files)".to_string());
+        let project = CMakeProject::parse(&cmake_files);
+        parts.push(format!("Source: {} CMake file(s) (parsed with Tree-sitter)", cmake_files.len()));
+        if project.is_empty() {
+            parts.push("(No project, targets or dependencies found in CMake files)".to_string());
+        } else {
+            parts.push(project.render());
         }
-
         Ok(parts.join("\n"))
     }
-    
-    /// Helper to extract dependencies from a single CMake file's content.
-    fn extract_cmake_deps(&self, content: &str) -> Option<String> {
-        let mut parser = Parser::new();
-        if parser.set_language(&tree_sitter_cmake::language()).is_err() {
-            return None;
-        }
-        let tree = parser.parse(content, None)?;
-        let query = Query::new(&tree_sitter_cmake::language(), CMAKE_DEPS_QUERY).ok()?;
-        
-        let mut cursor = tree_sitter::QueryCursor::new();
-        let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
-        
-        let mut packages = Vec::new();
-        for m in matches {
-            let node = m.captures[0].node;
-            
-            if let Some(name_node) = node.child(0) {
-                if name_node.kind() == "identifier" {
-                    if let Ok(name) = name_node.utf8_text(content.as_bytes()) {
-                        if name.to_lowercase() == "find_package" {
-                            // CORRECTED: The package name is at child index 2.
-                            if let Some(arg_node) = node.child(2) {
-                                 if let Ok(arg_text) = arg_node.utf8_text(content.as_bytes()) {
-                                    packages.push(format!("- {}", arg_text.trim()));
-                                 }
-                            }
-                        }
-                    }
-                }
-            }
-        }
 
-        if packages.is_empty() {
-            None
-        } else {
-            Some(packages.join("\n"))
-        }
+    /// Manifests recognized by content (docs/dynamic-dependancy-detection.md), with their scores.
+    fn heuristic_manifests(&self) -> String {
+        let mut parts: Vec<String> = vec!["Other manifests (detected by content)".to_string()];
+        parts.push("Source: heuristic funnel (confidence = manifest density × path context)".to_string());
+        for c in &self.manifest_candidates {
+            parts.push(format!(
+                "  {:.2}  {}  (MDS {:.2} × {:.2}; {}: `{}`)",
+                c.confidence,
+                c.path.to_string_lossy().replace('\\', "/").trim_start_matches("./"),
+                c.mds_score,
+                c.context_score,
+                c.structure.unwrap_or("structure"),
+                c.evidence.as_deref().unwrap_or_default()
+            ));
+            parts.push(format!("        keywords: {}", c.keywords.join(", ")));
+        }
+        clamp_and_scrub(&parts.join("\n"), "heuristic manifests")
     }
 
-    /// REFACTORED: Parse conanfile.py for `requires` dependencies using Tree-sitter.
-    fn deps_conan(&self) -> Result<String> {
-        let mut parts: Vec<String> = vec!["C++ (Conan)".to_string()];
-        let mut found_any = false;
-
-        let conan_files: Vec<_> = walkdir::WalkDir::new(".")
+    /// Declared dependencies from every manifest named in `names` (`*.ext` matches by extension).
+    fn manifest_blocks(&self, title: &str, names: &[&str]) -> String {
+        let mut parts: Vec<String> = vec![title.to_string()];
+        let matches = |file_name: &str| {
+            names.iter().any(|n| match n.strip_prefix('*') {
+                Some(ext) => file_name.ends_with(ext),
+                None => file_name == *n,
+            })
+        };
+        let mut manifests: Vec<BuildManifest> = walkdir::WalkDir::new(".")
             .into_iter()
+            .filter_entry(|e| !(e.file_type().is_dir() && is_pruned_dir(e.path())))
             .filter_map(|e| e.ok())
-            .filter(|e| e.file_name().to_string_lossy() == "conanfile.py")
+            .filter(|e| e.file_type().is_file() && matches(&e.file_name().to_string_lossy()))
+            .filter_map(|e| BuildManifest::read(e.path()))
             .collect();
-
-        for entry in conan_files {
-            let path = entry.path();
-            if let Ok(content) = fs::read_to_string(path) {
-                if let Some(deps) = self.extract_conan_deps(&content) {
-                    parts.push(format!(
-                        "Dependencies from: {}\n{}",
-                        path.display(),
-                        deps
-                    ));
-                    found_any = true;
-                }
-            }
-        }
-
-        if !found_any {
-            parts.push("(No `requires` dependencies found in Conan files)".to_string());
-        }
-
-        Ok(parts.join("\n"))
-    }
-
-    /// CORRECTED: Helper to extract `requires` from a conanfile.py's content using Tree-sitter.
-    fn extract_conan_deps(&self, content: &str) -> Option<String> {
-        let mut parser = Parser::new();
-        if parser.set_language(&tree_sitter_python::language()).is_err() {
-            return None;
-        }
-        let tree = parser.parse(content, None)?;
-        let query = Query::new(&tree_sitter_python::language(), PYTHON_CONAN_DEPS_QUERY).ok()?;
-        
-        let mut cursor = tree_sitter::QueryCursor::new();
-        let matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
-
-        let mut packages = Vec::new();
-        for m in matches {
-            // Robustly find captures by name, not index.
-            let mut potential_name = "";
-            let mut potential_value = "";
-    
-            for capture in m.captures {
-                let capture_name = &query.capture_names()[capture.index as usize];
-                if *capture_name == "name" {
-                    if let Ok(text) = capture.node.utf8_text(content.as_bytes()) {
-                        potential_name = text;
-                    }
-                } else if *capture_name == "value" {
-                    if let Ok(text) = capture.node.utf8_text(content.as_bytes()) {
-                        potential_value = text;
-                    }
-                }
-            }
-            
-            if potential_name == "requires" {
-                let cleaned_value = potential_value.trim_matches(|c| c == '\'' || c == '"');
-                packages.push(format!("- {}", cleaned_value));
-            }
+        manifests.sort_by(|a, b| a.path.cmp(&b.path));
+        for manifest in &manifests {
+            parts.push(format!("Source: manifest {}", manifest.path.to_string_lossy().trim_start_matches("./")));
+            parts.push(manifest.render().trim_end().to_string());
         }
-
-        if packages.is_empty() {
-            None
-        } else {
-            Some(packages.join("\n"))
+        if manifests.iter().all(|m| m.dependencies.is_empty()) {
+            parts.push(format!("(No dependencies found in {})", names.join(" / ")));
         }
+        parts.join("\n")
     }
 
-
     // ---------------------------------------------------------------------
     // API extraction helpers
     // ---------------------------------------------------------------------
@@ -521,20 +507,68 @@ impl Stage1Generator {
 // Helpers
 // -------------------------------------------------------------------------
 
-fn tool_exists(cmd: &str) -> bool {
-    Command::new(cmd).arg("--version").output().map(|o| o.status.success()).unwrap_or(false)
+/// Parsed lockfiles in the project root, one block each; `None` if none exist.
+/// Preferred over the ecosystem tools: no process, no network.
+fn lockfile_blocks(formats: &[LockFormat]) -> Option<String> {
+    let blocks: Vec<String> = formats
+        .iter()
+        .filter_map(|&format| Lockfile::read(Path::new("."), format))
+        .map(|lockfile| {
+            let label = lockfile.format.file_name();
+            format!("Source: lockfile {} (parsed in-process)\n{}", label, clamp_and_scrub(lockfile.render().trim_end(), label))
+        })
+        .collect();
+    (!blocks.is_empty()).then(|| blocks.join("\n"))
 }
 
-fn run_collect_any_status(cmd: &str, args: &[&str]) -> Option<String> {
-    Command::new(cmd).args(args).output().ok().and_then(collect_string)
+/// `names` in `root`, then in every `ecosystem` member of the workspace, so a virtual
+/// workspace's members are read even though the root manifest declares nothing.
+fn member_manifests(root: &Path, workspace: &Workspace, ecosystem: &str, names: &[&str]) -> Vec<PathBuf> {
+    let members = workspace.packages.iter().filter(|p| p.ecosystem == ecosystem && p.root != Path::new("."));
+    let dirs: Vec<PathBuf> = std::iter::once(root.to_path_buf()).chain(members.map(|p| root.join(&p.root))).collect();
+    dirs.iter().flat_map(|dir| names.iter().map(move |name| dir.join(name))).collect()
 }
 
-fn run_and_capture(cmd: &str, args: &[&str]) -> Option<String> {
-    Command::new(cmd).args(args).output().ok().filter(|o| o.status.success()).and_then(collect_string)
+/// Declared dependencies from whichever of `manifests` exist, used when there is no
+/// lockfile and the tools are off or failed (`reason` says which).
+fn manifest_blocks(manifests: &[PathBuf], reason: &str) -> String {
+    let blocks: Vec<String> = manifests
+        .iter()
+        .filter_map(|path| ManifestDeps::read(path))
+        .map(|manifest| {
+            let label = manifest.path.to_string_lossy().replace('\\', "/").trim_start_matches("./").to_string();
+            format!(
+                "Source: manifest {} (no lockfile; {})\n{}",
+                label,
+                reason,
+                clamp_and_scrub(manifest.render().trim_end(), &label)
+            )
+        })
+        .collect();
+    if blocks.is_empty() {
+        let mut names: Vec<String> = Vec::new();
+        for name in manifests.iter().filter_map(|m| m.file_name()).map(|n| n.to_string_lossy().into_owned()) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        return format!("(no lockfile and no declared dependencies in {}; {})", names.join("|"), reason);
+    }
+    blocks.join("\n")
 }
 
-fn collect_string(out: Output) -> Option<String> {
-    String::from_utf8(out.stdout).ok().map(|s| s.replace("\r\n", "\n"))
+fn tool_block(run: &ToolRun) -> String {
+    let output = run.stdout.trim_end();
+    let body = if output.is_empty() { "(no output)".to_string() } else { clamp_and_scrub(output, &run.command) };
+    format!("Source: tool {}\n{}\n", run.provenance(), body)
+}
+
+/// Why a tool's output was not used, for the fallback's `Source:` line.
+fn fallback_reason(run: &ToolRun) -> String {
+    match run.outcome {
+        ToolOutcome::Skipped => "--offline".to_string(),
+        _ => format!("`{}` {}", run.command, run.outcome),
+    }
 }
 
 fn warn_tool_missing(name: &str) -> String { format!("(tool not found or not installed: {})", name) }
@@ -555,7 +589,68 @@ fn clamp_block(s: &str, label: &str) -> String {
     } else { text }
 }
 
+/// Shortens registry cache paths. Emails and other PII are handled for the whole pack by `pii`.
 fn scrub(s: &str) -> String {
-    let out = EMAIL_RE.replace_all(s, "<email>").to_string();
-    REGISTRY_RE.replace_all(&out, "index.crates.io/…/").to_string()
-}
\ No newline at end of file
+    REGISTRY_RE.replace_all(s, "index.crates.io/…/").to_string()
+}
+
+/// Directories a manifest walk skips: `PRUNE_DIRS` and `PRUNE_DIR_PREFIXES`, except the
+/// names real sources use too. `tests`, `gen` and `schemas` can hold their own CMakeLists or
+/// package.json, so they are walked; `build`, `deps` and `_build` only count when they look
+/// like tool output.
+fn is_pruned_dir(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+    let sibling = |file: &str| path.parent().is_some_and(|p| p.join(file).is_file());
+    match name.as_ref() {
+        // Mix and rebar3 fetch and compile into these.
+        "deps" | "_build" => sibling("mix.exs") || sibling("rebar.config"),
+        "build" => {
+            ["CMakeCache.txt", "build.ninja", "CMakeFiles"].iter().any(|f| path.join(f).exists())
+                || ["pubspec.yaml", "build.gradle", "build.gradle.kts", "setup.py"].iter().any(|f| sibling(f))
+        }
+        "tests" | "test" | "__tests__" | "gen" | "schemas" => false,
+        name => PRUNE_DIRS.contains(&name) || PRUNE_DIR_PREFIXES.iter().any(|p| name.starts_with(p)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_and_deps_are_pruned_only_when_they_are_tool_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for sub in ["src/build", "src/deps", "cmake/build/CMakeFiles", "app/build", "ex/deps"] {
+            std::fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        std::fs::write(root.join("app/pubspec.yaml"), "name: app\n").unwrap();
+        std::fs::write(root.join("ex/mix.exs"), "defmodule Ex.MixProject do end\n").unwrap();
+        assert!(!is_pruned_dir(&root.join("src/build")));
+        assert!(!is_pruned_dir(&root.join("src/deps")));
+        assert!(is_pruned_dir(&root.join("cmake/build")));
+        assert!(is_pruned_dir(&root.join("app/build")));
+        assert!(is_pruned_dir(&root.join("ex/deps")));
+        assert!(is_pruned_dir(&root.join("node_modules")));
+        assert!(is_pruned_dir(&root.join("cmake-build-debug")));
+        assert!(is_pruned_dir(&root.join("vendor")));
+        assert!(!is_pruned_dir(&root.join("tests")));
+    }
+
+    #[test]
+    fn manifest_fallback_reads_every_workspace_member() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join("core")).unwrap();
+        std::fs::create_dir_all(root.join("cli")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"core\", \"cli\"]\n").unwrap();
+        std::fs::write(root.join("core/Cargo.toml"), "[package]\nname = \"app-core\"\n\n[dependencies]\nregex = \"1\"\nserde = \"1\"\n").unwrap();
+        std::fs::write(root.join("cli/Cargo.toml"), "[package]\nname = \"app\"\n\n[dependencies]\napp-core = { path = \"../core\" }\n").unwrap();
+
+        let manifests = member_manifests(root, &Workspace::discover(root), "rust", &["Cargo.toml"]);
+        let block = manifest_blocks(&manifests, "--offline");
+        assert!(block.contains("core/Cargo.toml: 2 declared dependencies"), "{}", block);
+        assert!(block.contains("  regex 1") && block.contains("  serde 1"));
+        assert!(block.contains("cli/Cargo.toml: 1 declared dependencies"));
+    }
+}
@@ -0,0 +1,198 @@
+// saccade/core/src/symbols.rs
+//
+// ctags-style index of every named definition Stage-2 parses, written to
+// SYMBOLS.txt in the pack dir:
+//
+//   !_TAG_FILE_FORMAT	saccade-symbols	1
+//   !_TAG_FILE_SORTED	1
+//   get_user	src/users.rs	12-14	fn	User	rust
+//
+// Columns: name, path, lines (1-based, inclusive), kind, container ("-" if none),
+// language. Rows are sorted by name, then path, then start line, so a name can
+// be found by binary search.
+
+use crate::error::{Result, SaccadeError};
+use crate::parser::{self, ItemRole, SkeletonItem, SkeletonLevel};
+use crate::registry::LanguageRegistry;
+use std::fs;
+use std::path::Path;
+
+pub const SYMBOLS_FILE_NAME: &str = "SYMBOLS.txt";
+
+const HEADER: &str = "!_TAG_FILE_FORMAT\tsaccade-symbols\t1\n!_TAG_FILE_SORTED\t1\n";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    /// Repo-relative, forward slashes.
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub kind: String,
+    /// Innermost enclosing type/impl/class/module.
+    pub container: Option<String>,
+    pub language: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolIndex {
+    pub fn new(mut symbols: Vec<Symbol>) -> Self {
+        symbols.sort_by(|a, b| (&a.name, &a.path, a.start_line).cmp(&(&b.name, &b.path, b.start_line)));
+        symbols.dedup();
+        Self { symbols }
+    }
+
+    /// Named definitions (defs, types, constants) from Stage-2 skeleton entries.
+    pub fn symbols_from_items(path: &Path, language: &str, items: &[SkeletonItem]) -> Vec<Symbol> {
+        let path = path.to_string_lossy().replace('\\', "/");
+        items
+            .iter()
+            .filter(|i| matches!(i.role, ItemRole::Def | ItemRole::Type | ItemRole::Const))
+            .filter_map(|i| {
+                Some(Symbol {
+                    name: i.name.clone()?,
+                    path: path.clone(),
+                    start_line: i.start_line,
+                    end_line: i.end_line,
+                    kind: i.kind.clone(),
+                    container: i.parent.clone(),
+                    language: language.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Index a single file on the fly (used when no SYMBOLS.txt is at hand).
+    pub fn from_source(path: &Path, content: &str, registry: &LanguageRegistry) -> Self {
+        let spec = path.extension().and_then(|e| e.to_str()).and_then(|ext| registry.get(ext));
+        let symbols = spec
+            .and_then(|spec| {
+                let items = parser::skeleton_items(spec, content, SkeletonLevel::Full)?;
+                Some(Self::symbols_from_items(path, &spec.name, &items))
+            })
+            .unwrap_or_default();
+        Self::new(symbols)
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// All definitions named `name`, via binary search.
+    pub fn lookup(&self, name: &str) -> &[Symbol] {
+        let start = self.symbols.partition_point(|s| s.name.as_str() < name);
+        let len = self.symbols[start..].partition_point(|s| s.name == name);
+        &self.symbols[start..start + len]
+    }
+
+    /// Definitions of `query` in `path`. `Type::method` / `Type.method` also match on container.
+    pub fn find_in(&self, path: &Path, query: &str) -> Vec<&Symbol> {
+        let path = path.to_string_lossy().replace('\\', "/");
+        let (container, name) = split_qualified(query);
+        self.lookup(name)
+            .iter()
+            .filter(|s| s.path == path)
+            .filter(|s| container.is_none() || s.container.as_deref() == container)
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from(HEADER);
+        for s in &self.symbols {
+            out.push_str(&format!(
+                "{}\t{}\t{}-{}\t{}\t{}\t{}\n",
+                s.name,
+                s.path,
+                s.start_line,
+                s.end_line,
+                s.kind,
+                s.container.as_deref().unwrap_or("-"),
+                s.language
+            ));
+        }
+        out
+    }
+
+    /// Parse a SYMBOLS.txt; malformed rows are skipped.
+    pub fn parse(text: &str) -> Self {
+        let symbols = text
+            .lines()
+            .filter(|l| !l.starts_with("!_"))
+            .filter_map(|line| {
+                let cols: Vec<&str> = line.split('\t').collect();
+                let [name, path, lines, kind, container, language] = cols.as_slice() else { return None };
+                let (start, end) = lines.split_once('-')?;
+                Some(Symbol {
+                    name: name.to_string(),
+                    path: path.to_string(),
+                    start_line: start.parse().ok()?,
+                    end_line: end.parse().ok()?,
+                    kind: kind.to_string(),
+                    container: (*container != "-").then(|| container.to_string()),
+                    language: language.to_string(),
+                })
+            })
+            .collect();
+        Self::new(symbols)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path).map_err(|e| SaccadeError::Io { source: e, path: path.to_path_buf() })?;
+        Ok(Self::parse(&text))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        fs::write(path, self.render()).map_err(|e| SaccadeError::Io { source: e, path: path.to_path_buf() })
+    }
+}
+
+/// `User::get_user` / `User.get_user` → (Some("User"), "get_user").
+fn split_qualified(query: &str) -> (Option<&str>, &str) {
+    let query = query.trim();
+    match query.rfind("::").map(|i| (i, 2)).or_else(|| query.rfind('.').map(|i| (i, 1))) {
+        Some((i, sep)) => (Some(&query[..i]), &query[i + sep..]),
+        None => (None, query),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "pub struct User { id: u64 }\n\nimpl User {\n    pub fn get_user(id: u64) -> Option<User> {\n        None\n    }\n}\n\nfn helper() {}\n";
+
+    #[test]
+    fn indexes_definitions_sorted_and_round_trips() {
+        let index = SymbolIndex::from_source(Path::new("src/users.rs"), SAMPLE, &LanguageRegistry::builtin());
+        let names: Vec<(&str, &str)> = index.symbols().iter().map(|s| (s.name.as_str(), s.kind.as_str())).collect();
+        assert_eq!(names, [("User", "struct"), ("User", "impl"), ("get_user", "fn"), ("helper", "fn")]);
+
+        let get = &index.lookup("get_user")[0];
+        assert_eq!((get.start_line, get.end_line, get.container.as_deref()), (4, 6, Some("User")));
+
+        let reparsed = SymbolIndex::parse(&index.render());
+        assert_eq!(reparsed.symbols(), index.symbols());
+        assert!(index.render().contains("get_user\tsrc/users.rs\t4-6\tfn\tUser\trust\n"));
+    }
+
+    #[test]
+    fn qualified_lookup_filters_by_container() {
+        let index = SymbolIndex::from_source(Path::new("src/users.rs"), SAMPLE, &LanguageRegistry::builtin());
+        assert_eq!(index.find_in(Path::new("src/users.rs"), "User::get_user").len(), 1);
+        assert!(index.find_in(Path::new("src/users.rs"), "Other::get_user").is_empty());
+        assert!(index.find_in(Path::new("src/other.rs"), "get_user").is_empty());
+        assert!(index.lookup("missing").is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+// saccade/core/src/tools.rs
+//
+// Sandboxed execution of ecosystem tools (`cargo tree`, `npm ls`, `go mod graph`, …)
+// for the DEPS section. Every run gets a wall-clock timeout and an output byte
+// cap, runs with stdin closed and network-disabling environment variables, and
+// leaves a diagnostic (outcome + stderr tail) instead of printing to the console.
+// In offline mode nothing is spawned at all.
+
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_TOOL_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
+const STDERR_MAX_BYTES: usize = 64 * 1024;
+const STDERR_TAIL_BYTES: usize = 2 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// After a kill, how long to wait for pipes held open by grandchildren.
+const DRAIN_GRACE: Duration = Duration::from_millis(500);
+
+/// Tell package managers not to reach for the network; unknown vars are harmless.
+const OFFLINE_ENV: &[(&str, &str)] = &[
+    ("CARGO_NET_OFFLINE", "true"),
+    ("npm_config_offline", "true"),
+    ("YARN_ENABLE_NETWORK", "0"),
+    ("PIP_NO_INDEX", "1"),
+    ("GOPROXY", "off"),
+    ("GOTOOLCHAIN", "local"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolOutcome {
+    /// Exit code; `None` if the process was ended by a signal.
+    Exited(Option<i32>),
+    TimedOut,
+    NotFound,
+    /// `--offline`: the tool was never started.
+    Skipped,
+    SpawnFailed(String),
+}
+
+impl std::fmt::Display for ToolOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolOutcome::Exited(Some(code)) => write!(f, "exit {}", code),
+            ToolOutcome::Exited(None) => write!(f, "killed by signal"),
+            ToolOutcome::TimedOut => write!(f, "timed out"),
+            ToolOutcome::NotFound => write!(f, "not found"),
+            ToolOutcome::Skipped => write!(f, "skipped (offline)"),
+            ToolOutcome::SpawnFailed(e) => write!(f, "failed to start: {}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ToolRun {
+    /// `program arg1 arg2`, for display.
+    pub command: String,
+    pub outcome: ToolOutcome,
+    pub stdout: String,
+    pub stderr: String,
+    /// Stdout hit the byte cap.
+    pub truncated: bool,
+    pub elapsed: Duration,
+}
+
+impl ToolRun {
+    pub fn success(&self) -> bool {
+        self.outcome == ToolOutcome::Exited(Some(0))
+    }
+
+    /// Started and exited on its own, whatever the status (`npm ls` exits 1 on peer warnings).
+    pub fn completed(&self) -> bool {
+        matches!(self.outcome, ToolOutcome::Exited(_))
+    }
+
+    /// `` `npm ls --depth 2` (exit 1, 0.84s, output capped) `` — how a DEPS block was obtained.
+    pub fn provenance(&self) -> String {
+        format!(
+            "`{}` ({}, {:.2}s{})",
+            self.command,
+            self.outcome,
+            self.elapsed.as_secs_f64(),
+            if self.truncated { ", output capped" } else { "" }
+        )
+    }
+}
+
+/// One per tool run worth mentioning: failures and runs that wrote to stderr.
+#[derive(Debug, Clone)]
+pub struct ToolDiagnostic {
+    pub command: String,
+    pub outcome: ToolOutcome,
+    /// Last `STDERR_TAIL_BYTES` of stderr, trimmed.
+    pub stderr: String,
+}
+
+pub struct ToolExecutor {
+    timeout: Duration,
+    max_output_bytes: usize,
+    offline: bool,
+    diagnostics: Mutex<Vec<ToolDiagnostic>>,
+}
+
+impl ToolExecutor {
+    pub fn new() -> Self {
+        Self {
+            timeout: DEFAULT_TOOL_TIMEOUT,
+            max_output_bytes: DEFAULT_TOOL_OUTPUT_MAX_BYTES,
+            offline: false,
+            diagnostics: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_max_output_bytes(mut self, bytes: usize) -> Self {
+        self.max_output_bytes = bytes;
+        self
+    }
+
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// `program --version` succeeds. Always `false` offline; never recorded as a diagnostic.
+    pub fn exists(&self, program: &str) -> bool {
+        !self.offline && self.execute(program, &["--version"]).success()
+    }
+
+    /// Run `program args…` under the timeout and byte cap, recording a diagnostic if noteworthy.
+    pub fn run(&self, program: &str, args: &[&str]) -> ToolRun {
+        let run = self.execute(program, args);
+        if run.outcome != ToolOutcome::Skipped && (!run.success() || !run.stderr.is_empty()) {
+            if let Ok(mut diagnostics) = self.diagnostics.lock() {
+                diagnostics.push(ToolDiagnostic {
+                    command: run.command.clone(),
+                    outcome: run.outcome.clone(),
+                    stderr: run.stderr.clone(),
+                });
+            }
+        }
+        run
+    }
+
+    pub fn diagnostics(&self) -> Vec<ToolDiagnostic> {
+        self.diagnostics.lock().map(|d| d.clone()).unwrap_or_default()
+    }
+
+    fn execute(&self, program: &str, args: &[&str]) -> ToolRun {
+        let command = std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" ");
+        let started = Instant::now();
+        let finish = |outcome, stdout: Capture, stderr: Capture| ToolRun {
+            command: command.clone(),
+            outcome,
+            stdout: String::from_utf8_lossy(&stdout.bytes).replace("\r\n", "\n"),
+            stderr: stderr_tail(&stderr.bytes),
+            truncated: stdout.truncated,
+            elapsed: started.elapsed(),
+        };
+        if self.offline {
+            return finish(ToolOutcome::Skipped, Capture::default(), Capture::default());
+        }
+
+        let mut cmd = Command::new(program);
+        cmd.args(args).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+        for (key, value) in OFFLINE_ENV {
+            cmd.env(key, value);
+        }
+        let mut child = match cmd.spawn() {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return finish(ToolOutcome::NotFound, Capture::default(), Capture::default())
+            }
+            Err(e) => return finish(ToolOutcome::SpawnFailed(e.to_string()), Capture::default(), Capture::default()),
+        };
+
+        let stdout = child.stdout.take().map(|pipe| spawn_reader(pipe, self.max_output_bytes));
+        let stderr = child.stderr.take().map(|pipe| spawn_reader(pipe, STDERR_MAX_BYTES));
+
+        let outcome = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break ToolOutcome::Exited(status.code()),
+                Ok(None) if started.elapsed() >= self.timeout => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    break ToolOutcome::TimedOut;
+                }
+                Ok(None) => thread::sleep(POLL_INTERVAL),
+                Err(e) => break ToolOutcome::SpawnFailed(e.to_string()),
+            }
+        };
+        let grace = if outcome == ToolOutcome::TimedOut { DRAIN_GRACE } else { self.timeout };
+        finish(outcome, collect(stdout, grace), collect(stderr, grace))
+    }
+}
+
+impl Default for ToolExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Default)]
+struct Capture {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+type Reader = (Arc<Mutex<Capture>>, Receiver<()>);
+
+/// Read `pipe` to EOF on a thread, keeping at most `cap` bytes. The rest is
+/// drained and dropped so a chatty child never blocks on a full pipe.
+fn spawn_reader(mut pipe: impl Read + Send + 'static, cap: usize) -> Reader {
+    let capture = Arc::new(Mutex::new(Capture::default()));
+    let (done, finished) = mpsc::channel();
+    let shared = Arc::clone(&capture);
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut buf) {
+            if n == 0 {
+                break;
+            }
+            let Ok(mut capture) = shared.lock() else { break };
+            let room = cap.saturating_sub(capture.bytes.len());
+            capture.bytes.extend_from_slice(&buf[..n.min(room)]);
+            capture.truncated |= n > room;
+        }
+        let _ = done.send(());
+    });
+    (capture, finished)
+}
+
+/// Whatever the reader has so far once it finishes or `grace` runs out.
+fn collect(reader: Option<Reader>, grace: Duration) -> Capture {
+    let Some((capture, finished)) = reader else { return Capture::default() };
+    let _ = finished.recv_timeout(grace);
+    capture.lock().map(|mut c| std::mem::take(&mut *c)).unwrap_or_default()
+}
+
+fn stderr_tail(bytes: &[u8]) -> String {
+    let start = bytes.len().saturating_sub(STDERR_TAIL_BYTES);
+    String::from_utf8_lossy(&bytes[start..]).replace("\r\n", "\n").trim().to_string()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slow_tools_time_out_and_output_is_capped() {
+        let tools = ToolExecutor::new().with_timeout(Duration::from_millis(200)).with_max_output_bytes(1000);
+
+        let slow = tools.run("sh", &["-c", "echo started; sleep 5"]);
+        assert_eq!(slow.outcome, ToolOutcome::TimedOut);
+        assert!(slow.elapsed < Duration::from_secs(3));
+
+        let loud = tools.run("sh", &["-c", "head -c 100000 /dev/zero | tr '\\0' x; echo oops >&2"]);
+        assert!(loud.success());
+        assert_eq!((loud.stdout.len(), loud.truncated), (1000, true));
+        assert!(loud.provenance().ends_with(", output capped)"));
+
+        let diagnostics = tools.diagnostics();
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[1].stderr, "oops");
+    }
+
+    #[test]
+    fn offline_mode_never_spawns() {
+        let tools = ToolExecutor::new().with_offline(true);
+        assert!(!tools.exists("sh"));
+        let run = tools.run("sh", &["-c", "echo hi"]);
+        assert_eq!((run.outcome, run.stdout.as_str()), (ToolOutcome::Skipped, ""));
+        assert_eq!(ToolExecutor::new().run("saccade-no-such-tool", &[]).outcome, ToolOutcome::NotFound);
+    }
+}
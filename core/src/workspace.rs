@@ -0,0 +1,531 @@
+// saccade/core/src/workspace.rs
+//
+// Internal package graph for monorepos, read from workspace definitions —
+// Cargo `[workspace] members`, npm/yarn `workspaces`, pnpm-workspace.yaml,
+// Go `go.work`, uv `[tool.uv.workspace]` — and from path dependencies in every
+// ecosystem (`path = "../x"`, `file:../x`, go.mod `replace … => ../x`, Poetry
+// and uv `path` sources), followed transitively.
+//
+// Rendered as the first DEPS block: each internal package with its root,
+// entry points, and the internal packages it depends on / is used by.
+
+use crate::graph::normalize;
+use crate::lockfiles::{node_requested, normalize_python_name, parse_cargo_manifest, parse_go_modules, pyproject_requirements};
+use crate::manifest_text::{
+    go_directive, go_module_path, inline_table_value, toml_entries, toml_strings, toml_value, unquote,
+};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspacePackage {
+    pub name: String,
+    pub ecosystem: &'static str,
+    /// Package dir relative to the repo root (`.` for the root package).
+    pub root: PathBuf,
+    /// Repo-relative files a reader should open first (lib/main, `bin`, `exports`, scripts).
+    pub entry_points: Vec<String>,
+    /// Names of internal packages this one depends on, sorted.
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Workspace {
+    /// Where membership came from, e.g. `Cargo.toml [workspace]`.
+    pub definitions: Vec<String>,
+    /// Sorted by ecosystem, then root.
+    pub packages: Vec<WorkspacePackage>,
+}
+
+/// One package as read from its manifest, before internal edges are resolved.
+struct Found {
+    name: String,
+    entry_points: Vec<String>,
+    /// Every declared dependency name; filtered down to internal ones later.
+    requirements: Vec<String>,
+    /// Local path dependencies, relative to the package dir.
+    path_deps: Vec<PathBuf>,
+}
+
+impl Workspace {
+    /// Read workspace definitions under `root` (normally `.`).
+    pub fn discover(root: &Path) -> Self {
+        let mut workspace = Self::default();
+        for (ecosystem, definition, seeds, read) in [
+            cargo_members(root),
+            node_members(root),
+            go_members(root),
+            python_members(root),
+        ] {
+            let packages = collect(root, ecosystem, seeds, read);
+            // A lone package is a project, not a workspace.
+            if packages.len() > 1 {
+                workspace.definitions.extend(definition);
+                workspace.packages.extend(packages);
+            }
+        }
+        workspace.packages.sort_by(|a, b| (a.ecosystem, &a.root).cmp(&(b.ecosystem, &b.root)));
+        workspace
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty()
+    }
+
+    /// Internal packages of the same ecosystem that depend on `package`.
+    pub fn used_by(&self, package: &WorkspacePackage) -> Vec<&str> {
+        self.packages
+            .iter()
+            .filter(|p| p.ecosystem == package.ecosystem && p.depends_on.contains(&package.name))
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    pub fn render(&self) -> String {
+        let edges: usize = self.packages.iter().map(|p| p.depends_on.len()).sum();
+        let mut out = format!(
+            "WORKSPACE (internal packages)\n{} packages, {} internal edge{}",
+            self.packages.len(),
+            edges,
+            if edges == 1 { "" } else { "s" }
+        );
+        if !self.definitions.is_empty() {
+            out.push_str(&format!("; from {}", self.definitions.join(", ")));
+        }
+        out.push('\n');
+        for package in &self.packages {
+            out.push_str(&format!("\n[{}] {}  ({})\n", package.ecosystem, package.name, display_dir(&package.root)));
+            if !package.entry_points.is_empty() {
+                out.push_str(&format!("  entry: {}\n", package.entry_points.join(", ")));
+            }
+            if !package.depends_on.is_empty() {
+                out.push_str(&format!("  depends on: {}\n", package.depends_on.join(", ")));
+            }
+            let used_by = self.used_by(package);
+            if !used_by.is_empty() {
+                out.push_str(&format!("  used by: {}\n", used_by.join(", ")));
+            }
+        }
+        out
+    }
+}
+
+type Reader = fn(&Path, &Path) -> Option<Found>;
+type Members = (&'static str, Option<String>, Vec<PathBuf>, Reader);
+
+/// Breadth-first over member dirs and their path dependencies, then keep only internal edges.
+fn collect(root: &Path, ecosystem: &'static str, seeds: Vec<PathBuf>, read: Reader) -> Vec<WorkspacePackage> {
+    let mut queue: VecDeque<PathBuf> = seeds.iter().filter_map(|s| within_root(s)).collect();
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut found: Vec<(PathBuf, Found)> = Vec::new();
+    while let Some(dir) = queue.pop_front() {
+        if !seen.insert(dir.clone()) {
+            continue;
+        }
+        let Some(package) = read(root, &dir) else { continue };
+        queue.extend(package.path_deps.iter().filter_map(|p| within_root(&dir.join(p))));
+        found.push((dir, package));
+    }
+
+    let names: HashSet<&str> = found.iter().map(|(_, f)| f.name.as_str()).collect();
+    found
+        .iter()
+        .map(|(dir, f)| {
+            let depends_on: BTreeSet<String> = f
+                .requirements
+                .iter()
+                .filter(|r| **r != f.name && names.contains(r.as_str()))
+                .cloned()
+                .collect();
+            WorkspacePackage {
+                name: f.name.clone(),
+                ecosystem,
+                root: if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir.clone() },
+                entry_points: f.entry_points.clone(),
+                depends_on: depends_on.into_iter().collect(),
+            }
+        })
+        .collect()
+}
+
+/// Lexically normalized `path`, or `None` if it is absolute or climbs above the root
+/// (a `path = "../sibling"` dependency lives outside the repo being packed).
+fn within_root(path: &Path) -> Option<PathBuf> {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    return None;
+                }
+            }
+            Component::Normal(part) => out.push(part),
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+    Some(out)
+}
+
+/// Drop repeated entry points, keeping the first occurrence.
+fn dedup_entry_points(entry_points: &mut Vec<String>) {
+    let mut seen = HashSet::new();
+    entry_points.retain(|e| seen.insert(e.clone()));
+}
+
+/// `core/`, or `.` for the root.
+fn display_dir(dir: &Path) -> String {
+    let dir = dir.to_string_lossy().replace('\\', "/");
+    if dir == "." { dir } else { format!("{}/", dir) }
+}
+
+/// Repo-relative display path of `file` inside package `dir`.
+fn display_file(dir: &Path, file: &str) -> String {
+    normalize(&dir.join(file)).to_string_lossy().replace('\\', "/")
+}
+
+/// Member dirs for `patterns` (globs allowed, `!pattern` excludes), relative to `root`.
+pub(crate) fn expand_members(root: &Path, patterns: &[String], excludes: &[String]) -> Vec<PathBuf> {
+    let excluded: Vec<glob::Pattern> = excludes
+        .iter()
+        .map(String::as_str)
+        .chain(patterns.iter().filter_map(|p| p.strip_prefix('!')))
+        .filter_map(|p| glob::Pattern::new(p.trim_start_matches("./").trim_end_matches('/')).ok())
+        .collect();
+    let mut dirs = Vec::new();
+    for pattern in patterns.iter().filter(|p| !p.starts_with('!')) {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/').replace("/**", "/*");
+        if !pattern.contains(['*', '?', '[']) {
+            dirs.push(PathBuf::from(&pattern));
+            continue;
+        }
+        let Ok(matches) = glob::glob(&root.join(&pattern).to_string_lossy()) else { continue };
+        dirs.extend(
+            matches
+                .filter_map(|m| m.ok())
+                .filter(|m| m.is_dir())
+                .filter_map(|m| m.strip_prefix(root).ok().map(Path::to_path_buf)),
+        );
+    }
+    dirs.retain(|d| !excluded.iter().any(|p| p.matches_path(d)));
+    dirs.sort();
+    dirs
+}
+
+// ---------------------------------------------------------------------------
+// Cargo
+// ---------------------------------------------------------------------------
+
+fn cargo_members(root: &Path) -> Members {
+    let entries = fs::read_to_string(root.join("Cargo.toml")).map(|c| toml_entries(&c)).unwrap_or_default();
+    let members = toml_strings(&entries, "workspace", "members");
+    let mut seeds = expand_members(root, &members, &toml_strings(&entries, "workspace", "exclude"));
+    if toml_value(&entries, "package", "name").is_some() {
+        seeds.insert(0, PathBuf::new());
+    }
+    let definition = (!members.is_empty()).then(|| "Cargo.toml [workspace]".to_string());
+    ("rust", definition, seeds, read_cargo_package)
+}
+
+fn read_cargo_package(root: &Path, dir: &Path) -> Option<Found> {
+    let content = fs::read_to_string(root.join(dir).join("Cargo.toml")).ok()?;
+    let entries = toml_entries(&content);
+    let name = unquote(toml_value(&entries, "package", "name")?).to_string();
+
+    let mut entry_points: Vec<String> = entries
+        .iter()
+        .filter(|(t, k, _)| (t == "lib" || t == "bin") && k == "path")
+        .map(|(_, _, v)| display_file(dir, unquote(v)))
+        .collect();
+    for file in ["src/lib.rs", "src/main.rs"] {
+        if root.join(dir).join(file).is_file() {
+            entry_points.push(display_file(dir, file));
+        }
+    }
+    if let Ok(bins) = glob::glob(&root.join(dir).join("src/bin/*.rs").to_string_lossy()) {
+        entry_points.extend(bins.filter_map(|b| b.ok()?.strip_prefix(root).ok().map(|p| display_file(p, ""))));
+    }
+    dedup_entry_points(&mut entry_points);
+
+    let dependencies = parse_cargo_manifest(&content);
+    let path_deps = dependencies.iter().filter_map(|(_, req)| req.strip_prefix("path ").map(PathBuf::from)).collect();
+    Some(Found { name, entry_points, requirements: dependencies.into_iter().map(|(n, _)| n).collect(), path_deps })
+}
+
+// ---------------------------------------------------------------------------
+// Node (npm/yarn `workspaces`, pnpm-workspace.yaml)
+// ---------------------------------------------------------------------------
+
+fn node_members(root: &Path) -> Members {
+    let manifest = fs::read_to_string(root.join("package.json")).ok().and_then(|s| serde_json::from_str::<Value>(&s).ok());
+    let mut patterns: Vec<String> = Vec::new();
+    let mut definition = None;
+    if let Some(workspaces) = manifest.as_ref().and_then(|m| m.get("workspaces")) {
+        let list = workspaces.get("packages").unwrap_or(workspaces);
+        patterns.extend(list.as_array().into_iter().flatten().filter_map(|v| v.as_str().map(str::to_string)));
+        definition = Some("package.json workspaces".to_string());
+    }
+    if let Ok(yaml) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_workspace_packages(&yaml));
+        definition = Some("pnpm-workspace.yaml".to_string());
+    }
+    let mut seeds = expand_members(root, &patterns, &[]);
+    if manifest.is_some() {
+        seeds.insert(0, PathBuf::new());
+    }
+    ("node", definition, seeds, read_node_package)
+}
+
+/// `packages:` list items of pnpm-workspace.yaml.
+fn pnpm_workspace_packages(yaml: &str) -> Vec<String> {
+    let mut in_packages = false;
+    let mut out = Vec::new();
+    for line in yaml.lines() {
+        if !line.starts_with([' ', '-']) && !line.trim().is_empty() {
+            in_packages = line.trim_end() == "packages:";
+            continue;
+        }
+        if let Some(item) = line.trim().strip_prefix('-').filter(|_| in_packages) {
+            out.push(unquote(item).to_string());
+        }
+    }
+    out
+}
+
+fn read_node_package(root: &Path, dir: &Path) -> Option<Found> {
+    let manifest: Value = serde_json::from_str(&fs::read_to_string(root.join(dir).join("package.json")).ok()?).ok()?;
+    let name = manifest.get("name")?.as_str()?.to_string();
+
+    let mut files: Vec<&str> = Vec::new();
+    for key in ["main", "module", "types"] {
+        files.extend(manifest.get(key).and_then(Value::as_str));
+    }
+    match manifest.get("bin") {
+        Some(Value::String(bin)) => files.push(bin),
+        Some(Value::Object(bins)) => files.extend(bins.values().filter_map(Value::as_str)),
+        _ => {}
+    }
+    let mut export = manifest.get("exports");
+    if let Some(Value::Object(map)) = export {
+        export = map.get(".").or(map.get("import")).or(map.get("default"));
+    }
+    if let Some(Value::Object(conditions)) = export {
+        export = conditions.get("import").or(conditions.get("default")).or(conditions.get("require"));
+    }
+    files.extend(export.and_then(Value::as_str));
+    if files.is_empty() {
+        files.extend(
+            ["index.ts", "index.js", "src/index.ts", "src/index.tsx", "src/index.js", "src/main.ts", "src/main.tsx"]
+                .into_iter()
+                .filter(|f| root.join(dir).join(f).is_file())
+                .take(1),
+        );
+    }
+    let mut entry_points: Vec<String> = files.into_iter().map(|f| display_file(dir, f)).collect();
+    dedup_entry_points(&mut entry_points);
+
+    let requested = node_requested(&manifest);
+    let path_deps = requested
+        .values()
+        .filter_map(|spec| spec.strip_prefix("file:").or_else(|| spec.strip_prefix("link:")).map(PathBuf::from))
+        .collect();
+    Some(Found { name, entry_points, requirements: requested.into_keys().collect(), path_deps })
+}
+
+// ---------------------------------------------------------------------------
+// Go (go.work, go.mod `replace … => ./local`)
+// ---------------------------------------------------------------------------
+
+fn go_members(root: &Path) -> Members {
+    let mut seeds = Vec::new();
+    let mut definition = None;
+    if let Ok(work) = fs::read_to_string(root.join("go.work")) {
+        let mut in_use = false;
+        for line in work.lines() {
+            let line = line.split("//").next().unwrap_or(line).trim();
+            let dir = if in_use {
+                if line.starts_with(')') {
+                    in_use = false;
+                    continue;
+                }
+                line
+            } else if let Some(rest) = line.strip_prefix("use") {
+                in_use = rest.trim() == "(";
+                rest.trim()
+            } else {
+                continue;
+            };
+            if !dir.is_empty() && dir != "(" {
+                seeds.push(PathBuf::from(unquote(dir)));
+            }
+        }
+        definition = Some("go.work".to_string());
+    } else if root.join("go.mod").is_file() {
+        seeds.push(PathBuf::new());
+    }
+    ("go", definition, seeds, read_go_module)
+}
+
+fn read_go_module(root: &Path, dir: &Path) -> Option<Found> {
+    let content = fs::read_to_string(root.join(dir).join("go.mod")).ok()?;
+    let name = go_module_path(&content)?.to_string();
+
+    let mut entry_points = Vec::new();
+    if root.join(dir).join("main.go").is_file() {
+        entry_points.push(display_file(dir, "main.go"));
+    }
+    if let Ok(mains) = glob::glob(&root.join(dir).join("cmd/*/main.go").to_string_lossy()) {
+        entry_points.extend(mains.filter_map(|m| m.ok()?.strip_prefix(root).ok().map(|p| display_file(p, ""))));
+    }
+
+    let path_deps = go_directive(&content, "replace")
+        .into_iter()
+        .filter_map(|(spec, _)| spec.split_once("=>")?.1.split_whitespace().next())
+        .filter(|target| target.starts_with("./") || target.starts_with("../"))
+        .map(PathBuf::from)
+        .collect();
+    let requirements = parse_go_modules(&content, None).into_iter().map(|p| p.name).collect();
+    Some(Found { name, entry_points, requirements, path_deps })
+}
+
+// ---------------------------------------------------------------------------
+// Python (uv workspaces, Poetry/uv path dependencies)
+// ---------------------------------------------------------------------------
+
+fn python_members(root: &Path) -> Members {
+    let entries = fs::read_to_string(root.join("pyproject.toml")).map(|c| toml_entries(&c)).unwrap_or_default();
+    let members = toml_strings(&entries, "tool.uv.workspace", "members");
+    let mut seeds = expand_members(root, &members, &toml_strings(&entries, "tool.uv.workspace", "exclude"));
+    if !entries.is_empty() {
+        seeds.insert(0, PathBuf::new());
+    }
+    let definition = (!members.is_empty()).then(|| "pyproject.toml [tool.uv.workspace]".to_string());
+    ("python", definition, seeds, read_python_project)
+}
+
+fn read_python_project(root: &Path, dir: &Path) -> Option<Found> {
+    let content = fs::read_to_string(root.join(dir).join("pyproject.toml")).ok()?;
+    let entries = toml_entries(&content);
+    let name = toml_value(&entries, "project", "name").or_else(|| toml_value(&entries, "tool.poetry", "name"))?;
+    let name = normalize_python_name(unquote(name));
+
+    let mut entry_points: Vec<String> = entries
+        .iter()
+        .filter(|(t, _, _)| t == "project.scripts" || t == "tool.poetry.scripts")
+        .map(|(_, script, target)| format!("{} = {}", script, unquote(target)))
+        .collect();
+    let module = name.replace('-', "_");
+    for candidate in [format!("src/{}/__init__.py", module), format!("{}/__init__.py", module)] {
+        if root.join(dir).join(&candidate).is_file() {
+            entry_points.push(display_file(dir, &candidate));
+            break;
+        }
+    }
+
+    // `x = { path = "../x" }` in `[tool.uv.sources]` or any Poetry dependency table.
+    let path_deps = entries
+        .iter()
+        .filter(|(t, _, _)| t == "tool.uv.sources" || (t.starts_with("tool.poetry") && t.ends_with("dependencies")))
+        .filter_map(|(_, _, v)| inline_table_value(v, "path").map(PathBuf::from))
+        .collect();
+    let requirements = pyproject_requirements(&content).into_iter().map(|(n, _)| n).collect();
+    Some(Found { name, entry_points, requirements, path_deps })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, files: &[(&str, &str)]) {
+        for (path, content) in files {
+            fs::create_dir_all(root.join(path).parent().unwrap()).unwrap();
+            fs::write(root.join(path), content).unwrap();
+        }
+    }
+
+    #[test]
+    fn cargo_and_node_workspaces_resolve_internal_edges() {
+        let tmp = TempDir::new().unwrap();
+        write(
+            tmp.path(),
+            &[
+                ("Cargo.toml", "[workspace]\nmembers = [\n  \"crates/*\",\n  \"cli\",\n]\nexclude = [\"crates/scratch\"]\n"),
+                ("crates/core/Cargo.toml", "[package]\nname = \"app-core\"\n\n[dependencies]\nserde = \"1\"\n"),
+                ("crates/core/src/lib.rs", ""),
+                ("crates/scratch/Cargo.toml", "[package]\nname = \"scratch\"\n"),
+                ("cli/Cargo.toml", "[package]\nname = \"app\"\n\n[dependencies]\napp-core = { path = \"../crates/core\" }\nanyhow = \"1\"\n"),
+                ("cli/src/main.rs", ""),
+                ("package.json", r#"{"name": "root", "private": true, "workspaces": ["packages/*"]}"#),
+                ("packages/ui/package.json", r#"{"name": "@acme/ui", "main": "dist/index.js", "dependencies": {"react": "^18"}}"#),
+                ("packages/web/package.json", r#"{"name": "@acme/web", "dependencies": {"@acme/ui": "workspace:*"}}"#),
+                ("packages/web/src/index.tsx", ""),
+            ],
+        );
+        let workspace = Workspace::discover(tmp.path());
+        let summary: Vec<(&str, &str, String, &[String])> = workspace
+            .packages
+            .iter()
+            .map(|p| (p.ecosystem, p.name.as_str(), display_dir(&p.root), p.depends_on.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("node", "root", ".".to_string(), &[][..]),
+                ("node", "@acme/ui", "packages/ui/".to_string(), &[][..]),
+                ("node", "@acme/web", "packages/web/".to_string(), &["@acme/ui".to_string()][..]),
+                ("rust", "app", "cli/".to_string(), &["app-core".to_string()][..]),
+                ("rust", "app-core", "crates/core/".to_string(), &[][..]),
+            ]
+        );
+        let rendered = workspace.render();
+        assert!(rendered.contains("[rust] app-core  (crates/core/)\n  entry: crates/core/src/lib.rs\n  used by: app\n"));
+        assert!(rendered.contains("[node] @acme/web  (packages/web/)\n  entry: packages/web/src/index.tsx\n  depends on: @acme/ui\n"));
+    }
+
+    #[test]
+    fn go_work_and_python_path_dependencies() {
+        let tmp = TempDir::new().unwrap();
+        write(
+            tmp.path(),
+            &[
+                ("go.work", "go 1.22\n\nuse (\n\t./api\n\t./lib\n)\n"),
+                ("api/go.mod", "module example.com/api\n\nrequire example.com/lib v0.0.0\n\nreplace example.com/lib => ../lib\n"),
+                ("api/cmd/server/main.go", "package main\n"),
+                ("lib/go.mod", "module example.com/lib\n"),
+                ("pyproject.toml", "[tool.poetry]\nname = \"service\"\n\n[tool.poetry.dependencies]\npython = \"^3.11\"\nshared_utils = { path = \"libs/shared\", develop = true }\n\n[tool.poetry.scripts]\nserve = \"service.main:run\"\n"),
+                ("libs/shared/pyproject.toml", "[project]\nname = \"shared-utils\"\n"),
+                ("libs/shared/src/shared_utils/__init__.py", ""),
+            ],
+        );
+        let workspace = Workspace::discover(tmp.path());
+        let find = |name: &str| workspace.packages.iter().find(|p| p.name == name).unwrap();
+
+        assert_eq!(find("example.com/api").depends_on, ["example.com/lib"]);
+        assert_eq!(find("example.com/api").entry_points, ["api/cmd/server/main.go"]);
+        assert_eq!(find("service").depends_on, ["shared-utils"]);
+        assert_eq!(find("service").entry_points, ["serve = service.main:run"]);
+        assert_eq!(find("shared-utils").entry_points, ["libs/shared/src/shared_utils/__init__.py"]);
+        assert_eq!(workspace.definitions, ["go.work"]);
+    }
+
+    #[test]
+    fn path_dependencies_outside_the_root_are_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        write(
+            tmp.path(),
+            &[
+                ("sibling/Cargo.toml", "[package]\nname = \"outside\"\n"),
+                ("repo/sibling/Cargo.toml", "[package]\nname = \"inside\"\n"),
+                ("repo/Cargo.toml", "[package]\nname = \"app\"\n\n[dependencies]\noutside = { path = \"../sibling\" }\n"),
+            ],
+        );
+        assert!(Workspace::discover(&repo).is_empty());
+        assert_eq!(within_root(Path::new("tools/..")), Some(PathBuf::new()));
+        assert_eq!(within_root(Path::new("tools/../../x")), None);
+        assert_eq!(within_root(Path::new("/etc")), None);
+    }
+}